@@ -323,6 +323,22 @@ ErrorCode anoncreds_create_revocation_registry_def(ObjectHandle cred_def,
                                                    ObjectHandle *reg_def_p,
                                                    ObjectHandle *reg_def_private_p);
 
+/**
+ * Same as `anoncreds_create_revocation_registry_def`, with the tails location of the
+ * definition set to `tails_location_template` with `{hash}` replaced by the tails hash,
+ * instead of the path of the tails file written under `tails_dir_path`.
+ */
+ErrorCode anoncreds_create_revocation_registry_def_with_tails_location(ObjectHandle cred_def,
+                                                                       FfiStr cred_def_id,
+                                                                       FfiStr _issuer_id,
+                                                                       FfiStr tag,
+                                                                       FfiStr rev_reg_type,
+                                                                       int64_t max_cred_num,
+                                                                       FfiStr tails_dir_path,
+                                                                       FfiStr tails_location_template,
+                                                                       ObjectHandle *reg_def_p,
+                                                                       ObjectHandle *reg_def_private_p);
+
 ErrorCode anoncreds_create_revocation_status_list(ObjectHandle cred_def,
                                                   FfiStr rev_reg_def_id,
                                                   ObjectHandle rev_reg_def,
@@ -546,6 +562,37 @@ ErrorCode anoncreds_verify_presentation(ObjectHandle presentation,
                                         struct FfiList_FfiNonrevokedIntervalOverride nonrevoked_interval_override,
                                         int8_t *result_p);
 
+/**
+ * Verify Presentation and return a detailed verification report
+ *
+ * # Params
+ * presentation:                   object handle pointing to presentation
+ * pres_req:                       object handle pointing to presentation request
+ * schemas:                        list of credential schemas
+ * schema_ids:                     list of schemas ids
+ * cred_defs:                      list of credential definitions
+ * cred_def_ids:                   list of credential definitions ids
+ * rev_reg_defs:                   list of revocation definitions
+ * rev_reg_def_ids:                list of revocation definitions ids
+ * rev_status_list:                revocation status list
+ * nonrevoked_interval_override:   not-revoked interval
+ * report_p:                       reference that will contain the verification report as JSON.
+ *
+ * # Returns
+ * Error code
+ */
+ErrorCode anoncreds_verify_presentation_detailed(ObjectHandle presentation,
+                                                 ObjectHandle pres_req,
+                                                 struct FfiList_ObjectHandle schemas,
+                                                 FfiStrList schema_ids,
+                                                 struct FfiList_ObjectHandle cred_defs,
+                                                 FfiStrList cred_def_ids,
+                                                 struct FfiList_ObjectHandle rev_reg_defs,
+                                                 FfiStrList rev_reg_def_ids,
+                                                 struct FfiList_ObjectHandle rev_status_list,
+                                                 struct FfiList_FfiNonrevokedIntervalOverride nonrevoked_interval_override,
+                                                 struct ByteBuffer *report_p);
+
 /**
  * Verity W3C styled Presentation
  *
@@ -577,6 +624,37 @@ ErrorCode anoncreds_verify_w3c_presentation(ObjectHandle presentation,
                                             struct FfiList_FfiNonrevokedIntervalOverride nonrevoked_interval_override,
                                             int8_t *result_p);
 
+/**
+ * Verify W3C styled Presentation and return a detailed verification report
+ *
+ * # Params
+ * presentation:                   object handle pointing to presentation
+ * pres_req:                       object handle pointing to presentation request
+ * schemas:                        list of credential schemas
+ * schema_ids:                     list of schemas ids
+ * cred_defs:                      list of credential definitions
+ * cred_def_ids:                   list of credential definitions ids
+ * rev_reg_defs:                   list of revocation definitions
+ * rev_reg_def_ids:                list of revocation definitions ids
+ * rev_status_list:                revocation status list
+ * nonrevoked_interval_override:   not-revoked interval
+ * report_p:                       reference that will contain the verification report as JSON.
+ *
+ * # Returns
+ * Error code
+ */
+ErrorCode anoncreds_verify_w3c_presentation_detailed(ObjectHandle presentation,
+                                                     ObjectHandle pres_req,
+                                                     struct FfiList_ObjectHandle schemas,
+                                                     FfiStrList schema_ids,
+                                                     struct FfiList_ObjectHandle cred_defs,
+                                                     FfiStrList cred_def_ids,
+                                                     struct FfiList_ObjectHandle rev_reg_defs,
+                                                     FfiStrList rev_reg_def_ids,
+                                                     struct FfiList_ObjectHandle rev_status_list,
+                                                     struct FfiList_FfiNonrevokedIntervalOverride nonrevoked_interval_override,
+                                                     struct ByteBuffer *report_p);
+
 char *anoncreds_version(void);
 
 ErrorCode anoncreds_w3c_credential_from_json(struct ByteBuffer json, ObjectHandle *result_p);
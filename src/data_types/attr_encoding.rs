@@ -0,0 +1,240 @@
+use std::fmt;
+
+use super::date::CalendarDate;
+
+use crate::Result;
+use crate::error::ValidationError;
+use crate::utils::validation::Validatable;
+
+/// Largest fixed-point scale accepted for decimal attributes.
+pub const MAX_DECIMAL_SCALE: u8 = 18;
+
+/// Typed encoding declared for a credential attribute.
+///
+/// Attributes without a declared encoding use the legacy scheme: values which
+/// parse as an `i32` are kept as-is and everything else is SHA-256 hashed.
+/// A typed encoding maps the raw value onto an integer instead, so that the
+/// encoded value can be decoded back into its canonical raw form and used in
+/// predicates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AttributeEncoding {
+    /// Signed or unsigned 64-bit integer, encoded as its decimal representation
+    Integer,
+    /// Fixed-point decimal with `scale` fractional digits, encoded as the
+    /// integer `value * 10^scale`
+    Decimal { scale: u8 },
+    /// ISO 8601 calendar date (`YYYY-MM-DD`), encoded as the number of days
+    /// since the common era (0001-01-01 is day 1)
+    Date,
+    /// Boolean (`true` or `false`), encoded as `1` or `0`
+    Boolean,
+}
+
+impl AttributeEncoding {
+    /// Encode a raw attribute value.
+    pub fn encode(&self, raw: &str) -> Result<String> {
+        match self {
+            Self::Integer => parse_integer(raw).map(|value| value.to_string()),
+            Self::Decimal { scale } => encode_decimal(raw, *scale).map(|value| value.to_string()),
+            Self::Date => raw
+                .parse::<CalendarDate>()
+                .map(|date| date.days_from_ce().to_string()),
+            Self::Boolean => match raw {
+                "true" => Ok("1".to_string()),
+                "false" => Ok("0".to_string()),
+                _ => Err(err_msg!("Invalid boolean attribute value: {}", raw)),
+            },
+        }
+    }
+
+    /// Decode an encoded attribute value back into its canonical raw form.
+    pub fn decode(&self, encoded: &str) -> Result<String> {
+        match self {
+            Self::Integer => parse_integer(encoded).map(|value| value.to_string()),
+            Self::Decimal { scale } => {
+                let value: i64 = encoded
+                    .parse()
+                    .map_err(|_| err_msg!("Invalid encoded decimal value: {}", encoded))?;
+                decode_decimal(value, *scale)
+            }
+            Self::Date => {
+                let days: i64 = encoded
+                    .parse()
+                    .map_err(|_| err_msg!("Invalid encoded date value: {}", encoded))?;
+                CalendarDate::from_days_from_ce(days).map(|date| date.to_string())
+            }
+            Self::Boolean => match encoded {
+                "1" => Ok("true".to_string()),
+                "0" => Ok("false".to_string()),
+                _ => Err(err_msg!("Invalid encoded boolean value: {}", encoded)),
+            },
+        }
+    }
+}
+
+impl Validatable for AttributeEncoding {
+    fn validate(&self) -> std::result::Result<(), ValidationError> {
+        if let Self::Decimal { scale } = self {
+            if *scale > MAX_DECIMAL_SCALE {
+                return Err(format!(
+                    "Decimal scale {scale} cannot be greater than {MAX_DECIMAL_SCALE}"
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AttributeEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer => f.write_str("integer"),
+            Self::Decimal { scale } => write!(f, "decimal({scale})"),
+            Self::Date => f.write_str("date"),
+            Self::Boolean => f.write_str("boolean"),
+        }
+    }
+}
+
+/// Parse a decimal integer within the combined `i64` and `u64` range.
+fn parse_integer(value: &str) -> Result<i128> {
+    let parsed: i128 = value
+        .parse()
+        .map_err(|_| err_msg!("Invalid integer attribute value: {}", value))?;
+    if parsed < i128::from(i64::MIN) || parsed > i128::from(u64::MAX) {
+        return Err(err_msg!("Integer attribute value out of range: {}", value));
+    }
+    Ok(parsed)
+}
+
+fn encode_decimal(raw: &str, scale: u8) -> Result<i64> {
+    let invalid = || err_msg!("Invalid decimal attribute value: {}", raw);
+
+    let (negative, unsigned) = match raw.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, raw),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if int_part.is_empty()
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+        || (unsigned.contains('.') && frac_part.is_empty())
+    {
+        return Err(invalid());
+    }
+    if frac_part.len() > usize::from(scale) {
+        return Err(err_msg!(
+            "Decimal attribute value {} has more than {} fractional digits",
+            raw,
+            scale
+        ));
+    }
+
+    let digits = format!("{int_part}{frac_part:0<width$}", width = usize::from(scale));
+    let magnitude: i128 = digits.parse().map_err(|_| invalid())?;
+    let value = if negative { -magnitude } else { magnitude };
+    i64::try_from(value).map_err(|_| err_msg!("Decimal attribute value out of range: {}", raw))
+}
+
+fn decode_decimal(value: i64, scale: u8) -> Result<String> {
+    if scale == 0 {
+        return Ok(value.to_string());
+    }
+    let scale = usize::from(scale);
+    let digits = format!("{:0>width$}", value.unsigned_abs(), width = scale + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+    let sign = if value < 0 { "-" } else { "" };
+    Ok(format!("{sign}{int_part}.{frac_part}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_round_trip() {
+        let encoding = AttributeEncoding::Integer;
+        for raw in [
+            "0",
+            "-1",
+            "9223372036854775807",
+            "-9223372036854775808",
+            "18446744073709551615",
+        ] {
+            let encoded = encoding.encode(raw).unwrap();
+            assert_eq!(encoded, raw);
+            assert_eq!(encoding.decode(&encoded).unwrap(), raw);
+        }
+        assert!(encoding.encode("18446744073709551616").is_err());
+        assert!(encoding.encode("1.5").is_err());
+        assert!(encoding.encode("abc").is_err());
+    }
+
+    #[test]
+    fn decimal_round_trip() {
+        let encoding = AttributeEncoding::Decimal { scale: 2 };
+        assert_eq!(encoding.encode("12.34").unwrap(), "1234");
+        assert_eq!(encoding.encode("12.3").unwrap(), "1230");
+        assert_eq!(encoding.encode("12").unwrap(), "1200");
+        assert_eq!(encoding.encode("-0.05").unwrap(), "-5");
+        assert_eq!(encoding.decode("1234").unwrap(), "12.34");
+        assert_eq!(encoding.decode("1200").unwrap(), "12.00");
+        assert_eq!(encoding.decode("-5").unwrap(), "-0.05");
+
+        assert!(encoding.encode("12.345").is_err());
+        assert!(encoding.encode("12.").is_err());
+        assert!(encoding.encode(".5").is_err());
+        assert!(encoding.encode("1e3").is_err());
+        assert!(AttributeEncoding::Decimal { scale: 19 }.validate().is_err());
+    }
+
+    #[test]
+    fn date_round_trip() {
+        let encoding = AttributeEncoding::Date;
+        assert_eq!(encoding.encode("0001-01-01").unwrap(), "1");
+        assert_eq!(encoding.encode("1970-01-01").unwrap(), "719163");
+        assert_eq!(encoding.encode("2000-02-29").unwrap(), "730179");
+        for raw in [
+            "0001-01-01",
+            "1970-01-01",
+            "2000-02-29",
+            "2024-12-31",
+            "9999-12-31",
+        ] {
+            let encoded = encoding.encode(raw).unwrap();
+            assert_eq!(encoding.decode(&encoded).unwrap(), raw);
+        }
+        // later dates compare greater, so predicates can be used on them
+        let earlier: i32 = encoding.encode("1999-12-31").unwrap().parse().unwrap();
+        let later: i32 = encoding.encode("2000-01-01").unwrap().parse().unwrap();
+        assert_eq!(later - earlier, 1);
+
+        assert!(encoding.encode("1900-02-29").is_err());
+        assert!(encoding.encode("2024-13-01").is_err());
+        assert!(encoding.encode("2024-1-01").is_err());
+        assert!(encoding.encode("0000-01-01").is_err());
+        assert!(encoding.decode("0").is_err());
+    }
+
+    #[test]
+    fn boolean_round_trip() {
+        let encoding = AttributeEncoding::Boolean;
+        assert_eq!(encoding.encode("true").unwrap(), "1");
+        assert_eq!(encoding.encode("false").unwrap(), "0");
+        assert_eq!(encoding.decode("1").unwrap(), "true");
+        assert_eq!(encoding.decode("0").unwrap(), "false");
+        assert!(encoding.encode("yes").is_err());
+        assert!(encoding.decode("2").is_err());
+    }
+
+    #[test]
+    fn serializes_with_type_tag() {
+        let encoding = AttributeEncoding::Decimal { scale: 2 };
+        let json = serde_json::to_value(encoding).unwrap();
+        assert_eq!(json, json!({"type": "decimal", "scale": 2}));
+        let encoding: AttributeEncoding = serde_json::from_value(json!({"type": "date"})).unwrap();
+        assert_eq!(encoding, AttributeEncoding::Date);
+    }
+}
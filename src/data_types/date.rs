@@ -0,0 +1,309 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Error, Result};
+
+/// Days between 0001-01-01 (day 1) and the Unix epoch.
+const UNIX_EPOCH_DAYS_FROM_CE: i64 = 719_163;
+
+/// Days from the common era of 9999-12-31.
+const MAX_DAYS_FROM_CE: i64 = 3_652_059;
+
+/// Proleptic Gregorian calendar date between 0001-01-01 and 9999-12-31.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl CalendarDate {
+    pub fn new(year: u32, month: u32, day: u32) -> Result<Self> {
+        if !(1..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+        {
+            return Err(err_msg!(
+                "Invalid calendar date: {:04}-{:02}-{:02}",
+                year,
+                month,
+                day
+            ));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// The current date in UTC.
+    pub fn today() -> Result<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| err_msg!("Invalid system time: {}", err))?;
+        Self::from_unix_timestamp(now.as_secs())
+    }
+
+    /// The UTC date of a Unix timestamp.
+    pub fn from_unix_timestamp(timestamp: u64) -> Result<Self> {
+        let days = i64::try_from(timestamp / 86_400)
+            .map_err(|_| err_msg!("Timestamp out of range: {}", timestamp))?;
+        Self::from_days_from_ce(days + UNIX_EPOCH_DAYS_FROM_CE)
+    }
+
+    #[must_use]
+    pub const fn year(&self) -> u32 {
+        self.year
+    }
+
+    #[must_use]
+    pub const fn month(&self) -> u32 {
+        self.month
+    }
+
+    #[must_use]
+    pub const fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Number of days since the common era, 0001-01-01 being day 1.
+    #[must_use]
+    pub fn days_from_ce(&self) -> i64 {
+        days_from_civil(i64::from(self.year), self.month, self.day) + UNIX_EPOCH_DAYS_FROM_CE
+    }
+
+    pub fn from_days_from_ce(days: i64) -> Result<Self> {
+        if !(1..=MAX_DAYS_FROM_CE).contains(&days) {
+            return Err(err_msg!("Date out of range: {} days from CE", days));
+        }
+        let (year, month, day) = civil_from_days(days - UNIX_EPOCH_DAYS_FROM_CE);
+        // the range check above keeps all components within u32
+        Self::new(year as u32, month as u32, day as u32)
+    }
+
+    /// The date as a `YYYYMMDD` integer.
+    #[must_use]
+    pub fn yyyymmdd(&self) -> i64 {
+        i64::from(self.year) * 10_000 + i64::from(self.month) * 100 + i64::from(self.day)
+    }
+
+    pub fn from_yyyymmdd(value: i64) -> Result<Self> {
+        let invalid = || err_msg!("Invalid YYYYMMDD date: {}", value);
+        if !(10_101..=99_991_231).contains(&value) {
+            return Err(invalid());
+        }
+        // the range check above keeps all components within u32
+        Self::new(
+            (value / 10_000) as u32,
+            (value / 100 % 100) as u32,
+            (value % 100) as u32,
+        )
+        .map_err(|_| invalid())
+    }
+
+    /// Add a calendar offset to the date.
+    ///
+    /// Offsets in years or months keep the day of the month, clamping it to the
+    /// last day of the resulting month, so that 2024-02-29 minus one year is 2023-02-28.
+    pub fn checked_add(&self, offset: DateOffset) -> Result<Self> {
+        let out_of_range = || err_msg!("Date {} plus {:?} is out of range", self, offset);
+        match offset {
+            DateOffset::Days(days) => self
+                .days_from_ce()
+                .checked_add(days)
+                .ok_or_else(out_of_range)
+                .and_then(Self::from_days_from_ce),
+            DateOffset::Months(months) => {
+                let month_index = i64::from(self.year) * 12 + i64::from(self.month) - 1;
+                let month_index = month_index
+                    .checked_add(i64::from(months))
+                    .ok_or_else(out_of_range)?;
+                let year = u32::try_from(month_index.div_euclid(12)).map_err(|_| out_of_range())?;
+                // rem_euclid(12) is always within 0..12
+                let month = month_index.rem_euclid(12) as u32 + 1;
+                if !(1..=9999).contains(&year) {
+                    return Err(out_of_range());
+                }
+                Self::new(year, month, self.day.min(days_in_month(year, month)))
+            }
+            DateOffset::Years(years) => self
+                .checked_add(DateOffset::Months(
+                    years.checked_mul(12).ok_or_else(out_of_range)?,
+                ))
+                .map_err(|_| out_of_range()),
+        }
+    }
+}
+
+impl FromStr for CalendarDate {
+    type Err = Error;
+
+    /// Parse an ISO 8601 calendar date (`YYYY-MM-DD`).
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || err_msg!("Invalid date: {}", value);
+
+        let mut parts = value.split('-');
+        let (Some(year), Some(month), Some(day), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+        let parse = |s: &str| -> Result<u32> {
+            if s.bytes().all(|b| b.is_ascii_digit()) {
+                s.parse().map_err(|_| invalid())
+            } else {
+                Err(invalid())
+            }
+        };
+        Self::new(parse(year)?, parse(month)?, parse(day)?).map_err(|_| invalid())
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Calendar offset applied to a [`CalendarDate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOffset {
+    Years(i32),
+    Months(i32),
+    Days(i64),
+}
+
+/// Integer representation of a date attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateEncoding {
+    /// `YYYYMMDD`, as commonly used for raw date-of-birth attributes
+    YearMonthDay,
+    /// Days from the common era, as produced by the typed
+    /// [`AttributeEncoding::Date`](super::attr_encoding::AttributeEncoding::Date) encoding
+    DaysFromCe,
+}
+
+impl DateEncoding {
+    #[must_use]
+    pub fn encode(&self, date: &CalendarDate) -> i64 {
+        match self {
+            Self::YearMonthDay => date.yyyymmdd(),
+            Self::DaysFromCe => date.days_from_ce(),
+        }
+    }
+
+    pub fn decode(&self, value: i64) -> Result<CalendarDate> {
+        match self {
+            Self::YearMonthDay => CalendarDate::from_yyyymmdd(value),
+            Self::DaysFromCe => CalendarDate::from_days_from_ce(value),
+        }
+    }
+}
+
+const fn is_leap_year(year: u32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+const fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian date for a number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> CalendarDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_formats_iso_dates() {
+        assert_eq!(date("2024-02-29").to_string(), "2024-02-29");
+        assert!("2023-02-29".parse::<CalendarDate>().is_err());
+        assert!("2024-2-01".parse::<CalendarDate>().is_err());
+        assert!("0000-01-01".parse::<CalendarDate>().is_err());
+    }
+
+    #[test]
+    fn converts_between_encodings() {
+        let epoch = date("1970-01-01");
+        assert_eq!(epoch.days_from_ce(), 719_163);
+        assert_eq!(epoch.yyyymmdd(), 19_700_101);
+        assert_eq!(CalendarDate::from_unix_timestamp(86_399).unwrap(), epoch);
+        for encoding in [DateEncoding::YearMonthDay, DateEncoding::DaysFromCe] {
+            for value in ["0001-01-01", "2000-02-29", "9999-12-31"] {
+                let d = date(value);
+                assert_eq!(encoding.decode(encoding.encode(&d)).unwrap(), d);
+            }
+        }
+        assert!(CalendarDate::from_yyyymmdd(20_230_229).is_err());
+    }
+
+    #[test]
+    fn adds_calendar_offsets() {
+        let leap_day = date("2024-02-29");
+        assert_eq!(
+            leap_day.checked_add(DateOffset::Years(-1)).unwrap(),
+            date("2023-02-28")
+        );
+        assert_eq!(
+            leap_day.checked_add(DateOffset::Years(4)).unwrap(),
+            date("2028-02-29")
+        );
+        assert_eq!(
+            date("2024-01-31")
+                .checked_add(DateOffset::Months(1))
+                .unwrap(),
+            date("2024-02-29")
+        );
+        assert_eq!(
+            date("2024-01-15")
+                .checked_add(DateOffset::Months(-13))
+                .unwrap(),
+            date("2022-12-15")
+        );
+        assert_eq!(
+            date("2023-12-31").checked_add(DateOffset::Days(1)).unwrap(),
+            date("2024-01-01")
+        );
+        assert!(
+            date("0001-01-01")
+                .checked_add(DateOffset::Days(-1))
+                .is_err()
+        );
+    }
+}
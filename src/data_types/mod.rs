@@ -1,3 +1,9 @@
+/// Typed attribute encodings
+pub mod attr_encoding;
+
+/// Calendar dates for date attributes and predicates
+pub mod date;
+
 /// Credential definitions
 pub mod cred_def;
 
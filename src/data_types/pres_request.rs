@@ -1,14 +1,17 @@
 use anoncreds_clsignatures::PredicateType;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
 use serde_json::Value;
 
 use super::credential::Credential;
+use super::date::{CalendarDate, DateEncoding, DateOffset};
 use super::nonce::Nonce;
 use crate::error::ValidationError;
 use crate::invalid;
+use crate::services::helpers::attr_common_view;
+use crate::services::verifier::is_known_restriction_tag;
 use crate::utils::{
     query::Query,
     validation::{self, Validatable},
@@ -23,7 +26,60 @@ pub struct PresentationRequestPayload {
     pub requested_attributes: HashMap<String, AttributeInfo>,
     #[serde(default)]
     pub requested_predicates: HashMap<String, PredicateInfo>,
+    /// Range predicates, each expanded into a pair of `requested_predicates`
+    /// which must be proven by the same credential
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub requested_ranges: HashMap<String, RangeInfo>,
     pub non_revoked: Option<NonRevokedInterval>,
+    /// Identifier of the verifier, mixed into the nonce of the presentation proof so
+    /// that a presentation can not be replayed to another verifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifier_domain: Option<String>,
+}
+
+impl PresentationRequestPayload {
+    /// Add the lower and upper bound predicates of every requested range to
+    /// `requested_predicates`.
+    ///
+    /// Ranges are expanded in the serialized request as well, so implementations
+    /// unaware of ranges still prove both bounds.
+    pub fn expand_ranges(&mut self) -> Result<(), ValidationError> {
+        for (referent, range) in &self.requested_ranges {
+            range.validate()?;
+            let (lower_referent, upper_referent) = RangeInfo::predicate_referents(referent);
+            for (predicate_referent, predicate) in [
+                (lower_referent, range.lower_predicate()),
+                (upper_referent, range.upper_predicate()),
+            ] {
+                match self.requested_predicates.get(&predicate_referent) {
+                    Some(existing) if *existing != predicate => {
+                        return Err(invalid!(
+                            "Requested predicate \"{}\" does not match requested range \"{}\"",
+                            predicate_referent,
+                            referent
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.requested_predicates
+                            .insert(predicate_referent, predicate);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the range which a requested predicate is a bound of.
+    #[must_use]
+    pub fn range_for_predicate(&self, predicate_referent: &str) -> Option<(&str, &RangeInfo)> {
+        let range_referent = predicate_referent
+            .strip_suffix(RangeInfo::LOWER_SUFFIX)
+            .or_else(|| predicate_referent.strip_suffix(RangeInfo::UPPER_SUFFIX))?;
+        self.requested_ranges
+            .get_key_value(range_referent)
+            .map(|(referent, range)| (referent.as_str(), range))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -72,25 +128,29 @@ impl<'de> Deserialize<'de> for PresentationRequest {
         let req = if let Some(version) = helper.ver {
             match version.as_ref() {
                 "1.0" => {
-                    let request =
-                        PresentationRequestPayload::deserialize(v).map_err(de::Error::custom)?;
+                    let request = deserialize_payload(v)?;
                     Self::PresentationRequestV1(request)
                 }
                 "2.0" => {
-                    let request =
-                        PresentationRequestPayload::deserialize(v).map_err(de::Error::custom)?;
+                    let request = deserialize_payload(v)?;
                     Self::PresentationRequestV2(request)
                 }
                 _ => return Err(de::Error::unknown_variant(&version, &["2.0"])),
             }
         } else {
-            let request = PresentationRequestPayload::deserialize(v).map_err(de::Error::custom)?;
+            let request = deserialize_payload(v)?;
             Self::PresentationRequestV1(request)
         };
         Ok(req)
     }
 }
 
+fn deserialize_payload<E: de::Error>(value: Value) -> Result<PresentationRequestPayload, E> {
+    let mut request = PresentationRequestPayload::deserialize(value).map_err(E::custom)?;
+    request.expand_ranges().map_err(E::custom)?;
+    Ok(request)
+}
+
 impl Serialize for PresentationRequest {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -186,17 +246,139 @@ pub struct AttributeInfo {
     pub non_revoked: Option<NonRevokedInterval>,
 }
 
+impl AttributeInfo {
+    /// Request a single attribute, without restrictions
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            names: None,
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+
+    /// Request a group of attributes which must be revealed from the same credential
+    #[must_use]
+    pub fn group<N: Into<String>>(names: impl IntoIterator<Item = N>) -> Self {
+        Self {
+            name: None,
+            names: Some(names.into_iter().map(Into::into).collect()),
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+}
+
+/// Bound of a requested predicate.
+///
+/// CL predicate proofs operate on 32-bit attribute values, so predicate bounds are
+/// limited to the `i32` range. Requests with a bound outside of it are rejected when
+/// they are parsed.
 pub type PredicateValue = i32;
 
+fn deserialize_predicate_value<'de, D>(deserializer: D) -> Result<PredicateValue, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = i64::deserialize(deserializer)?;
+    PredicateValue::try_from(value).map_err(|_| {
+        de::Error::custom(format!(
+            "Predicate value {value} is outside the 32-bit range supported by predicate proofs"
+        ))
+    })
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct PredicateInfo {
     pub name: String,
     pub p_type: PredicateTypes,
+    #[serde(deserialize_with = "deserialize_predicate_value")]
     pub p_value: PredicateValue,
     pub restrictions: Option<Query>,
     pub non_revoked: Option<NonRevokedInterval>,
 }
 
+impl PredicateInfo {
+    #[must_use]
+    pub fn new(name: impl Into<String>, p_type: PredicateTypes, p_value: PredicateValue) -> Self {
+        Self {
+            name: name.into(),
+            p_type,
+            p_value,
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+
+    /// Build a predicate comparing a date attribute with a date relative to `reference`.
+    ///
+    /// The bound is `reference` plus `offset`, encoded with `encoding`, and the attribute
+    /// is compared against it with `p_type`.
+    pub fn for_relative_date(
+        name: impl Into<String>,
+        p_type: PredicateTypes,
+        reference: CalendarDate,
+        offset: DateOffset,
+        encoding: DateEncoding,
+    ) -> crate::Result<Self> {
+        let bound = reference.checked_add(offset)?;
+        let p_value = PredicateValue::try_from(encoding.encode(&bound)).map_err(|_| {
+            err_msg!(
+                "Date {} is outside the 32-bit range supported by predicate proofs",
+                bound
+            )
+        })?;
+        Ok(Self::new(name, p_type, p_value))
+    }
+
+    /// Build an "age of at least `years` on `reference`" predicate over a date of birth.
+    ///
+    /// Holders born on February 29th reach the age on March 1st in non-leap years.
+    pub fn for_minimum_age(
+        name: impl Into<String>,
+        years: u32,
+        reference: CalendarDate,
+        encoding: DateEncoding,
+    ) -> crate::Result<Self> {
+        let years = i32::try_from(years).map_err(|_| err_msg!("Invalid age: {}", years))?;
+        Self::for_relative_date(
+            name,
+            PredicateTypes::LE,
+            reference,
+            DateOffset::Years(-years),
+            encoding,
+        )
+    }
+
+    /// Build a "later than `reference`" predicate, such as an expiry date after today.
+    pub fn for_date_after(
+        name: impl Into<String>,
+        reference: CalendarDate,
+        encoding: DateEncoding,
+    ) -> crate::Result<Self> {
+        Self::for_relative_date(
+            name,
+            PredicateTypes::GT,
+            reference,
+            DateOffset::Days(0),
+            encoding,
+        )
+    }
+
+    /// Check whether an encoded attribute value satisfies the predicate.
+    #[must_use]
+    pub fn is_satisfied_by(&self, value: i64) -> bool {
+        let bound = i64::from(self.p_value);
+        match self.p_type {
+            PredicateTypes::GE => value >= bound,
+            PredicateTypes::GT => value > bound,
+            PredicateTypes::LE => value <= bound,
+            PredicateTypes::LT => value < bound,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum PredicateTypes {
     #[serde(rename = ">=")]
@@ -231,6 +413,79 @@ impl From<PredicateTypes> for PredicateType {
     }
 }
 
+/// Range predicate `lower <= value < upper` over a single attribute.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RangeInfo {
+    pub name: String,
+    /// Inclusive lower bound
+    #[serde(deserialize_with = "deserialize_predicate_value")]
+    pub lower: PredicateValue,
+    /// Exclusive upper bound
+    #[serde(deserialize_with = "deserialize_predicate_value")]
+    pub upper: PredicateValue,
+    pub restrictions: Option<Query>,
+    pub non_revoked: Option<NonRevokedInterval>,
+}
+
+impl RangeInfo {
+    const LOWER_SUFFIX: &'static str = "::lower";
+    const UPPER_SUFFIX: &'static str = "::upper";
+
+    #[must_use]
+    pub fn new(name: impl Into<String>, lower: PredicateValue, upper: PredicateValue) -> Self {
+        Self {
+            name: name.into(),
+            lower,
+            upper,
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+
+    /// Referents of the lower and upper bound predicates of a requested range.
+    #[must_use]
+    pub fn predicate_referents(range_referent: &str) -> (String, String) {
+        (
+            format!("{range_referent}{}", Self::LOWER_SUFFIX),
+            format!("{range_referent}{}", Self::UPPER_SUFFIX),
+        )
+    }
+
+    #[must_use]
+    pub fn lower_predicate(&self) -> PredicateInfo {
+        self.predicate(PredicateTypes::GE, self.lower)
+    }
+
+    #[must_use]
+    pub fn upper_predicate(&self) -> PredicateInfo {
+        self.predicate(PredicateTypes::LT, self.upper)
+    }
+
+    fn predicate(&self, p_type: PredicateTypes, p_value: PredicateValue) -> PredicateInfo {
+        PredicateInfo {
+            name: self.name.clone(),
+            p_type,
+            p_value,
+            restrictions: self.restrictions.clone(),
+            non_revoked: self.non_revoked.clone(),
+        }
+    }
+}
+
+impl Validatable for RangeInfo {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.lower >= self.upper {
+            return Err(invalid!(
+                "Requested range for \"{}\" is empty: {} >= {}",
+                self.name,
+                self.lower,
+                self.upper
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RequestedAttributeInfo {
     pub attr_referent: String,
@@ -255,50 +510,370 @@ impl Validatable for PresentationRequest {
             ));
         }
 
+        if value.verifier_domain.as_deref().is_some_and(str::is_empty) {
+            return Err(invalid!(
+                "Presentation request validation failed: `verifier_domain` is empty"
+            ));
+        }
+
         for requested_attribute in value.requested_attributes.values() {
-            let has_name = !requested_attribute
-                .name
-                .as_ref()
-                .map_or(true, String::is_empty);
-            let has_names = !requested_attribute
-                .names
-                .as_ref()
-                .map_or(true, Vec::is_empty);
-            if !has_name && !has_names {
+            _validate_requested_attribute(requested_attribute, &version)?;
+        }
+
+        for requested_predicate in value.requested_predicates.values() {
+            _validate_requested_predicate(requested_predicate, &version)?;
+        }
+
+        for (referent, requested_range) in &value.requested_ranges {
+            requested_range.validate()?;
+            let (lower_referent, upper_referent) = RangeInfo::predicate_referents(referent);
+            if value.requested_predicates.get(&lower_referent)
+                != Some(&requested_range.lower_predicate())
+                || value.requested_predicates.get(&upper_referent)
+                    != Some(&requested_range.upper_predicate())
+            {
                 return Err(invalid!(
-                    "Presentation request validation failed: there is empty requested attribute: {:?}",
-                    requested_attribute
+                    "Presentation request validation failed: requested range \"{}\" is not expanded into requested predicates",
+                    referent
                 ));
             }
+        }
 
-            if has_name && has_names {
-                return Err(invalid!(
-                    "Presentation request validation failed: there is a requested attribute with both name and names: {:?}",
-                    requested_attribute
-                ));
+        Ok(())
+    }
+}
+
+/// Severity of a [`LintDiagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    /// The request is invalid or can never be verified successfully
+    Error,
+    /// The request is valid, but holders are likely to be unable to satisfy it
+    Warning,
+}
+
+/// Problem found in a presentation request by [`PresentationRequest::lint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    pub severity: LintSeverity,
+    /// Referent of the requested attribute or predicate the problem was found in,
+    /// or `None` for problems with the request as a whole
+    pub referent: Option<String>,
+    pub message: String,
+}
+
+impl LintDiagnostic {
+    fn error(referent: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            severity: LintSeverity::Error,
+            referent: referent.map(str::to_owned),
+            message: message.into(),
+        }
+    }
+
+    fn warning(referent: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: LintSeverity::Warning,
+            referent: Some(referent.to_owned()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.referent {
+            Some(referent) => write!(
+                f,
+                "{:?} in \"{}\": {}",
+                self.severity, referent, self.message
+            ),
+            None => write!(f, "{:?}: {}", self.severity, self.message),
+        }
+    }
+}
+
+impl PresentationRequest {
+    /// Check the request for problems which would otherwise only surface once a holder
+    /// fails to create a presentation, or its verification fails.
+    ///
+    /// Unlike [`Validatable::validate`], which stops at the first problem, every requested
+    /// attribute and predicate is checked. Diagnostics are ordered by severity and referent.
+    #[must_use]
+    pub fn lint(&self) -> Vec<LintDiagnostic> {
+        let value = self.value();
+        let version = self.version();
+        let mut diagnostics = Vec::new();
+
+        let requested_names: Vec<(&str, &str)> = value
+            .requested_attributes
+            .iter()
+            .flat_map(|(referent, info)| {
+                _attribute_names(info).map(move |name| (referent.as_str(), name))
+            })
+            .collect();
+
+        for (referent, info) in &value.requested_attributes {
+            if let Err(err) = _validate_requested_attribute(info, &version) {
+                diagnostics.push(LintDiagnostic::error(Some(referent), _lint_message(err)));
             }
+            _lint_restrictions(
+                referent,
+                info.restrictions.as_ref(),
+                &_attribute_names(info).collect::<Vec<_>>(),
+                &mut diagnostics,
+            );
+        }
 
-            if let Some(restrictions) = &requested_attribute.restrictions {
-                _process_operator(restrictions, &version)?;
+        for (referent, info) in &value.requested_predicates {
+            if let Err(err) = _validate_requested_predicate(info, &version) {
+                diagnostics.push(LintDiagnostic::error(Some(referent), _lint_message(err)));
+            }
+            // revealed attributes of the same credential are available to `attr::<name>::value`
+            let attr_names: Vec<&str> = std::iter::once(info.name.as_str())
+                .chain(requested_names.iter().map(|(_, name)| *name))
+                .collect();
+            _lint_restrictions(
+                referent,
+                info.restrictions.as_ref(),
+                &attr_names,
+                &mut diagnostics,
+            );
+
+            let predicate_name = attr_common_view(&info.name);
+            for (attr_referent, name) in &requested_names {
+                if attr_common_view(name) == predicate_name {
+                    diagnostics.push(LintDiagnostic::warning(
+                        referent,
+                        format!(
+                            "predicate attribute \"{}\" is also requested revealed by \"{}\", which can not both be proven by the same credential",
+                            info.name, attr_referent
+                        ),
+                    ));
+                }
             }
         }
 
-        for requested_predicate in value.requested_predicates.values() {
-            if requested_predicate.name.is_empty() {
-                return Err(invalid!(
-                    "Presentation request validation failed: there is empty requested attribute: {:?}",
-                    requested_predicate
+        let mut referents_by_name: BTreeMap<String, (&str, BTreeSet<&str>)> = BTreeMap::new();
+        for (referent, name) in &requested_names {
+            referents_by_name
+                .entry(attr_common_view(name))
+                .or_insert_with(|| (name, BTreeSet::new()))
+                .1
+                .insert(referent);
+        }
+        for (name, referents) in referents_by_name.values() {
+            let mut referents = referents.iter();
+            let Some(first) = referents.next() else {
+                continue;
+            };
+            for referent in referents {
+                diagnostics.push(LintDiagnostic::warning(
+                    referent,
+                    format!("attribute \"{name}\" is also requested by \"{first}\""),
                 ));
             }
-            if let Some(restrictions) = &requested_predicate.restrictions {
-                _process_operator(restrictions, &version)?;
+        }
+
+        // request level problems, such as unexpanded ranges, are only found by validation
+        if !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == LintSeverity::Error)
+        {
+            if let Err(err) = self.validate() {
+                diagnostics.push(LintDiagnostic::error(None, _lint_message(err)));
             }
         }
 
-        Ok(())
+        diagnostics.sort_by(|a, b| (a.severity, &a.referent).cmp(&(b.severity, &b.referent)));
+        diagnostics
     }
 }
 
+/// Builds a [`PresentationRequest`], expanding requested ranges and rejecting requests
+/// with [`LintSeverity::Error`] diagnostics.
+#[derive(Debug)]
+pub struct PresentationRequestBuilder {
+    version: PresentationRequestVersion,
+    payload: PresentationRequestPayload,
+}
+
+impl PresentationRequestBuilder {
+    #[must_use]
+    pub fn new(name: impl Into<String>, version: impl Into<String>, nonce: Nonce) -> Self {
+        Self {
+            version: PresentationRequestVersion::V1,
+            payload: PresentationRequestPayload {
+                nonce,
+                name: name.into(),
+                version: version.into(),
+                requested_attributes: HashMap::new(),
+                requested_predicates: HashMap::new(),
+                requested_ranges: HashMap::new(),
+                non_revoked: None,
+                verifier_domain: None,
+            },
+        }
+    }
+
+    /// Set the request format version, which must be `V2` for fully qualified identifiers
+    pub fn set_request_version(&mut self, version: PresentationRequestVersion) {
+        self.version = version;
+    }
+
+    pub fn set_non_revoked(&mut self, non_revoked: NonRevokedInterval) {
+        self.payload.non_revoked = Some(non_revoked);
+    }
+
+    /// Bind presentations to the verifier, see [`PresentationRequestPayload::verifier_domain`]
+    pub fn set_verifier_domain(&mut self, verifier_domain: impl Into<String>) {
+        self.payload.verifier_domain = Some(verifier_domain.into());
+    }
+
+    pub fn add_requested_attribute(&mut self, referent: impl Into<String>, info: AttributeInfo) {
+        self.payload
+            .requested_attributes
+            .insert(referent.into(), info);
+    }
+
+    pub fn add_requested_predicate(&mut self, referent: impl Into<String>, info: PredicateInfo) {
+        self.payload
+            .requested_predicates
+            .insert(referent.into(), info);
+    }
+
+    pub fn add_requested_range(&mut self, referent: impl Into<String>, info: RangeInfo) {
+        self.payload.requested_ranges.insert(referent.into(), info);
+    }
+
+    pub fn build(mut self) -> crate::Result<PresentationRequest> {
+        self.payload.expand_ranges()?;
+        let request = match self.version {
+            PresentationRequestVersion::V1 => {
+                PresentationRequest::PresentationRequestV1(self.payload)
+            }
+            PresentationRequestVersion::V2 => {
+                PresentationRequest::PresentationRequestV2(self.payload)
+            }
+        };
+        let errors: Vec<String> = request
+            .lint()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == LintSeverity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return Err(err_msg!(
+                "Invalid presentation request: {}",
+                errors.join("; ")
+            ));
+        }
+        Ok(request)
+    }
+}
+
+fn _attribute_names(info: &AttributeInfo) -> impl Iterator<Item = &str> {
+    info.name
+        .iter()
+        .chain(info.names.iter().flatten())
+        .map(String::as_str)
+}
+
+fn _lint_message(mut err: ValidationError) -> String {
+    err.context.take().unwrap_or_else(|| err.to_string())
+}
+
+fn _lint_restrictions(
+    referent: &str,
+    restrictions: Option<&Query>,
+    attr_names: &[&str],
+    diagnostics: &mut Vec<LintDiagnostic>,
+) {
+    let Some(restrictions) = restrictions else {
+        return;
+    };
+    let mut tags = BTreeSet::new();
+    _collect_restriction_tags(restrictions, &mut tags);
+    for tag in tags {
+        if !is_known_restriction_tag(tag, attr_names.iter().copied()) {
+            diagnostics.push(LintDiagnostic::error(
+                Some(referent),
+                format!("unknown restriction tag \"{tag}\""),
+            ));
+        }
+    }
+}
+
+fn _collect_restriction_tags<'a>(restriction_op: &'a Query, tags: &mut BTreeSet<&'a str>) {
+    match restriction_op {
+        Query::Eq(tag_name, _)
+        | Query::Neq(tag_name, _)
+        | Query::Gt(tag_name, _)
+        | Query::Gte(tag_name, _)
+        | Query::Lt(tag_name, _)
+        | Query::Lte(tag_name, _)
+        | Query::Like(tag_name, _)
+        | Query::In(tag_name, _) => {
+            tags.insert(tag_name);
+        }
+        Query::Exist(tag_names) => tags.extend(tag_names.iter().map(String::as_str)),
+        Query::And(operators) | Query::Or(operators) => {
+            for operator in operators {
+                _collect_restriction_tags(operator, tags);
+            }
+        }
+        Query::Not(operator) => _collect_restriction_tags(operator, tags),
+    }
+}
+
+fn _validate_requested_attribute(
+    requested_attribute: &AttributeInfo,
+    version: &PresentationRequestVersion,
+) -> Result<(), ValidationError> {
+    let has_name = !requested_attribute
+        .name
+        .as_ref()
+        .map_or(true, String::is_empty);
+    let has_names = !requested_attribute
+        .names
+        .as_ref()
+        .map_or(true, Vec::is_empty);
+    if !has_name && !has_names {
+        return Err(invalid!(
+            "Presentation request validation failed: there is empty requested attribute: {:?}",
+            requested_attribute
+        ));
+    }
+
+    if has_name && has_names {
+        return Err(invalid!(
+            "Presentation request validation failed: there is a requested attribute with both name and names: {:?}",
+            requested_attribute
+        ));
+    }
+
+    if let Some(restrictions) = &requested_attribute.restrictions {
+        _process_operator(restrictions, version)?;
+    }
+    Ok(())
+}
+
+fn _validate_requested_predicate(
+    requested_predicate: &PredicateInfo,
+    version: &PresentationRequestVersion,
+) -> Result<(), ValidationError> {
+    if requested_predicate.name.is_empty() {
+        return Err(invalid!(
+            "Presentation request validation failed: there is empty requested attribute: {:?}",
+            requested_predicate
+        ));
+    }
+    if let Some(restrictions) = &requested_predicate.restrictions {
+        _process_operator(restrictions, version)?;
+    }
+    Ok(())
+}
+
 fn _process_operator(
     restriction_op: &Query,
     version: &PresentationRequestVersion,
@@ -395,6 +970,340 @@ mod tests {
         }
     }
 
+    #[test]
+    fn presentation_request_verifier_domain() {
+        let req_json = json!({
+            "nonce": "123456",
+            "name": "name",
+            "version": "2.0",
+            "requested_attributes": { "attr1": { "name": "name" } },
+        });
+
+        let req: PresentationRequest = serde_json::from_value(req_json.clone()).unwrap();
+        assert_eq!(req.value().verifier_domain, None);
+        req.validate().unwrap();
+        assert!(
+            serde_json::to_value(&req)
+                .unwrap()
+                .get("verifier_domain")
+                .is_none()
+        );
+
+        let mut req_json = req_json;
+        req_json["verifier_domain"] = json!("https://verifier.example.org");
+        let req: PresentationRequest = serde_json::from_value(req_json).unwrap();
+        assert_eq!(
+            req.value().verifier_domain.as_deref(),
+            Some("https://verifier.example.org")
+        );
+        req.validate().unwrap();
+
+        let mut builder = PresentationRequestBuilder::new("name", "2.0", Nonce::new().unwrap());
+        builder.add_requested_attribute("attr1", AttributeInfo::new("name"));
+        builder.set_verifier_domain("");
+        builder.build().unwrap_err();
+    }
+
+    #[test]
+    fn predicate_value_rejects_values_outside_i32() {
+        let info: PredicateInfo =
+            serde_json::from_value(json!({"name": "age", "p_type": ">=", "p_value": 18})).unwrap();
+        assert_eq!(info.p_value, 18);
+
+        let err = serde_json::from_value::<PredicateInfo>(json!({
+            "name": "expiry",
+            "p_type": ">=",
+            "p_value": 4_102_444_800_i64
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("outside the 32-bit range"));
+
+        serde_json::from_value::<RangeInfo>(json!({
+            "name": "balance",
+            "lower": 0,
+            "upper": 4_102_444_800_i64
+        }))
+        .unwrap_err();
+    }
+
+    mod date_predicates {
+        use super::*;
+
+        fn date(value: &str) -> CalendarDate {
+            value.parse().unwrap()
+        }
+
+        fn is_adult(dob: &str, today: &str) -> bool {
+            let info =
+                PredicateInfo::for_minimum_age("dob", 18, date(today), DateEncoding::YearMonthDay)
+                    .unwrap();
+            info.is_satisfied_by(date(dob).yyyymmdd())
+        }
+
+        #[test]
+        fn minimum_age_predicate_works() {
+            let info = PredicateInfo::for_minimum_age(
+                "dob",
+                18,
+                date("2024-06-15"),
+                DateEncoding::YearMonthDay,
+            )
+            .unwrap();
+            assert_eq!(info.p_type, PredicateTypes::LE);
+            assert_eq!(info.p_value, 20_060_615);
+
+            assert!(is_adult("2006-06-15", "2024-06-15"));
+            assert!(!is_adult("2006-06-16", "2024-06-15"));
+        }
+
+        #[test]
+        fn minimum_age_predicate_handles_leap_days() {
+            // born on a leap day: adult on March 1st in a non-leap year
+            assert!(!is_adult("2004-02-29", "2022-02-28"));
+            assert!(is_adult("2004-02-29", "2022-03-01"));
+            // checked on a leap day against a non-leap birth year
+            assert!(is_adult("2006-02-28", "2024-02-29"));
+            assert!(!is_adult("2006-03-01", "2024-02-29"));
+        }
+
+        #[test]
+        fn date_after_predicate_works() {
+            let info = PredicateInfo::for_date_after(
+                "expiry",
+                date("2024-12-31"),
+                DateEncoding::DaysFromCe,
+            )
+            .unwrap();
+            assert_eq!(info.p_type, PredicateTypes::GT);
+            assert!(info.is_satisfied_by(date("2025-01-01").days_from_ce()));
+            assert!(!info.is_satisfied_by(date("2024-12-31").days_from_ce()));
+        }
+
+        #[test]
+        fn relative_date_predicate_rejects_out_of_range_dates() {
+            PredicateInfo::for_relative_date(
+                "dob",
+                PredicateTypes::LE,
+                date("0010-01-01"),
+                DateOffset::Years(-18),
+                DateEncoding::YearMonthDay,
+            )
+            .unwrap_err();
+        }
+    }
+
+    mod requested_ranges {
+        use super::*;
+
+        fn _range_request() -> serde_json::Value {
+            json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "1.0",
+                "requested_ranges": {
+                    "working_age": { "name": "age", "lower": 18, "upper": 65 }
+                }
+            })
+        }
+
+        #[test]
+        fn range_is_expanded_into_predicates() {
+            let req: PresentationRequest = serde_json::from_value(_range_request()).unwrap();
+            req.validate().unwrap();
+            let payload = req.value();
+
+            let (lower, upper) = RangeInfo::predicate_referents("working_age");
+            assert_eq!(
+                payload.requested_predicates[&lower].p_type,
+                PredicateTypes::GE
+            );
+            assert_eq!(payload.requested_predicates[&lower].p_value, 18);
+            assert_eq!(
+                payload.requested_predicates[&upper].p_type,
+                PredicateTypes::LT
+            );
+            assert_eq!(payload.requested_predicates[&upper].p_value, 65);
+            assert_eq!(
+                payload
+                    .range_for_predicate(&upper)
+                    .map(|(referent, _)| referent),
+                Some("working_age")
+            );
+
+            // the expanded form deserializes to the same request
+            let json = serde_json::to_value(&req).unwrap();
+            assert!(json["requested_predicates"][&lower].is_object());
+            assert_eq!(
+                serde_json::from_value::<PresentationRequest>(json).unwrap(),
+                req
+            );
+        }
+
+        #[test]
+        fn range_conflicting_with_predicate_is_rejected() {
+            let mut req = _range_request();
+            req["requested_predicates"] = json!({
+                "working_age::lower": { "name": "age", "p_type": ">=", "p_value": 21 }
+            });
+            serde_json::from_value::<PresentationRequest>(req).unwrap_err();
+        }
+
+        #[test]
+        fn empty_range_is_rejected() {
+            let mut req = _range_request();
+            req["requested_ranges"]["working_age"]["upper"] = json!(18);
+            serde_json::from_value::<PresentationRequest>(req).unwrap_err();
+        }
+
+        #[test]
+        fn unexpanded_range_is_invalid() {
+            let req: PresentationRequest = serde_json::from_value(_range_request()).unwrap();
+            let PresentationRequest::PresentationRequestV1(mut payload) = req else {
+                unreachable!()
+            };
+            payload.requested_predicates.clear();
+            payload.requested_attributes.insert(
+                "name".to_owned(),
+                AttributeInfo {
+                    name: Some("name".to_owned()),
+                    names: None,
+                    restrictions: None,
+                    non_revoked: None,
+                },
+            );
+            let req = PresentationRequest::PresentationRequestV1(payload);
+            req.validate().unwrap_err();
+        }
+    }
+
+    mod lint {
+        use super::*;
+
+        fn _builder() -> PresentationRequestBuilder {
+            PresentationRequestBuilder::new("name", "1.0", Nonce::new().unwrap())
+        }
+
+        #[test]
+        fn builder_builds_valid_request() {
+            let mut builder = _builder();
+            let mut attr = AttributeInfo::new("name");
+            attr.restrictions = Some(Query::And(vec![
+                Query::Eq("schema_name".to_owned(), "gvt".to_owned()),
+                Query::Eq("attr::name::value".to_owned(), "Alex".to_owned()),
+            ]));
+            builder.add_requested_attribute("attr1_referent", attr);
+            builder.add_requested_attribute("attr2_referent", AttributeInfo::group(["sex", "zip"]));
+            builder.add_requested_predicate(
+                "predicate1_referent",
+                PredicateInfo::new("height", PredicateTypes::GE, 150),
+            );
+            builder.add_requested_range("range1_referent", RangeInfo::new("age", 18, 65));
+            builder.set_request_version(PresentationRequestVersion::V2);
+
+            let req = builder.build().unwrap();
+            assert_eq!(req.version(), PresentationRequestVersion::V2);
+            assert_eq!(req.value().requested_predicates.len(), 3);
+            req.validate().unwrap();
+            assert_eq!(req.lint(), vec![]);
+        }
+
+        #[test]
+        fn builder_rejects_unknown_restriction_tag() {
+            let mut builder = _builder();
+            let mut predicate = PredicateInfo::new("age", PredicateTypes::GE, 18);
+            predicate.restrictions = Some(Query::Eq("schema_nam".to_owned(), "gvt".to_owned()));
+            builder.add_requested_predicate("predicate1_referent", predicate);
+
+            let err = builder.build().unwrap_err();
+            assert!(err.to_string().contains("predicate1_referent"));
+        }
+
+        #[test]
+        fn lint_reports_every_referent() {
+            let req: PresentationRequest = serde_json::from_value(json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "1.0",
+                "requested_attributes": {
+                    "empty": { "names": [] },
+                    "both": { "name": "name", "names": ["sex"] },
+                    "name": { "name": "Name" },
+                    "restricted": {
+                        "name": "height",
+                        "restrictions": { "attr::age::value": "28" }
+                    },
+                },
+                "requested_predicates": {
+                    "age": {
+                        "name": "age",
+                        "p_type": ">=",
+                        "p_value": 18,
+                        "restrictions": { "attr::height::value": "175" }
+                    },
+                    "sex": { "name": "sex", "p_type": ">", "p_value": 0 },
+                },
+            }))
+            .unwrap();
+            req.validate().unwrap_err();
+
+            let lint = req.lint();
+            let found = |severity: LintSeverity, referent: &str, message: &str| {
+                lint.iter().any(|d| {
+                    d.severity == severity
+                        && d.referent.as_deref() == Some(referent)
+                        && d.message.contains(message)
+                })
+            };
+            assert!(found(
+                LintSeverity::Error,
+                "empty",
+                "empty requested attribute"
+            ));
+            assert!(found(LintSeverity::Error, "both", "both name and names"));
+            assert!(found(LintSeverity::Error, "restricted", "attr::age::value"));
+            assert!(found(
+                LintSeverity::Warning,
+                "sex",
+                "also requested revealed by \"both\""
+            ));
+            assert!(found(
+                LintSeverity::Warning,
+                "name",
+                "also requested by \"both\""
+            ));
+            assert_eq!(
+                lint.iter()
+                    .map(|d| (d.severity, d.referent.as_deref().unwrap()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (LintSeverity::Error, "both"),
+                    (LintSeverity::Error, "empty"),
+                    (LintSeverity::Error, "restricted"),
+                    (LintSeverity::Warning, "name"),
+                    (LintSeverity::Warning, "sex"),
+                ]
+            );
+        }
+
+        #[test]
+        fn lint_reports_request_level_problems() {
+            let req = PresentationRequest::PresentationRequestV1(PresentationRequestPayload {
+                nonce: Nonce::new().unwrap(),
+                name: "name".to_owned(),
+                version: "1.0".to_owned(),
+                requested_attributes: HashMap::new(),
+                requested_predicates: HashMap::new(),
+                requested_ranges: HashMap::new(),
+                non_revoked: None,
+                verifier_domain: None,
+            });
+            let lint = req.lint();
+            assert_eq!(lint.len(), 1);
+            assert_eq!(lint[0].severity, LintSeverity::Error);
+            assert_eq!(lint[0].referent, None);
+        }
+    }
+
     #[test]
     fn override_works() {
         let mut interval = NonRevokedInterval::default();
@@ -1,11 +1,13 @@
 use super::issuer_id::IssuerId;
+use super::pres_request::NonRevokedInterval;
 use super::rev_reg::RevocationRegistry;
 use super::rev_reg_def::RevocationRegistryDefinitionId;
 
 use crate::cl::{Accumulator, RevocationRegistry as CryptoRevocationRegistry};
 use crate::{Error, Result};
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::BitXor;
 
 /// Data model for the revocation status list as defined in the [Anoncreds V1.0
 /// specification](https://hyperledger.github.io/anoncreds-spec/#creating-the-initial-revocation-status-list-object)
@@ -15,8 +17,7 @@ pub struct RevocationStatusList {
     #[serde(skip_serializing_if = "Option::is_none")]
     rev_reg_def_id: Option<RevocationRegistryDefinitionId>,
     issuer_id: IssuerId,
-    #[serde(with = "serde_revocation_list")]
-    revocation_list: bitvec::vec::BitVec,
+    revocation_list: RevocationList,
     #[serde(
         rename = "currentAccumulator",
         alias = "accum",
@@ -27,6 +28,52 @@ pub struct RevocationStatusList {
     timestamp: Option<u64>,
 }
 
+/// Wire encoding of the `revocationList` field of a [`RevocationStatusList`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RevocationListEncoding {
+    /// JSON array with one `0`/`1` entry per credential, i.e. `[0, 1, 0]`
+    #[default]
+    Array,
+    /// GZIP compressed bitstring encoded as multibase base64url, following the
+    /// [W3C Bitstring Status List](https://www.w3.org/TR/vc-bitstring-status-list/)
+    /// encoding, i.e. `{"length": 3, "encodedList": "uH4sI..."}`
+    Bitstring,
+}
+
+/// Revocation state together with the encoding it is serialized with
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct RevocationList {
+    bits: bitvec::vec::BitVec,
+    encoding: RevocationListEncoding,
+}
+
+impl serde::Serialize for RevocationList {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.encoding {
+            RevocationListEncoding::Array => {
+                serde_revocation_list::serialize(&self.bits, serializer)
+            }
+            RevocationListEncoding::Bitstring => {
+                serde_revocation_list::serialize_bitstring(&self.bits, serializer)
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RevocationList {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (bits, encoding) = serde_revocation_list::deserialize_encoded(deserializer)?;
+        Ok(Self { bits, encoding })
+    }
+}
+
 impl From<&RevocationStatusList> for Option<CryptoRevocationRegistry> {
     fn from(value: &RevocationStatusList) -> Self {
         value.accum.map(From::from)
@@ -51,11 +98,60 @@ impl RevocationStatusList {
     }
 
     pub(crate) const fn state(&self) -> &bitvec::vec::BitVec {
-        &self.revocation_list
+        &self.revocation_list.bits
     }
 
-    pub(crate) fn state_owned(&self) -> bitvec::vec::BitVec {
-        self.revocation_list.clone()
+    /// The encoding used for the revocation list when this status list is serialized
+    #[must_use]
+    pub const fn list_encoding(&self) -> RevocationListEncoding {
+        self.revocation_list.encoding
+    }
+
+    /// Select the encoding used for the revocation list when this status list is
+    /// serialized. Deserialization accepts either encoding and preserves it.
+    pub fn set_list_encoding(&mut self, encoding: RevocationListEncoding) {
+        self.revocation_list.encoding = encoding;
+    }
+
+    /// Compute the changes from `previous` to this revocation status list
+    ///
+    /// Both lists must belong to the same registry and have the same size.
+    pub fn diff(&self, previous: &Self) -> Result<RevocationStatusListDelta> {
+        if let (Some(id), Some(previous_id)) = (&self.rev_reg_def_id, &previous.rev_reg_def_id) {
+            if id != previous_id {
+                return Err(err_msg!(
+                    "Revocation Status Lists belong to different registries: {}, {}",
+                    previous_id,
+                    id
+                ));
+            }
+        }
+        if self.state().len() != previous.state().len() {
+            return Err(err_msg!(
+                "Revocation Status Lists differ in size: {}, {}",
+                previous.state().len(),
+                self.state().len()
+            ));
+        }
+        let (issued, revoked) = index_deltas(previous.state(), self.state());
+        Ok(RevocationStatusListDelta {
+            issued,
+            revoked,
+            previous_accum: previous.accum,
+            accum: self.accum,
+        })
+    }
+
+    /// Stamp a list with the registry identifier and a later timestamp at which it was
+    /// still the current state of the registry
+    pub(crate) fn current_at(
+        mut self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Self {
+        self.rev_reg_def_id = Some(rev_reg_def_id.clone());
+        self.timestamp = Some(timestamp);
+        self
     }
 
     pub fn set_registry(&mut self, registry: CryptoRevocationRegistry) -> Result<()> {
@@ -64,7 +160,7 @@ impl RevocationStatusList {
     }
 
     pub(crate) fn get(&self, idx: usize) -> Option<bool> {
-        self.revocation_list.get(idx).as_deref().copied()
+        self.revocation_list.bits.get(idx).as_deref().copied()
     }
 
     pub(crate) fn update(
@@ -78,7 +174,7 @@ impl RevocationStatusList {
         if let Some(reg) = registry {
             self.accum = Some(reg.accum);
         }
-        let slots_count = self.revocation_list.len();
+        let slots_count = self.revocation_list.bits.len();
         if let Some(issued) = issued {
             if let Some(max_idx) = issued.iter().last().copied() {
                 if max_idx as usize >= slots_count {
@@ -91,7 +187,7 @@ impl RevocationStatusList {
             // issued credentials are assigned `false`
             // i.e. NOT revoked
             for i in issued {
-                self.revocation_list.set(i as usize, false);
+                self.revocation_list.bits.set(i as usize, false);
             }
         }
         if let Some(revoked) = revoked {
@@ -106,7 +202,7 @@ impl RevocationStatusList {
             // revoked credentials are assigned `true`
             // i.e. IS revoked
             for i in revoked {
-                self.revocation_list.set(i as usize, true);
+                self.revocation_list.bits.set(i as usize, true);
             }
         }
         // only update if input is Some
@@ -128,20 +224,192 @@ impl RevocationStatusList {
                 .map(RevocationRegistryDefinitionId::new)
                 .transpose()?,
             issuer_id,
-            revocation_list,
+            revocation_list: RevocationList {
+                bits: revocation_list,
+                encoding: RevocationListEncoding::default(),
+            },
             accum: registry.map(|r| r.accum),
             timestamp,
         })
     }
 }
 
+/// Indices whose state differs between two revocation lists, split into the credentials
+/// issued (cleared) and revoked (set) in `current`
+pub(crate) fn index_deltas(
+    previous: &bitvec::vec::BitVec,
+    current: &bitvec::vec::BitVec,
+) -> (BTreeSet<u32>, BTreeSet<u32>) {
+    let mut issued = BTreeSet::new();
+    let mut revoked = BTreeSet::new();
+    for i in current.clone().bitxor(previous).iter_ones() {
+        if current[i] {
+            // true means cred has been revoked
+            revoked.insert(i as u32);
+        } else {
+            // false means cred has not been
+            issued.insert(i as u32);
+        }
+    }
+    (issued, revoked)
+}
+
+/// Changes between two revocation status lists of the same registry
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationStatusListDelta {
+    /// Indices which are no longer revoked
+    pub issued: BTreeSet<u32>,
+    /// Indices which have been revoked
+    pub revoked: BTreeSet<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_accum: Option<Accumulator>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accum: Option<Accumulator>,
+}
+
+impl RevocationStatusListDelta {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.issued.is_empty() && self.revoked.is_empty() && !self.accum_changed()
+    }
+
+    #[must_use]
+    pub fn accum_changed(&self) -> bool {
+        self.previous_accum != self.accum
+    }
+}
+
+/// Revocation status lists of a single registry, ordered by timestamp
+#[derive(Clone, Debug, Default)]
+pub struct RevocationStatusListHistory {
+    rev_reg_def_id: Option<RevocationRegistryDefinitionId>,
+    lists: BTreeMap<u64, RevocationStatusList>,
+}
+
+impl RevocationStatusListHistory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub const fn rev_reg_def_id(&self) -> Option<&RevocationRegistryDefinitionId> {
+        self.rev_reg_def_id.as_ref()
+    }
+
+    /// Add a revocation status list, replacing any list with the same timestamp
+    ///
+    /// The list must carry the registry identifier and timestamp, and belong to the same
+    /// registry as the lists already stored.
+    pub fn insert(&mut self, rev_status_list: RevocationStatusList) -> Result<()> {
+        let rev_reg_def_id = rev_status_list
+            .id()
+            .ok_or_else(|| err_msg!("Revocation Status List missing Id"))?;
+        let timestamp = rev_status_list
+            .timestamp()
+            .ok_or_else(|| err_msg!("Revocation Status List missing timestamp"))?;
+        match &self.rev_reg_def_id {
+            Some(id) if *id != rev_reg_def_id => {
+                return Err(err_msg!(
+                    "Revocation Status List {} does not belong to registry {}",
+                    rev_reg_def_id,
+                    id
+                ));
+            }
+            Some(_) => {}
+            None => self.rev_reg_def_id = Some(rev_reg_def_id),
+        }
+        self.lists.insert(timestamp, rev_status_list);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+
+    /// The list published at exactly `timestamp`
+    #[must_use]
+    pub fn get(&self, timestamp: u64) -> Option<&RevocationStatusList> {
+        self.lists.get(&timestamp)
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Option<&RevocationStatusList> {
+        self.lists.values().next_back()
+    }
+
+    /// The list which was the current state of the registry at `timestamp`, i.e. the
+    /// latest list published at or before it
+    #[must_use]
+    pub fn current_at(&self, timestamp: u64) -> Option<&RevocationStatusList> {
+        self.lists
+            .range(..=timestamp)
+            .next_back()
+            .map(|(_, list)| list)
+    }
+
+    /// The list to use for proving or verifying non-revocation over `interval`
+    ///
+    /// This is the state of the registry at the end of the interval. When that list was
+    /// published before the interval starts, it is stamped with the start of the
+    /// interval, at which it was still current, so that its timestamp passes
+    /// `NonRevokedInterval::is_valid`. Holders and verifiers looking up the same interval
+    /// get the same list and timestamp.
+    #[must_use]
+    pub fn for_interval(&self, interval: &NonRevokedInterval) -> Option<RevocationStatusList> {
+        let list = self.current_at(interval.to.unwrap_or(u64::MAX))?;
+        match (
+            interval.from,
+            list.timestamp(),
+            self.rev_reg_def_id.as_ref(),
+        ) {
+            (Some(from), Some(timestamp), Some(rev_reg_def_id)) if timestamp < from => {
+                Some(list.clone().current_at(rev_reg_def_id, from))
+            }
+            _ => Some(list.clone()),
+        }
+    }
+
+    /// Changes between the lists current at `from` and at `to`
+    pub fn diff(&self, from: u64, to: u64) -> Result<RevocationStatusListDelta> {
+        let list = self
+            .current_at(to)
+            .ok_or_else(|| err_msg!("Revocation Status List not found for timestamp: {}", to))?;
+        let previous = self
+            .current_at(from)
+            .ok_or_else(|| err_msg!("Revocation Status List not found for timestamp: {}", from))?;
+        list.diff(previous)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RevocationStatusList> {
+        self.lists.values()
+    }
+}
+
 pub mod serde_revocation_list {
-    use bitvec::vec::BitVec;
+    use std::io::{Read, Write};
+
+    use bitvec::prelude::{BitVec, Msb0};
+    use flate2::{Compression, read::GzDecoder, write::GzEncoder};
     use serde::{
-        de::{Deserializer, Error as DeError, SeqAccess, Visitor},
-        ser::{SerializeSeq, Serializer},
+        de::{Deserializer, Error as DeError, MapAccess, SeqAccess, Visitor},
+        ser::{Error as SerError, SerializeSeq, SerializeStruct, Serializer},
     };
 
+    use super::RevocationListEncoding;
+    use crate::utils::base64;
+
+    /// Multibase prefix for base64url without padding
+    const MULTIBASE_BASE64URL: char = 'u';
+
+    /// Serialize the revocation state as a JSON array, i.e. `[1, 0, 1]`
     pub fn serialize<S>(state: &bitvec::vec::BitVec, s: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -154,19 +422,42 @@ pub mod serde_revocation_list {
         seq.end()
     }
 
+    /// Serialize the revocation state as a GZIP compressed, base64url encoded bitstring,
+    /// i.e. `{"length": 3, "encodedList": "uH4sI..."}`
+    pub fn serialize_bitstring<S>(state: &bitvec::vec::BitVec, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoded = encode_bitstring(state).map_err(S::Error::custom)?;
+        let mut map = s.serialize_struct("RevocationList", 2)?;
+        map.serialize_field("length", &state.len())?;
+        map.serialize_field("encodedList", &encoded)?;
+        map.end()
+    }
+
+    /// Deserialize the revocation state from either the array or the bitstring encoding
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bitvec::vec::BitVec, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_encoded(deserializer).map(|(state, _)| state)
+    }
+
+    pub(crate) fn deserialize_encoded<'de, D>(
+        deserializer: D,
+    ) -> Result<(bitvec::vec::BitVec, RevocationListEncoding), D::Error>
     where
         D: Deserializer<'de>,
     {
         struct JsonBitStringVisitor;
 
         impl<'de> Visitor<'de> for JsonBitStringVisitor {
-            type Value = bitvec::vec::BitVec;
+            type Value = (bitvec::vec::BitVec, RevocationListEncoding);
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
                 write!(
                     formatter,
-                    "a seq containing revocation state, i.e. [1, 0, 1]"
+                    "a seq containing revocation state, i.e. [1, 0, 1], or an encoded bitstring, i.e. {{\"length\": 3, \"encodedList\": \"uH4sI...\"}}"
                 )
             }
 
@@ -185,10 +476,75 @@ pub mod serde_revocation_list {
                         }
                     }
                 }
-                Ok(bv)
+                Ok((bv, RevocationListEncoding::Array))
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut length: Option<usize> = None;
+                let mut encoded: Option<String> = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "length" => {
+                            if length.replace(map.next_value()?).is_some() {
+                                return Err(M::Error::duplicate_field("length"));
+                            }
+                        }
+                        "encodedList" => {
+                            if encoded.replace(map.next_value()?).is_some() {
+                                return Err(M::Error::duplicate_field("encodedList"));
+                            }
+                        }
+                        other => {
+                            return Err(M::Error::unknown_field(other, &["length", "encodedList"]));
+                        }
+                    }
+                }
+                let length = length.ok_or_else(|| M::Error::missing_field("length"))?;
+                let encoded = encoded.ok_or_else(|| M::Error::missing_field("encodedList"))?;
+                let state = decode_bitstring(length, &encoded).map_err(M::Error::custom)?;
+                Ok((state, RevocationListEncoding::Bitstring))
             }
         }
-        deserializer.deserialize_seq(JsonBitStringVisitor)
+        deserializer.deserialize_any(JsonBitStringVisitor)
+    }
+
+    /// Pack the state most significant bit first, so index 0 is the left-most bit of
+    /// the first byte, then compress and encode it
+    fn encode_bitstring(state: &bitvec::vec::BitVec) -> std::io::Result<String> {
+        let mut packed: BitVec<u8, Msb0> = state.iter().by_vals().collect();
+        packed.set_uninitialized(false);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(packed.as_raw_slice())?;
+        let compressed = encoder.finish()?;
+        Ok(format!(
+            "{}{}",
+            MULTIBASE_BASE64URL,
+            base64::encode(compressed)
+        ))
+    }
+
+    fn decode_bitstring(length: usize, encoded: &str) -> Result<bitvec::vec::BitVec, String> {
+        let encoded = encoded.strip_prefix(MULTIBASE_BASE64URL).unwrap_or(encoded);
+        let compressed =
+            base64::decode(encoded).map_err(|_| "invalid base64url encoded bitstring")?;
+        let expected = length.div_ceil(8);
+        // never inflate more than the declared length requires
+        let mut packed = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .take(expected as u64 + 1)
+            .read_to_end(&mut packed)
+            .map_err(|err| format!("invalid compressed bitstring: {err}"))?;
+        if packed.len() != expected {
+            return Err(format!(
+                "encoded bitstring does not match length {length}: expected {expected} bytes"
+            ));
+        }
+        let mut state: BitVec<u8, Msb0> = BitVec::from_vec(packed);
+        state.truncate(length);
+        Ok(state.iter().by_vals().collect())
     }
 }
 
@@ -239,7 +595,7 @@ mod rev_reg_tests {
     #[test]
     fn update_rev_status_list_works() {
         let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
-        let list_status = list.state_owned();
+        let list_status = list.state().clone();
         assert_eq!(list.timestamp().unwrap(), 1234);
         assert_eq!(list_status.get(0usize).unwrap(), true);
 
@@ -248,4 +604,138 @@ mod rev_reg_tests {
         assert!(!list.get(0usize).unwrap());
         assert_eq!(list.timestamp().unwrap(), 1245);
     }
+
+    #[test]
+    fn bitstring_rev_list_roundtrip_serde() {
+        let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        list.update(None, Some(BTreeSet::from([1u32, 2])), None, None)
+            .unwrap();
+        list.set_list_encoding(RevocationListEncoding::Bitstring);
+
+        let json = serde_json::to_value(&list).unwrap();
+        assert_eq!(json["revocationList"]["length"], 4);
+        let encoded = json["revocationList"]["encodedList"].as_str().unwrap();
+        assert!(encoded.starts_with("uH4sI"));
+
+        let des = serde_json::from_value::<RevocationStatusList>(json.clone()).unwrap();
+        assert_eq!(des.state(), &bitvec![1, 0, 0, 1]);
+        assert_eq!(des.list_encoding(), RevocationListEncoding::Bitstring);
+        assert_eq!(serde_json::to_value(&des).unwrap(), json);
+    }
+
+    #[test]
+    fn bitstring_rev_list_uses_most_significant_bit_first() {
+        // gzip of the single byte 0b1001_0000
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut gz, &[0b1001_0000]).unwrap();
+        let encoded = format!("u{}", crate::utils::base64::encode(gz.finish().unwrap()));
+
+        let mut json: serde_json::Value = serde_json::from_str(REVOCATION_LIST).unwrap();
+        json["revocationList"] = serde_json::json!({ "length": 5, "encodedList": encoded });
+        let des = serde_json::from_value::<RevocationStatusList>(json).unwrap();
+        assert_eq!(des.state(), &bitvec![1, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn bitstring_rev_list_rejects_length_mismatch() {
+        let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        list.set_list_encoding(RevocationListEncoding::Bitstring);
+        let json = serde_json::to_value(&list).unwrap();
+
+        for length in [9, 1_000_000] {
+            let mut json = json.clone();
+            json["revocationList"]["length"] = length.into();
+            assert!(serde_json::from_value::<RevocationStatusList>(json).is_err());
+        }
+
+        let mut json = json;
+        json["revocationList"]["encodedList"] = "unot-gzip".into();
+        assert!(serde_json::from_value::<RevocationStatusList>(json).is_err());
+    }
+
+    #[test]
+    fn array_rev_list_keeps_array_encoding() {
+        let des = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        assert_eq!(des.list_encoding(), RevocationListEncoding::Array);
+        let json = serde_json::to_value(&des).unwrap();
+        assert_eq!(json["revocationList"], serde_json::json!([1, 1, 1, 1]));
+    }
+
+    fn rev_list_at(timestamp: u64, issued: &[u32]) -> RevocationStatusList {
+        let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        list.update(
+            None,
+            Some(issued.iter().copied().collect()),
+            None,
+            Some(timestamp),
+        )
+        .unwrap();
+        list
+    }
+
+    #[test]
+    fn diff_reports_issued_and_revoked_indices() {
+        let previous = rev_list_at(10, &[0, 1]);
+        let list = rev_list_at(20, &[1, 2]);
+
+        let delta = list.diff(&previous).unwrap();
+        assert_eq!(delta.issued, BTreeSet::from([2]));
+        assert_eq!(delta.revoked, BTreeSet::from([0]));
+        assert!(!delta.accum_changed());
+        assert!(list.diff(&list).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_rejects_lists_of_other_registry() {
+        let previous = rev_list_at(10, &[]);
+        let mut json = serde_json::to_value(rev_list_at(20, &[])).unwrap();
+        json["revRegDefId"] = "other".into();
+        let list = serde_json::from_value::<RevocationStatusList>(json).unwrap();
+        assert!(list.diff(&previous).is_err());
+    }
+
+    #[test]
+    fn history_returns_list_for_interval() {
+        let mut history = RevocationStatusListHistory::new();
+        history.insert(rev_list_at(10, &[0])).unwrap();
+        history.insert(rev_list_at(20, &[0, 1])).unwrap();
+        assert_eq!(history.len(), 2);
+
+        assert!(history.current_at(5).is_none());
+        assert_eq!(history.current_at(15).unwrap().timestamp(), Some(10));
+        assert_eq!(history.latest().unwrap().timestamp(), Some(20));
+
+        // latest list published within the interval
+        let list = history
+            .for_interval(&NonRevokedInterval::new(Some(5), Some(25)))
+            .unwrap();
+        assert_eq!(list.timestamp(), Some(20));
+
+        // list published before the interval is stamped with its start
+        let interval = NonRevokedInterval::new(Some(12), Some(18));
+        let list = history.for_interval(&interval).unwrap();
+        assert_eq!(list.timestamp(), Some(12));
+        assert!(interval.is_valid(list.timestamp().unwrap()).is_ok());
+        assert_eq!(list.state(), history.get(10).unwrap().state());
+
+        assert!(
+            history
+                .for_interval(&NonRevokedInterval::new(None, Some(5)))
+                .is_none()
+        );
+
+        let delta = history.diff(15, 25).unwrap();
+        assert_eq!(delta.issued, BTreeSet::from([1]));
+        assert!(delta.revoked.is_empty());
+    }
+
+    #[test]
+    fn history_rejects_lists_of_other_registry() {
+        let mut history = RevocationStatusListHistory::new();
+        history.insert(rev_list_at(10, &[])).unwrap();
+        let mut json = serde_json::to_value(rev_list_at(20, &[])).unwrap();
+        json["revRegDefId"] = "other".into();
+        let list = serde_json::from_value::<RevocationStatusList>(json).unwrap();
+        assert!(history.insert(list).is_err());
+    }
 }
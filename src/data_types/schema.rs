@@ -1,8 +1,10 @@
 use crate::impl_anoncreds_object_identifier;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use super::attr_encoding::AttributeEncoding;
 use super::issuer_id::IssuerId;
+use crate::services::helpers::{attr_common_view, encode_credential_attribute};
 
 pub const MAX_ATTRIBUTES_COUNT: usize = 125;
 
@@ -15,6 +17,36 @@ pub struct Schema {
     pub version: String,
     pub attr_names: AttributeNames,
     pub issuer_id: IssuerId,
+    /// Typed encodings for attributes which do not use the default encoding
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attr_encodings: HashMap<String, AttributeEncoding>,
+}
+
+impl Schema {
+    /// The encoding declared for an attribute, if any.
+    ///
+    /// Attribute names are compared case-insensitively and ignoring spaces, as in
+    /// presentation requests.
+    #[must_use]
+    pub fn attr_encoding(&self, attr_name: &str) -> Option<&AttributeEncoding> {
+        if let Some(encoding) = self.attr_encodings.get(attr_name) {
+            return Some(encoding);
+        }
+        let attr_name = attr_common_view(attr_name);
+        self.attr_encodings
+            .iter()
+            .find(|(name, _)| attr_common_view(name) == attr_name)
+            .map(|(_, encoding)| encoding)
+    }
+
+    /// Encode a raw attribute value with the encoding declared for it, or with the
+    /// default encoding if the schema does not declare one.
+    pub fn encode_attribute(&self, attr_name: &str, raw: &str) -> crate::Result<String> {
+        match self.attr_encoding(attr_name) {
+            Some(encoding) => encoding.encode(raw),
+            None => encode_credential_attribute(raw),
+        }
+    }
 }
 
 // QUESTION: If these must be unique, why not directly store them as a set?
@@ -49,6 +81,14 @@ impl Validatable for Schema {
     fn validate(&self) -> Result<(), ValidationError> {
         self.issuer_id.validate()?;
         self.attr_names.validate()?;
+        for (name, encoding) in &self.attr_encodings {
+            if !self.attr_names.0.contains(name) {
+                return Err(
+                    format!("Attribute encoding declared for unknown attribute: {name}").into(),
+                );
+            }
+            encoding.validate()?;
+        }
         Ok(())
     }
 }
@@ -146,4 +186,52 @@ mod test_schema_validation {
         let schema: Schema = serde_json::from_value(schema_json).unwrap();
         assert!(schema.validate().is_err());
     }
+
+    #[test]
+    fn test_schema_attr_encodings() {
+        let schema_json = json!({
+            "name": "gvt",
+            "version": "1.0",
+            "attrNames": ["name", "birthdate"],
+            "issuerId": "mock:uri",
+            "attrEncodings": {
+                "birthdate": {"type": "date"}
+            }
+        });
+
+        let schema: Schema = serde_json::from_value(schema_json.clone()).unwrap();
+        assert!(schema.validate().is_ok());
+        assert_eq!(
+            schema.attr_encoding("birthdate"),
+            Some(&AttributeEncoding::Date)
+        );
+        assert_eq!(
+            schema.attr_encoding("Birth Date"),
+            Some(&AttributeEncoding::Date)
+        );
+        assert_eq!(schema.attr_encoding("name"), None);
+        assert_eq!(
+            schema.encode_attribute("birthdate", "1970-01-01").unwrap(),
+            "719163"
+        );
+        assert_eq!(schema.encode_attribute("name", "28").unwrap(), "28");
+        assert!(schema.encode_attribute("birthdate", "01/01/1970").is_err());
+        assert_eq!(serde_json::to_value(&schema).unwrap(), schema_json);
+    }
+
+    #[test]
+    fn test_schema_invalid_attr_encodings() {
+        let schema_json = json!({
+            "name": "gvt",
+            "version": "1.0",
+            "attrNames": ["name"],
+            "issuerId": "mock:uri",
+            "attrEncodings": {
+                "birthdate": {"type": "date"}
+            }
+        });
+
+        let schema: Schema = serde_json::from_value(schema_json).unwrap();
+        assert!(schema.validate().is_err());
+    }
 }
@@ -17,6 +17,18 @@ pub static ISSUER_DEPENDENT_VOCABULARY: Lazy<Value> = Lazy::new(|| {
     })
 });
 
+/// Term definitions for the `credentialStatus` entry of revocable credentials
+pub static ANONCREDS_CREDENTIAL_STATUS_CONTEXT: Lazy<Value> = Lazy::new(|| {
+    json!({
+        "AnonCredsCredentialStatusList2023": {
+            "@id": "https://www.w3.org/ns/credentials/issuer-dependent#AnonCredsCredentialStatusList2023",
+            "@context": {
+                "revocationRegistryIndex": "https://www.w3.org/ns/credentials/issuer-dependent#revocationRegistryIndex"
+            }
+        }
+    })
+});
+
 pub(crate) static ANONCREDS_VC_1_1_CONTEXTS: Lazy<Contexts> = Lazy::new(|| {
     Contexts(vec![
         Context::URI(URI::from(W3C_VC_1_1_BASE_CONTEXT)),
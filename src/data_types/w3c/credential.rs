@@ -5,14 +5,17 @@ use std::string::ToString;
 
 use crate::Result;
 use crate::data_types::w3c::VerifiableCredentialSpecVersion;
-use crate::data_types::w3c::constants::ANONCREDS_CREDENTIAL_TYPES;
-use crate::data_types::w3c::context::Contexts;
+use crate::data_types::w3c::constants::{
+    ANONCREDS_CREDENTIAL_STATUS_CONTEXT, ANONCREDS_CREDENTIAL_TYPES,
+};
+use crate::data_types::w3c::context::{Context, Contexts};
 use crate::data_types::w3c::credential_attributes::CredentialSubject;
 use crate::data_types::w3c::proof::{
     CredentialPresentationProofValue, CredentialSignatureProofValue, DataIntegrityProof,
 };
 use crate::data_types::{
     issuer_id::IssuerId,
+    rev_reg_def::RevocationRegistryDefinitionId,
     w3c::{constants::W3C_CREDENTIAL_TYPE, one_or_many::OneOrMany, uri::URI},
 };
 
@@ -40,6 +43,8 @@ pub struct W3CCredential {
     pub issuance_date: Option<IssuanceDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub valid_from: Option<IssuanceDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_status: Option<CredentialStatus>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
@@ -47,6 +52,48 @@ pub struct Types(pub HashSet<String>);
 
 pub type IssuanceDate = DateTime<Utc>;
 
+/// Status entry of a revocable credential pointing to the AnonCreds revocation registry
+///
+/// The same information is contained in the credential proof; this entry makes the
+/// status mechanism discoverable for generic W3C tooling and is cross-checked against
+/// the proof whenever the credential is processed.
+/// The revocation registry index is only present in issued credentials: credentials
+/// derived for a presentation name the registry only, as the index would make the
+/// holder linkable.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CredentialStatus {
+    #[serde(rename = "type")]
+    pub type_: CredentialStatusType,
+    pub id: RevocationRegistryDefinitionId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation_registry_index: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CredentialStatusType {
+    AnonCredsCredentialStatusList2023,
+}
+
+impl CredentialStatus {
+    pub fn new(id: RevocationRegistryDefinitionId, revocation_registry_index: Option<u32>) -> Self {
+        Self {
+            type_: CredentialStatusType::AnonCredsCredentialStatusList2023,
+            id,
+            revocation_registry_index,
+        }
+    }
+
+    /// Build the status entry matching a credential proof, if the credential is revocable
+    pub(crate) fn from_proof(proof: &DataIntegrityProof) -> Result<Option<Self>> {
+        let details = proof.get_credential_proof_details()?;
+        Ok(details
+            .rev_reg_id
+            .map(|rev_reg_id| Self::new(rev_reg_id, details.rev_reg_index)))
+    }
+}
+
 pub type NonAnonCredsDataIntegrityProof = serde_json::Value;
 
 #[allow(clippy::large_enum_variant)]
@@ -78,6 +125,7 @@ impl W3CCredential {
             proof: OneOrMany::Many(vec![CredentialProof::AnonCredsDataIntegrityProof(proof)]),
             valid_from: None,
             id: None,
+            credential_status: None,
         }
     }
 
@@ -95,7 +143,19 @@ impl W3CCredential {
             valid_from: credential.valid_from,
             credential_subject,
             proof: OneOrMany::One(CredentialProof::AnonCredsDataIntegrityProof(proof)),
+            credential_status: None,
+        }
+    }
+
+    /// Set the `credentialStatus` entry from the revocation registry of the credential proof,
+    /// if the credential is revocable, along with the context defining it.
+    pub fn with_credential_status(mut self) -> Result<Self> {
+        self.credential_status = CredentialStatus::from_proof(self.get_data_integrity_proof()?)?;
+        let status_context = Context::Object(ANONCREDS_CREDENTIAL_STATUS_CONTEXT.clone());
+        if self.credential_status.is_some() && !self.context.0.contains(&status_context) {
+            self.context.0.push(status_context);
         }
+        Ok(self)
     }
 
     pub fn version(&self) -> Result<VerifiableCredentialSpecVersion> {
@@ -145,13 +205,44 @@ impl W3CCredential {
             ));
         }
 
+        self.validate_credential_status()
+    }
+
+    /// Check that the `credentialStatus` entry, if present, refers to the revocation
+    /// registry and index contained in the credential proof
+    pub(crate) fn validate_credential_status(&self) -> Result<()> {
+        let Some(status) = self.credential_status.as_ref() else {
+            return Ok(());
+        };
+        let details = self
+            .get_data_integrity_proof()?
+            .get_credential_proof_details()?;
+        if details.rev_reg_id.as_ref() != Some(&status.id) {
+            return Err(err_msg!(
+                "Credential status {} does not match the revocation registry of the credential proof: {:?}",
+                status.id,
+                details.rev_reg_id
+            ));
+        }
+        if status.revocation_registry_index != details.rev_reg_index {
+            return Err(err_msg!(
+                "Credential status index {:?} does not match the credential proof: {:?}",
+                status.revocation_registry_index,
+                details.rev_reg_index
+            ));
+        }
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::W3CCredential;
+    use super::{CredentialStatus, W3CCredential};
+    use crate::data_types::issuer_id::IssuerId;
+    use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
+    use crate::data_types::w3c::credential_attributes::CredentialSubject;
+    use crate::data_types::w3c::proof::{self, DataIntegrityProof};
+    use crate::services::w3c::verifier;
 
     #[test]
     fn serde_w3c_credential() {
@@ -175,4 +266,59 @@ mod tests {
         let res = serde_json::from_value::<W3CCredential>(cred);
         assert!(res.is_err());
     }
+
+    #[test]
+    fn credential_status_must_match_credential_proof() {
+        let cred_json = include_str!("sample_credential.json");
+        let mut cred: W3CCredential =
+            serde_json::from_str(cred_json).expect("Error deserializing w3c credential");
+        cred.validate().expect("Sample credential must be valid");
+
+        // sample credential is not revocable, so any status entry is inconsistent
+        cred.credential_status = Some(CredentialStatus::new(
+            RevocationRegistryDefinitionId::new_unchecked("mock:uri:rev_reg"),
+            Some(1),
+        ));
+        assert!(cred.validate().is_err());
+
+        let out_json = serde_json::to_value(&cred).expect("Error serializing w3c credential");
+        assert_eq!(
+            out_json["credentialStatus"],
+            serde_json::json!({
+                "type": "AnonCredsCredentialStatusList2023",
+                "id": "mock:uri:rev_reg",
+                "revocationRegistryIndex": 1
+            })
+        );
+        let parsed: W3CCredential =
+            serde_json::from_value(out_json).expect("Error deserializing w3c credential");
+        assert_eq!(parsed, cred);
+    }
+
+    #[test]
+    fn with_credential_status_follows_credential_proof() {
+        let proof = DataIntegrityProof::new_credential_presentation_proof(
+            &proof::tests::credential_pres_proof_value(),
+        )
+        .unwrap();
+        let cred = W3CCredential::new(
+            IssuerId::new_unchecked("mock:uri"),
+            CredentialSubject::default(),
+            proof,
+            None,
+        );
+        assert_eq!(cred.credential_status, None);
+        let contexts = cred.context.0.len();
+
+        let cred = cred.with_credential_status().unwrap();
+        let status = cred.credential_status.as_ref().unwrap();
+        assert_eq!(status.id, verifier::tests::revocation_id());
+        assert_eq!(status.revocation_registry_index, None);
+        assert_eq!(cred.context.0.len(), contexts + 1);
+        cred.validate_credential_status().unwrap();
+
+        // the status context is only added once
+        let cred = cred.with_credential_status().unwrap();
+        assert_eq!(cred.context.0.len(), contexts + 1);
+    }
 }
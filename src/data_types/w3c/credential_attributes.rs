@@ -1,3 +1,4 @@
+use crate::data_types::schema::Schema;
 use crate::error::ValidationError;
 use crate::types::{CredentialValues, MakeCredentialValues};
 use crate::utils::validation::Validatable;
@@ -86,22 +87,24 @@ impl CredentialSubject {
         }
     }
 
-    pub(crate) fn encode(&self) -> crate::Result<CredentialValues> {
+    /// Encode the attribute values, using the typed encodings declared by the schema of the
+    /// credential, if any.
+    pub(crate) fn encode(&self, schema: Option<&Schema>) -> crate::Result<CredentialValues> {
         let mut cred_values = MakeCredentialValues::default();
         for (attribute, raw_value) in self.0.iter() {
-            match raw_value {
-                CredentialAttributeValue::String(raw_value) => {
-                    cred_values.add_raw(attribute, raw_value)?
-                }
-                CredentialAttributeValue::Number(raw_value) => {
-                    cred_values.add_raw(attribute, raw_value.to_string())?
-                }
+            let raw_value = match raw_value {
+                CredentialAttributeValue::String(raw_value) => raw_value.to_owned(),
+                CredentialAttributeValue::Number(raw_value) => raw_value.to_string(),
                 value => {
                     return Err(err_msg!(
                         "Encoding is not supported for credential value {:?}",
                         value
                     ));
                 }
+            };
+            match schema.and_then(|schema| schema.attr_encoding(attribute)) {
+                Some(encoding) => cred_values.add_typed(attribute, raw_value, encoding)?,
+                None => cred_values.add_raw(attribute, raw_value)?,
             }
         }
         Ok(cred_values.into())
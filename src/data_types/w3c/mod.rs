@@ -8,6 +8,8 @@ pub mod credential_attributes;
 pub mod one_or_many;
 /// AnonCreds W3C Presentation definition
 pub mod presentation;
+/// DIF Presentation Exchange definition and submission
+pub mod presentation_exchange;
 pub mod proof;
 pub mod uri;
 
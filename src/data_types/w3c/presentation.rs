@@ -52,6 +52,9 @@ impl W3CPresentation {
                 "Credential does not contain w3c presentation type"
             ));
         }
+        for credential in &self.verifiable_credential {
+            credential.validate_credential_status()?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,167 @@
+use serde_json::Value;
+
+/// DIF Presentation Exchange presentation definition
+///
+/// Only the subset of the specification which can be expressed by an AnonCreds
+/// presentation request is modeled, unknown properties are rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PresentationDefinition {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
+    pub input_descriptors: Vec<InputDescriptor>,
+}
+
+/// Requirements on a single credential of the presentation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InputDescriptor {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<Vec<String>>,
+    /// Accepted schemas, as defined by Presentation Exchange v1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Vec<SchemaFilter>>,
+    #[serde(default)]
+    pub constraints: Constraints,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaFilter {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+    /// AnonCreds presentations always limit disclosure to the requested attributes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_disclosure: Option<Optionality>,
+}
+
+/// Constraint on a credential property selected by a JSONPath expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Field {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Alternative JSONPath expressions, the first one supported is used
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<FieldFilter>,
+    /// Prove that the property satisfies the filter without revealing it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<Optionality>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optional: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intent_to_retain: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Optionality {
+    Required,
+    Preferred,
+}
+
+/// JSON Schema subset usable as a field filter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct FieldFilter {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+    pub const_: Option<Value>,
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_minimum: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_maximum: Option<Value>,
+}
+
+/// DIF Presentation Exchange presentation submission, describing which credential of a
+/// presentation satisfies each input descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresentationSubmission {
+    pub id: String,
+    pub definition_id: String,
+    pub descriptor_map: Vec<DescriptorMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DescriptorMapping {
+    pub id: String,
+    pub format: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_nested: Option<Box<DescriptorMapping>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_presentation_definition() {
+        let json = json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "input_descriptors": [{
+                "id": "gvt",
+                "constraints": {
+                    "limit_disclosure": "required",
+                    "fields": [
+                        { "path": ["$.credentialSubject.name"] },
+                        {
+                            "path": ["$.credentialSubject.age"],
+                            "predicate": "required",
+                            "filter": { "type": "number", "exclusiveMinimum": 18 }
+                        }
+                    ]
+                }
+            }]
+        });
+        let definition: PresentationDefinition = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            definition.input_descriptors[0].constraints.fields[1].predicate,
+            Some(Optionality::Required)
+        );
+        assert_eq!(serde_json::to_value(&definition).unwrap(), json);
+    }
+
+    #[test]
+    fn serde_presentation_definition_deny_unknown() {
+        let json = json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "submission_requirements": [{ "rule": "all", "from": "A" }],
+            "input_descriptors": []
+        });
+        let err = serde_json::from_value::<PresentationDefinition>(json).unwrap_err();
+        assert!(err.to_string().contains("submission_requirements"));
+    }
+}
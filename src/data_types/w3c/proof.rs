@@ -7,12 +7,14 @@ use serde::{
     de::{Error, Visitor},
     ser::SerializeSeq,
 };
+use std::collections::HashMap;
 use std::fmt::Debug;
 
 use crate::Result;
+use crate::data_types::attr_encoding::AttributeEncoding;
 use crate::data_types::cred_def::CredentialDefinitionId;
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
-use crate::data_types::schema::SchemaId;
+use crate::data_types::schema::{Schema, SchemaId};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataIntegrityProofType {
@@ -132,6 +134,9 @@ pub struct DataIntegrityProof {
     pub(crate) proof_value: DataIntegrityProofValue,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) challenge: Option<String>,
+    /// Identifier of the verifier the proof is bound to, along with the `challenge`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) domain: Option<String>,
 }
 
 impl DataIntegrityProof {
@@ -147,9 +152,18 @@ impl DataIntegrityProof {
             verification_method,
             proof_value: value.clone().into(),
             challenge,
+            domain: None,
         })
     }
 
+    pub fn challenge(&self) -> Option<&str> {
+        self.challenge.as_deref()
+    }
+
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
     pub(crate) fn new_credential_proof(
         value: &CredentialSignatureProofValue,
     ) -> Result<DataIntegrityProof> {
@@ -272,6 +286,36 @@ pub struct CredentialSignatureProofValue {
     pub rev_reg: Option<RevocationRegistry>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub witness: Option<Witness>,
+    /// Typed encodings the credential values were signed with, or `None` if they are not
+    /// known (e.g. for a credential converted from the legacy form)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attr_encodings: Option<HashMap<String, AttributeEncoding>>,
+}
+
+impl CredentialSignatureProofValue {
+    /// Check that the credential was signed with the encodings declared by its schema, so that
+    /// proofs built from the schema encodings match the signature.
+    pub(crate) fn check_attr_encodings(&self, schema: Option<&Schema>) -> Result<()> {
+        let Some(signed) = &self.attr_encodings else {
+            return Ok(());
+        };
+        let declared = schema.map(|schema| &schema.attr_encodings);
+        let matches = match declared {
+            Some(declared) => signed == declared,
+            None => signed.is_empty(),
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(err_msg!(
+                "Credential for schema {} was signed with attribute encodings {:?}, but {:?} are expected; \
+                 credentials for a schema with typed encodings must be issued and processed with the schema",
+                self.schema_id,
+                signed,
+                declared.cloned().unwrap_or_default(),
+            ))
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -344,7 +388,7 @@ pub(crate) mod tests {
                     },
                     "m2":"2553030889054034879941219523536672152702359185828546810612564355745759663351165380563310203986319611277915826660660011443138240248924364893067083241825560",
                     "revealed_attrs":{
-                        "name":"66682250590915135919393234675423675079281389286836524491448775067034910960723"
+                        "name":"27034640024117331033063128044004318218486816931520886405535659934417438781507"
                     },
                     "v":"241132863422049783305938040060597331735278274539541049316128678268379301866997158072011728743321723078574060931449243960464715113938435991871547190135480379265493203441002211218757120311064385792274455797457074741542288420192538286547871288116110058144080647854995527978708188991483561739974917309498779192480418427060775726652318167442183177955447797995160859302520108340826199956754805286213211181508112097818654928169122460464135690611512133363376553662825967455495276836834812520601471833287810311342575033448652033691127511180098524259451386027266077398672694996373787324223860522678035901333613641370426224798680813171225438770578377781015860719028452471648107174226406996348525110692233661632116547069810544117288754524961349911209241835217711929316799411645465546281445291569655422683908113895340361971530636987203042713656548617543163562701947578529101436799250628979720035967402306966520999250819096598649121167"
                 },
@@ -399,6 +443,7 @@ pub(crate) mod tests {
             signature_correctness_proof: cl_credential_signature_correctness_proof(),
             rev_reg: None,
             witness: None,
+            attr_encodings: None,
         }
     }
 
@@ -471,4 +516,34 @@ pub(crate) mod tests {
             proof.get_presentation_proof().is_ok()
         );
     }
+
+    #[test]
+    fn check_attr_encodings_cases() {
+        let schema = crate::issuer::create_schema_with_attr_encodings(
+            "schema",
+            "1.0",
+            crate::data_types::issuer_id::IssuerId::new_unchecked("mock:uri"),
+            ["name", "birthdate"][..].into(),
+            HashMap::from([("birthdate".to_owned(), AttributeEncoding::Date)]),
+        )
+        .unwrap();
+
+        // encodings of a converted credential are not known
+        let mut proof = credential_signature_proof();
+        proof.check_attr_encodings(Some(&schema)).unwrap();
+
+        // signed with the default encodings
+        proof.attr_encodings = Some(HashMap::new());
+        proof.check_attr_encodings(None).unwrap();
+        let err = proof.check_attr_encodings(Some(&schema)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("must be issued and processed with the schema")
+        );
+
+        // signed with the schema encodings
+        proof.attr_encodings = Some(schema.attr_encodings.clone());
+        proof.check_attr_encodings(Some(&schema)).unwrap();
+        proof.check_attr_encodings(None).unwrap_err();
+    }
 }
@@ -1,5 +1,5 @@
 use super::error::{ErrorCode, catch_error};
-use super::object::{AnoncredsObject, AnoncredsObjectList, ObjectHandle};
+use super::object::{AnoncredsObject, AnoncredsObjectList, ObjectHandle, ToJson};
 use super::util::{FfiList, FfiStrList};
 use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
 use crate::data_types::link_secret::LinkSecret;
@@ -11,10 +11,10 @@ use crate::data_types::schema::{Schema, SchemaId};
 use crate::error::Result;
 use crate::services::prover::create_presentation;
 use crate::services::types::PresentCredentials;
-use crate::services::verifier::verify_presentation;
+use crate::services::verifier::{verify_presentation, verify_presentation_detailed};
 
 use crate::ffi::object::AnyAnoncredsObject;
-use ffi_support::FfiStr;
+use ffi_support::{ByteBuffer, FfiStr};
 use std::collections::HashMap;
 
 impl_anoncreds_object!(Presentation, "Presentation");
@@ -179,6 +179,60 @@ pub extern "C" fn anoncreds_verify_presentation(
     })
 }
 
+/// Verify Presentation and return a detailed verification report
+///
+/// # Params
+/// presentation:                   object handle pointing to presentation
+/// pres_req:                       object handle pointing to presentation request
+/// schemas:                        list of credential schemas
+/// schema_ids:                     list of schemas ids
+/// cred_defs:                      list of credential definitions
+/// cred_def_ids:                   list of credential definitions ids
+/// rev_reg_defs:                   list of revocation definitions
+/// rev_reg_def_ids:                list of revocation definitions ids
+/// rev_status_list:                revocation status list
+/// nonrevoked_interval_override:   not-revoked interval
+/// report_p:                       reference that will contain the verification report as JSON.
+///
+/// # Returns
+/// Error code
+#[unsafe(no_mangle)]
+pub extern "C" fn anoncreds_verify_presentation_detailed(
+    presentation: ObjectHandle,
+    pres_req: ObjectHandle,
+    schemas: FfiList<ObjectHandle>,
+    schema_ids: FfiStrList,
+    cred_defs: FfiList<ObjectHandle>,
+    cred_def_ids: FfiStrList,
+    rev_reg_defs: FfiList<ObjectHandle>,
+    rev_reg_def_ids: FfiStrList,
+    rev_status_list: FfiList<ObjectHandle>,
+    nonrevoked_interval_override: FfiList<FfiNonrevokedIntervalOverride>,
+    report_p: *mut ByteBuffer,
+) -> ErrorCode {
+    catch_error(|| {
+        check_useful_c_ptr!(report_p);
+        let cred_defs = _prepare_cred_defs(cred_defs, cred_def_ids)?;
+        let schemas = _prepare_schemas(schemas, schema_ids)?;
+        let rev_reg_defs = _rev_reg_defs(rev_reg_defs, rev_reg_def_ids)?;
+        let rev_status_lists = _rev_status_list(rev_status_list)?;
+        let map_nonrevoked_interval_override =
+            _nonrevoke_interval_override(nonrevoked_interval_override)?;
+
+        let report = verify_presentation_detailed(
+            presentation.load()?.cast_ref()?,
+            pres_req.load()?.cast_ref()?,
+            &schemas,
+            &cred_defs,
+            rev_reg_defs.as_ref(),
+            rev_status_lists,
+            Some(&map_nonrevoked_interval_override),
+        )?;
+        unsafe { *report_p = ByteBuffer::from_vec(report.to_json()?) };
+        Ok(())
+    })
+}
+
 pub(crate) fn _link_secret(link_secret: FfiStr) -> Result<LinkSecret> {
     let link_secret = link_secret
         .as_opt_str()
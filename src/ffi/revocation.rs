@@ -8,6 +8,7 @@ use crate::data_types::{
         RegistryType, RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate,
     },
 };
+use crate::error::Result;
 use crate::issuer;
 use crate::services::issuer::create_revocation_registry_def;
 use crate::services::prover::create_or_update_revocation_state;
@@ -141,40 +142,100 @@ pub extern "C" fn anoncreds_create_revocation_registry_def(
     reg_def_private_p: *mut ObjectHandle,
 ) -> ErrorCode {
     catch_error(|| {
-        check_useful_c_ptr!(reg_def_p);
-        check_useful_c_ptr!(reg_def_private_p);
-        let tag = tag.as_opt_str().ok_or_else(|| err_msg!("Missing tag"))?;
-        let cred_def_id = cred_def_id
-            .as_opt_str()
-            .ok_or_else(|| err_msg!("Missing cred def id"))?
-            .try_into()?;
-        let rev_reg_type = {
-            let rtype = rev_reg_type
-                .as_opt_str()
-                .ok_or_else(|| err_msg!("Missing registry type"))?;
-            RegistryType::from_str(rtype).map_err(err_map!(Input))?
-        };
         let mut tails_writer = TailsFileWriter::new(tails_dir_path.into_opt_string());
-        let (reg_def, reg_def_private) = create_revocation_registry_def(
-            cred_def.load()?.cast_ref()?,
+        _create_revocation_registry_def(
+            cred_def,
             cred_def_id,
             tag,
             rev_reg_type,
-            max_cred_num
-                .try_into()
-                .map_err(|_| err_msg!("Invalid maximum credential count"))?,
+            max_cred_num,
             &mut tails_writer,
-        )?;
-        let reg_def = ObjectHandle::create(reg_def)?;
-        let reg_def_private = ObjectHandle::create(reg_def_private)?;
-        unsafe {
-            *reg_def_p = reg_def;
-            *reg_def_private_p = reg_def_private;
-        };
-        Ok(())
+            reg_def_p,
+            reg_def_private_p,
+        )
+    })
+}
+
+/// Same as `anoncreds_create_revocation_registry_def`, with the tails location of the
+/// definition set to `tails_location_template` with `{hash}` replaced by the tails hash,
+/// instead of the path of the tails file written under `tails_dir_path`.
+#[unsafe(no_mangle)]
+pub extern "C" fn anoncreds_create_revocation_registry_def_with_tails_location(
+    cred_def: ObjectHandle,
+    cred_def_id: FfiStr,
+    _issuer_id: FfiStr, // leaving it here not to break existing code
+    tag: FfiStr,
+    rev_reg_type: FfiStr,
+    max_cred_num: i64,
+    tails_dir_path: FfiStr,
+    tails_location_template: FfiStr,
+    reg_def_p: *mut ObjectHandle,
+    reg_def_private_p: *mut ObjectHandle,
+) -> ErrorCode {
+    catch_error(|| {
+        let location_template = tails_location_template
+            .into_opt_string()
+            .ok_or_else(|| err_msg!("Missing tails location template"))?;
+        let mut tails_writer = TailsFileWriter::with_location_template(
+            tails_dir_path.into_opt_string(),
+            location_template,
+        );
+        _create_revocation_registry_def(
+            cred_def,
+            cred_def_id,
+            tag,
+            rev_reg_type,
+            max_cred_num,
+            &mut tails_writer,
+            reg_def_p,
+            reg_def_private_p,
+        )
     })
 }
 
+#[allow(clippy::too_many_arguments)]
+fn _create_revocation_registry_def(
+    cred_def: ObjectHandle,
+    cred_def_id: FfiStr,
+    tag: FfiStr,
+    rev_reg_type: FfiStr,
+    max_cred_num: i64,
+    tails_writer: &mut TailsFileWriter,
+    reg_def_p: *mut ObjectHandle,
+    reg_def_private_p: *mut ObjectHandle,
+) -> Result<()> {
+    check_useful_c_ptr!(reg_def_p);
+    check_useful_c_ptr!(reg_def_private_p);
+    let tag = tag.as_opt_str().ok_or_else(|| err_msg!("Missing tag"))?;
+    let cred_def_id = cred_def_id
+        .as_opt_str()
+        .ok_or_else(|| err_msg!("Missing cred def id"))?
+        .try_into()?;
+    let rev_reg_type = {
+        let rtype = rev_reg_type
+            .as_opt_str()
+            .ok_or_else(|| err_msg!("Missing registry type"))?;
+        RegistryType::from_str(rtype).map_err(err_map!(Input))?
+    };
+    let (reg_def, reg_def_private) = create_revocation_registry_def(
+        cred_def.load()?.cast_ref()?,
+        cred_def_id,
+        tag,
+        rev_reg_type,
+        max_cred_num
+            .try_into()
+            .map_err(|_| err_msg!("Invalid maximum credential count"))?,
+        tails_writer,
+    )?;
+    let reg_def = ObjectHandle::create(reg_def)?;
+    let reg_def_private = ObjectHandle::create(reg_def_private)?;
+    unsafe {
+        *reg_def_p = reg_def;
+        *reg_def_private_p = reg_def_private;
+    };
+    Ok(())
+}
+
 impl_anoncreds_object!(RevocationRegistryDefinition, "RevocationRegistryDefinition");
 impl_anoncreds_object_from_json!(
     RevocationRegistryDefinition,
@@ -2,7 +2,7 @@ use crate::data_types::w3c::VerifiableCredentialSpecVersion;
 use crate::data_types::w3c::presentation::W3CPresentation;
 use crate::ffi::credential::_link_secret;
 use crate::ffi::error::{ErrorCode, catch_error};
-use crate::ffi::object::ObjectHandle;
+use crate::ffi::object::{ObjectHandle, ToJson};
 use crate::ffi::presentation::{
     _credentials, _nonrevoke_interval_override, _prepare_cred_defs, _prepare_schemas,
     _present_credentials, _rev_reg_defs, _rev_status_list, FfiCredentialEntry, FfiCredentialProve,
@@ -10,8 +10,8 @@ use crate::ffi::presentation::{
 };
 use crate::ffi::util::{FfiList, FfiStrList};
 use crate::w3c::prover::create_presentation;
-use crate::w3c::verifier::verify_presentation;
-use ffi_support::FfiStr;
+use crate::w3c::verifier::{verify_presentation, verify_presentation_detailed};
+use ffi_support::{ByteBuffer, FfiStr};
 
 impl_anoncreds_object!(W3CPresentation, "W3CPresentation");
 impl_anoncreds_object_from_json!(W3CPresentation, anoncreds_w3c_presentation_from_json);
@@ -125,3 +125,57 @@ pub extern "C" fn anoncreds_verify_w3c_presentation(
         Ok(())
     })
 }
+
+/// Verify W3C styled Presentation and return a detailed verification report
+///
+/// # Params
+/// presentation:                   object handle pointing to presentation
+/// pres_req:                       object handle pointing to presentation request
+/// schemas:                        list of credential schemas
+/// schema_ids:                     list of schemas ids
+/// cred_defs:                      list of credential definitions
+/// cred_def_ids:                   list of credential definitions ids
+/// rev_reg_defs:                   list of revocation definitions
+/// rev_reg_def_ids:                list of revocation definitions ids
+/// rev_status_list:                revocation status list
+/// nonrevoked_interval_override:   not-revoked interval
+/// report_p:                       reference that will contain the verification report as JSON.
+///
+/// # Returns
+/// Error code
+#[unsafe(no_mangle)]
+pub extern "C" fn anoncreds_verify_w3c_presentation_detailed(
+    presentation: ObjectHandle,
+    pres_req: ObjectHandle,
+    schemas: FfiList<ObjectHandle>,
+    schema_ids: FfiStrList,
+    cred_defs: FfiList<ObjectHandle>,
+    cred_def_ids: FfiStrList,
+    rev_reg_defs: FfiList<ObjectHandle>,
+    rev_reg_def_ids: FfiStrList,
+    rev_status_list: FfiList<ObjectHandle>,
+    nonrevoked_interval_override: FfiList<FfiNonrevokedIntervalOverride>,
+    report_p: *mut ByteBuffer,
+) -> ErrorCode {
+    catch_error(|| {
+        check_useful_c_ptr!(report_p);
+        let cred_defs = _prepare_cred_defs(cred_defs, cred_def_ids)?;
+        let schemas = _prepare_schemas(schemas, schema_ids)?;
+        let rev_reg_defs = _rev_reg_defs(rev_reg_defs, rev_reg_def_ids)?;
+        let rev_status_lists = _rev_status_list(rev_status_list)?;
+        let map_nonrevoked_interval_override =
+            _nonrevoke_interval_override(nonrevoked_interval_override)?;
+
+        let report = verify_presentation_detailed(
+            presentation.load()?.cast_ref()?,
+            pres_req.load()?.cast_ref()?,
+            &schemas,
+            &cred_defs,
+            rev_reg_defs.as_ref(),
+            rev_status_lists,
+            Some(&map_nonrevoked_interval_override),
+        )?;
+        unsafe { *report_p = ByteBuffer::from_vec(report.to_json()?) };
+        Ok(())
+    })
+}
@@ -9,17 +9,37 @@ use crate::data_types::{
     credential::CredentialValues,
     link_secret::LinkSecret,
     nonce::Nonce,
-    pres_request::{NonRevokedInterval, PresentationRequestPayload},
+    pres_request::{NonRevokedInterval, PredicateInfo, PresentationRequestPayload, RangeInfo},
 };
 use crate::error::Result;
-use anoncreds_clsignatures::Predicate;
+use anoncreds_clsignatures::{Predicate, PredicateType};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 
+/// Size in bytes of derived nonces, matching the 80 bits of `verifier::generate_nonce`
+pub(crate) const DERIVED_NONCE_SIZE: usize = 10;
+
+/// Domain separation tag of presentation proof nonces bound to a verifier domain
+const VERIFIER_DOMAIN_NONCE_TAG: &[u8] = b"anoncreds-verifier-domain-nonce";
+
 pub fn attr_common_view(attr: &str) -> String {
     attr.replace(' ', "").to_lowercase()
 }
 
+/// Derive a nonce from a domain separation tag and a list of values.
+///
+/// Values are length prefixed, so they can not be shifted between each other.
+pub(crate) fn derive_nonce(tag: &[u8], values: &[&str]) -> Result<Nonce> {
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    for value in values {
+        hasher.update((value.len() as u64).to_be_bytes());
+        hasher.update(value.as_bytes());
+    }
+    let digest = hasher.finalize();
+    Ok(Nonce::from_bytes(&digest[..DERIVED_NONCE_SIZE])?)
+}
+
 pub fn build_credential_schema(schema: &Schema) -> Result<CredentialSchema> {
     trace!("build_credential_schema >>> schema: {:?}", schema);
 
@@ -87,6 +107,75 @@ pub fn encode_credential_attribute(raw_value: &str) -> Result<String> {
     }
 }
 
+pub(crate) fn build_predicate(info: &PredicateInfo) -> Predicate {
+    Predicate {
+        attr_name: info.name.clone(),
+        p_type: info.p_type.clone().into(),
+        value: info.p_value,
+    }
+}
+
+/// Check that the encoded value of a credential attribute can be used to prove a predicate.
+pub(crate) fn check_predicate_satisfiable(
+    predicate: &Predicate,
+    credential_values: &CredentialValues,
+) -> Result<()> {
+    let attr_name = attr_common_view(&predicate.attr_name);
+    let Some((_, attr_values)) = credential_values
+        .0
+        .iter()
+        .find(|(name, _)| attr_common_view(name) == attr_name)
+    else {
+        return Err(err_msg!(
+            "Attribute \"{}\" not found in credential",
+            predicate.attr_name
+        ));
+    };
+
+    check_predicate_value(predicate, &attr_values.encoded)
+}
+
+/// Check that an encoded attribute value can be used to prove a predicate.
+pub(crate) fn check_predicate_value(predicate: &Predicate, encoded: &str) -> Result<()> {
+    let value = encoded.parse::<i64>().map_err(|_| {
+        err_msg!(
+            "Encoded value of attribute \"{}\" is not an integer and cannot be used in a predicate",
+            predicate.attr_name
+        )
+    })?;
+    if i32::try_from(value).is_err() {
+        return Err(err_msg!(
+            "Encoded value of attribute \"{}\" is outside the 32-bit range supported by predicate proofs",
+            predicate.attr_name
+        ));
+    }
+
+    let bound = i64::from(predicate.value);
+    let delta = match predicate.p_type {
+        PredicateType::GE => value - bound,
+        PredicateType::GT => value - bound - 1,
+        PredicateType::LE => bound - value,
+        PredicateType::LT => bound - value - 1,
+    };
+    if delta < 0 {
+        return Err(err_msg!(
+            "Attribute \"{}\" does not satisfy predicate {:?} {}",
+            predicate.attr_name,
+            predicate.p_type,
+            predicate.value
+        ));
+    }
+    if i32::try_from(delta).is_err() {
+        return Err(err_msg!(
+            "Difference between attribute \"{}\" and predicate value {} exceeds the 32-bit range supported by predicate proofs",
+            predicate.attr_name,
+            predicate.value
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn build_sub_proof_request(
     attrs_for_credential: &Vec<String>,
     predicates_for_credential: &Vec<Predicate>,
@@ -194,6 +283,18 @@ pub fn get_requested_non_revoked_interval(
 }
 
 impl PresentationRequestPayload {
+    /// Nonce of the presentation proof, which is the request nonce bound to the
+    /// `verifier_domain` when present.
+    pub(crate) fn proof_nonce(&self) -> Result<Nonce> {
+        match &self.verifier_domain {
+            Some(verifier_domain) => derive_nonce(
+                VERIFIER_DOMAIN_NONCE_TAG,
+                &[self.nonce.as_ref(), verifier_domain],
+            ),
+            None => Ok(self.nonce.try_clone()?),
+        }
+    }
+
     pub(crate) fn get_requested_attributes(
         &self,
         referents: &HashSet<String>,
@@ -234,6 +335,24 @@ impl PresentationRequestPayload {
         Ok((attributes, non_revoked_interval))
     }
 
+    /// Check that the predicates presented by one credential include both bounds of any
+    /// requested range they are part of.
+    pub(crate) fn check_requested_ranges(&self, referents: &HashSet<String>) -> Result<()> {
+        for referent in referents {
+            if let Some((range_referent, _)) = self.range_for_predicate(referent) {
+                let (lower_referent, upper_referent) =
+                    RangeInfo::predicate_referents(range_referent);
+                if !referents.contains(&lower_referent) || !referents.contains(&upper_referent) {
+                    return Err(err_msg!(
+                        "Both bounds of requested range \"{}\" must be proven by the same credential",
+                        range_referent
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn get_requested_predicates(
         &self,
         referents: &HashSet<String>,
@@ -257,11 +376,7 @@ impl PresentationRequestPayload {
                     None => non_revoked_interval = Some(int.clone()),
                 }
             }
-            predicates.push(Predicate {
-                attr_name: requested.name,
-                p_type: requested.p_type.into(),
-                value: requested.p_value,
-            });
+            predicates.push(build_predicate(&requested));
         }
 
         trace!(
@@ -300,3 +415,111 @@ impl RequestedProof {
         referents
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::credential::AttributeValues;
+    use crate::data_types::pres_request::PresentationRequest;
+
+    fn _cred_values(encoded: &str) -> CredentialValues {
+        CredentialValues(HashMap::from([(
+            "Balance".to_owned(),
+            AttributeValues {
+                raw: encoded.to_owned(),
+                encoded: encoded.to_owned(),
+            },
+        )]))
+    }
+
+    fn _predicate(p_type: PredicateType, value: i32) -> Predicate {
+        Predicate {
+            attr_name: "balance".to_owned(),
+            p_type,
+            value,
+        }
+    }
+
+    #[test]
+    fn check_requested_ranges_requires_both_bounds() {
+        let pres_req: PresentationRequest = serde_json::from_value(json!({
+            "nonce": "123456",
+            "name": "name",
+            "version": "1.0",
+            "requested_ranges": {
+                "working_age": { "name": "age", "lower": 18, "upper": 65 }
+            }
+        }))
+        .unwrap();
+        let pres_req = pres_req.value();
+        let (lower, upper) = RangeInfo::predicate_referents("working_age");
+
+        pres_req
+            .check_requested_ranges(&HashSet::from([lower.clone(), upper]))
+            .unwrap();
+        pres_req
+            .check_requested_ranges(&HashSet::from([lower]))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn proof_nonce_is_bound_to_verifier_domain() {
+        let pres_req_for = |verifier_domain: Option<&str>| -> PresentationRequest {
+            serde_json::from_value(json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "1.0",
+                "requested_attributes": { "attr1": { "name": "name" } },
+                "verifier_domain": verifier_domain
+            }))
+            .unwrap()
+        };
+
+        let proof_nonce = pres_req_for(None).value().proof_nonce().unwrap();
+        assert_eq!(&*proof_nonce, "123456");
+
+        let proof_nonce = pres_req_for(Some("https://verifier.example.org"))
+            .value()
+            .proof_nonce()
+            .unwrap();
+        assert_ne!(&*proof_nonce, "123456");
+        assert_eq!(
+            proof_nonce,
+            pres_req_for(Some("https://verifier.example.org"))
+                .value()
+                .proof_nonce()
+                .unwrap()
+        );
+        assert_ne!(
+            proof_nonce,
+            pres_req_for(Some("https://other.example.org"))
+                .value()
+                .proof_nonce()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn check_predicate_satisfiable_works() {
+        let values = _cred_values("2000");
+        check_predicate_satisfiable(&_predicate(PredicateType::GE, 2000), &values).unwrap();
+        check_predicate_satisfiable(&_predicate(PredicateType::LT, 2001), &values).unwrap();
+        check_predicate_satisfiable(&_predicate(PredicateType::GT, 2000), &values).unwrap_err();
+        check_predicate_satisfiable(&_predicate(PredicateType::LE, 1999), &values).unwrap_err();
+    }
+
+    #[test]
+    fn check_predicate_satisfiable_rejects_unsupported_values() {
+        let predicate = _predicate(PredicateType::GE, 0);
+        // hashed attribute value
+        check_predicate_satisfiable(&predicate, &_cred_values("abc")).unwrap_err();
+        // encoded value outside i32
+        check_predicate_satisfiable(&predicate, &_cred_values("4102444800")).unwrap_err();
+        // delta overflows i32
+        check_predicate_satisfiable(
+            &_predicate(PredicateType::GE, i32::MIN),
+            &_cred_values(&i32::MAX.to_string()),
+        )
+        .unwrap_err();
+    }
+}
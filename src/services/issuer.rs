@@ -1,4 +1,5 @@
 use crate::cl::{Issuer, RevocationRegistry as CryptoRevocationRegistry};
+use crate::data_types::attr_encoding::AttributeEncoding;
 use crate::data_types::cred_def::CredentialDefinitionId;
 use crate::data_types::issuer_id::IssuerId;
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
@@ -13,13 +14,20 @@ use crate::error::{Error, ErrorKind, Result};
 use crate::services::helpers::{
     build_credential_schema, build_credential_values, build_non_credential_schema,
 };
-use crate::types::{CredentialDefinitionConfig, CredentialRevocationConfig};
+use crate::types::{
+    CredentialBatch, CredentialBatchItem, CredentialBatchRevocationConfig,
+    CredentialDefinitionConfig, CredentialRevocationConfig, RegistryPublication,
+    RevocationRegistrySetConfig, RevocationStatusListAudit,
+};
 use crate::utils::validation::Validatable;
-use anoncreds_clsignatures::{SignatureCorrectnessProof, Witness};
+
+#[cfg(feature = "w3c")]
+use crate::data_types::w3c::credential::W3CCredential;
+use anoncreds_clsignatures::{CredentialPublicKey, SignatureCorrectnessProof, Witness};
 use bitvec::bitvec;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 
-use super::tails::TailsWriter;
+use super::tails::{TailsWriter, verify_tails_file};
 use super::types::{
     AttributeNames, Credential, CredentialDefinitionPrivate, CredentialKeyCorrectnessProof,
     CredentialOffer, CredentialRequest, CredentialValues, RegistryType,
@@ -53,10 +61,52 @@ pub fn create_schema(
     schema_version: &str,
     issuer_id: IssuerId,
     attr_names: AttributeNames,
+) -> Result<Schema> {
+    create_schema_with_attr_encodings(
+        schema_name,
+        schema_version,
+        issuer_id,
+        attr_names,
+        HashMap::new(),
+    )
+}
+
+/// Create an Anoncreds schema which declares typed encodings for some of its attributes
+///
+/// Attributes listed in `attr_encodings` must be encoded with
+/// [`MakeCredentialValues::add_typed`](crate::types::MakeCredentialValues::add_typed) when
+/// issuing, which [`create_credential_with_schema`] enforces, and verifiers check that
+/// revealed values round-trip through the declared encoding.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::data_types::attr_encoding::AttributeEncoding;
+/// use anoncreds::data_types::issuer_id::IssuerId;
+/// use std::collections::HashMap;
+///
+/// let attribute_names: &[&str] = &["name", "birthdate"];
+/// let attr_encodings = HashMap::from([("birthdate".to_owned(), AttributeEncoding::Date)]);
+///
+/// let issuer_id = IssuerId::new("did:web:xyz").expect("Invalid issuer ID");
+///
+/// let schema = issuer::create_schema_with_attr_encodings("schema name",
+///                                    "1.0", issuer_id,
+///                                    attribute_names.into(),
+///                                    attr_encodings,
+///                                    ).expect("Unable to create schema");
+/// ```
+pub fn create_schema_with_attr_encodings(
+    schema_name: &str,
+    schema_version: &str,
+    issuer_id: IssuerId,
+    attr_names: AttributeNames,
+    attr_encodings: HashMap<String, AttributeEncoding>,
 ) -> Result<Schema> {
     trace!(
-        "create_schema >>> schema_name: {}, schema_version: {}, attr_names: {:?}",
-        schema_name, schema_version, attr_names,
+        "create_schema >>> schema_name: {}, schema_version: {}, attr_names: {:?}, attr_encodings: {:?}",
+        schema_name, schema_version, attr_names, attr_encodings,
     );
 
     let schema = Schema {
@@ -64,6 +114,7 @@ pub fn create_schema(
         version: schema_version.to_string(),
         issuer_id,
         attr_names,
+        attr_encodings,
     };
 
     schema.validate()?;
@@ -564,6 +615,122 @@ pub fn update_revocation_status_list(
     Ok(new_list)
 }
 
+/// Check that a revocation status list is consistent with its revocation registry
+///
+/// The accumulator is recomputed from the revocation states in the list, starting from the
+/// initial state of the registry, and compared with the accumulator of the list. The tails
+/// file at `tails_path` is checked against the registry definition, as holders derive their
+/// witnesses from it.
+///
+/// A list whose accumulator does not match can be repaired with
+/// `rebuild_revocation_status_list`.
+pub fn audit_revocation_status_list(
+    cred_def: &CredentialDefinition,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_reg_priv: &RevocationRegistryDefinitionPrivate,
+    tails_path: &str,
+    rev_status_list: &RevocationStatusList,
+    issuance_by_default: bool,
+) -> Result<RevocationStatusListAudit> {
+    trace!(
+        "audit_revocation_status_list >>> rev_reg_def: {:?}, tails_path: {:?}, rev_status_list: {:?}, issuance_by_default: {:?}",
+        rev_reg_def, tails_path, rev_status_list, issuance_by_default
+    );
+
+    let expected = expected_revocation_registry(
+        cred_def,
+        rev_reg_def,
+        rev_reg_priv,
+        rev_status_list,
+        issuance_by_default,
+    )?;
+    let current: Option<CryptoRevocationRegistry> = rev_status_list.into();
+    let tails_valid = match verify_tails_file(tails_path, rev_reg_def) {
+        Ok(()) => true,
+        Err(err) if err.kind() == ErrorKind::InvalidState => false,
+        Err(err) => return Err(err),
+    };
+
+    let audit = RevocationStatusListAudit {
+        accum: current.map(|rev_reg| rev_reg.accum),
+        expected_accum: expected.accum,
+        tails_valid,
+    };
+
+    trace!("audit_revocation_status_list <<< audit: {:?}", audit);
+
+    Ok(audit)
+}
+
+/// Rebuild a revocation status list with the accumulator recomputed from its revocation
+/// states
+///
+/// The revocation states are taken as the source of truth. The rebuilt list has to be
+/// published to replace the inconsistent one.
+pub fn rebuild_revocation_status_list(
+    cred_def: &CredentialDefinition,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_reg_priv: &RevocationRegistryDefinitionPrivate,
+    rev_status_list: &RevocationStatusList,
+    issuance_by_default: bool,
+    timestamp: Option<u64>,
+) -> Result<RevocationStatusList> {
+    let rev_reg = expected_revocation_registry(
+        cred_def,
+        rev_reg_def,
+        rev_reg_priv,
+        rev_status_list,
+        issuance_by_default,
+    )?;
+    let mut new_list = rev_status_list.clone();
+    new_list.update(Some(rev_reg), None, None, timestamp)?;
+    Ok(new_list)
+}
+
+/// Replay the revocation states of a list onto the initial state of its registry
+fn expected_revocation_registry(
+    cred_def: &CredentialDefinition,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_reg_priv: &RevocationRegistryDefinitionPrivate,
+    rev_status_list: &RevocationStatusList,
+    issuance_by_default: bool,
+) -> Result<CryptoRevocationRegistry> {
+    let max_cred_num = rev_reg_def.value.max_cred_num;
+    let state = rev_status_list.state();
+    if state.len() != max_cred_num as usize {
+        return Err(err_msg!(
+            "Revocation Status List size {} does not match the registry size {}",
+            state.len(),
+            max_cred_num
+        ));
+    }
+
+    let cred_pub_key = cred_def.get_public_key()?;
+    let mut rev_reg = CryptoRevocationRegistry::initial_state(
+        &cred_pub_key,
+        &rev_reg_priv.value,
+        max_cred_num,
+        issuance_by_default,
+    )?;
+    // Index 0 is not accepted by the CL accumulator, so its state never affects it
+    let (issued, revoked) = if issuance_by_default {
+        let revoked = state.iter_ones().filter(|&i| i > 0).map(|i| i as u32);
+        (BTreeSet::new(), revoked.collect())
+    } else {
+        let issued = state.iter_zeros().filter(|&i| i > 0).map(|i| i as u32);
+        (issued.collect(), BTreeSet::new())
+    };
+    Issuer::update_revocation_registry(
+        &mut rev_reg,
+        max_cred_num,
+        issued,
+        revoked,
+        &cred_pub_key,
+        &rev_reg_priv.value,
+    )?;
+    Ok(rev_reg)
+}
+
 /// Create an Anoncreds credential offer according to the [Anoncreds v1.0 specification -
 /// Credential Offer](https://hyperledger.github.io/anoncreds-spec/#credential-offer)
 ///
@@ -633,6 +800,8 @@ pub fn create_credential_offer(
 /// Credential](https://hyperledger.github.io/anoncreds-spec/#issue-credential)
 ///
 /// This object can be send to a holder which means that the credential is issued to that entity.
+/// The values are not checked against typed attribute encodings; use
+/// [`create_credential_with_schema`] for a schema which declares them.
 ///
 /// # Example
 ///
@@ -745,6 +914,765 @@ pub fn create_credential(
     Ok(credential)
 }
 
+/// Create an Anoncreds credential for a schema which declares typed encodings
+///
+/// Works as [`create_credential`], but first checks every value against the encoding its
+/// attribute declares in `schema`: the raw value must parse for that encoding, and the
+/// encoded value must be the one produced by it, as with
+/// [`MakeCredentialValues::add_typed`](crate::types::MakeCredentialValues::add_typed).
+/// Values of attributes without a declared encoding are not checked.
+#[allow(clippy::too_many_arguments)]
+pub fn create_credential_with_schema(
+    cred_def: &CredentialDefinition,
+    cred_def_private: &CredentialDefinitionPrivate,
+    schema: &Schema,
+    cred_offer: &CredentialOffer,
+    cred_request: &CredentialRequest,
+    cred_values: CredentialValues,
+    revocation_config: Option<CredentialRevocationConfig>,
+) -> Result<Credential> {
+    check_typed_credential_values(schema, &cred_values)?;
+
+    create_credential(
+        cred_def,
+        cred_def_private,
+        cred_offer,
+        cred_request,
+        cred_values,
+        revocation_config,
+    )
+}
+
+/// Check that credential values are encoded with the typed encodings declared by the schema.
+fn check_typed_credential_values(schema: &Schema, cred_values: &CredentialValues) -> Result<()> {
+    for (attr_name, values) in &cred_values.0 {
+        let Some(encoding) = schema.attr_encoding(attr_name) else {
+            continue;
+        };
+        let encoded = encoding.encode(&values.raw).map_err(|err| {
+            err_msg!(
+                "Value for attribute \"{}\" is not a valid {} value: {}",
+                attr_name,
+                encoding,
+                err
+            )
+        })?;
+        if encoded != values.encoded {
+            return Err(err_msg!(
+                "Value for attribute \"{}\" is not encoded with its declared {} encoding",
+                attr_name,
+                encoding
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Create a batch of Anoncreds credentials for one credential definition
+///
+/// The public key is derived from the credential definition once for the whole batch.
+/// When `revocation_config` is given, every credential is issued in its revocation registry:
+/// items without a revocation index are assigned the lowest free index from
+/// `first_idx` onwards, skipping indices requested by other items. Revocation indices start
+/// at 1 and must be below the size of the revocation status list; a `first_idx` or a
+/// requested index outside of this range fails the whole batch. All credentials are
+/// signed against the given revocation status list, which is then updated once for all of
+/// them.
+///
+/// The outcome of each item is reported separately, so a failing item does not prevent the
+/// rest of the batch from being issued. Only successfully issued credentials are marked
+/// in the resulting revocation status list.
+pub fn create_credentials_batch(
+    cred_def: &CredentialDefinition,
+    cred_def_private: &CredentialDefinitionPrivate,
+    items: Vec<CredentialBatchItem>,
+    revocation_config: Option<CredentialBatchRevocationConfig>,
+    timestamp: Option<u64>,
+) -> Result<CredentialBatch> {
+    trace!(
+        "create_credentials_batch >>> cred_def: {:?}, cred_def_private: {:?}, items: {:?}, revocation_config: {:?}, timestamp: {:?}",
+        cred_def,
+        secret!(&cred_def_private),
+        items,
+        revocation_config,
+        timestamp,
+    );
+
+    let issuer = CLCredentialIssuer::new(cred_def, cred_def_private);
+    let cred_public_key = issuer.public_key()?;
+
+    let requested: BTreeSet<u32> = items.iter().filter_map(|item| item.registry_idx).collect();
+    // Index 0 is not accepted by the CL accumulator, and the revocation status list holds
+    // one entry per index
+    let idx_range = revocation_config.as_ref().map(|config| {
+        let end = u32::try_from(config.status_list.state().len()).unwrap_or(u32::MAX);
+        1..end
+    });
+    if let (Some(config), Some(idx_range)) = (&revocation_config, &idx_range) {
+        if !idx_range.contains(&config.first_idx) {
+            return Err(err_msg!(
+                "First revocation index {} is outside of the revocation registry range {:?}",
+                config.first_idx,
+                idx_range
+            ));
+        }
+        if let Some(idx) = requested.iter().find(|idx| !idx_range.contains(idx)) {
+            return Err(err_msg!(
+                "Revocation index {} is outside of the revocation registry range {:?}",
+                idx,
+                idx_range
+            ));
+        }
+    }
+    let mut assigned = BTreeSet::new();
+    let mut next_idx = revocation_config.as_ref().map(|config| config.first_idx);
+
+    let mut issued = BTreeSet::new();
+    let mut credentials = Vec::with_capacity(items.len());
+    for item in items {
+        let registry_idx = match (&revocation_config, item.registry_idx) {
+            (None, Some(_)) => Err(err_msg!(
+                "Revocation index given without a revocation registry"
+            )),
+            (None, None) => Ok(None),
+            (Some(_), Some(idx)) => {
+                if assigned.insert(idx) {
+                    Ok(Some(idx))
+                } else {
+                    Err(err_msg!("Revocation index {} is used more than once", idx))
+                }
+            }
+            (Some(_), None) => {
+                let idx_range = idx_range
+                    .as_ref()
+                    .expect("range of the revocation registry");
+                let mut idx = next_idx;
+                while idx.is_some_and(|idx| requested.contains(&idx) || assigned.contains(&idx)) {
+                    idx = idx.and_then(|idx| idx.checked_add(1));
+                }
+                match idx.filter(|idx| idx_range.contains(idx)) {
+                    Some(idx) => {
+                        next_idx = idx.checked_add(1);
+                        assigned.insert(idx);
+                        Ok(Some(idx))
+                    }
+                    None => Err(err_msg!("No free index left in the revocation registry")),
+                }
+            }
+        };
+
+        let credential = registry_idx.and_then(|registry_idx| {
+            let item_revocation_config =
+                revocation_config
+                    .as_ref()
+                    .zip(registry_idx)
+                    .map(|(config, registry_idx)| CredentialRevocationConfig {
+                        reg_def: config.reg_def,
+                        reg_def_private: config.reg_def_private,
+                        status_list: config.status_list,
+                        registry_idx,
+                    });
+            let (signature, signature_correctness_proof, rev_reg_id, rev_reg, witness) = issuer
+                .create_credential_with_key(
+                    &cred_public_key,
+                    item.cred_offer,
+                    item.cred_request,
+                    &item.cred_values,
+                    item_revocation_config,
+                )?;
+            if let Some(registry_idx) = registry_idx {
+                issued.insert(registry_idx);
+            }
+            Ok(Credential {
+                schema_id: item.cred_offer.schema_id.clone(),
+                cred_def_id: item.cred_offer.cred_def_id.clone(),
+                rev_reg_id,
+                values: item.cred_values,
+                signature,
+                signature_correctness_proof,
+                rev_reg,
+                witness,
+            })
+        });
+        credentials.push(credential);
+    }
+
+    let status_list = revocation_config
+        .map(|config| {
+            update_revocation_status_list(
+                cred_def,
+                config.reg_def,
+                config.reg_def_private,
+                config.status_list,
+                Some(issued),
+                None,
+                timestamp,
+            )
+        })
+        .transpose()?;
+
+    let batch = CredentialBatch {
+        credentials,
+        status_list,
+    };
+
+    trace!("create_credentials_batch <<< batch: {:?}", secret!(&batch));
+
+    Ok(batch)
+}
+
+/// Keeps track of a revocation registry on behalf of an issuer.
+///
+/// The manager owns the revocation registry definition, its private key and the current
+/// revocation status list, and allocates registry indices so that no index is handed out
+/// twice. Its complete state can be serialized to JSON and restored later on.
+///
+/// Operations which change the revocation status list return the updated list, which has to
+/// be published for holders and verifiers to pick it up.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", try_from = "RevocationRegistryManagerParts")]
+pub struct RevocationRegistryManager {
+    rev_reg_def_id: RevocationRegistryDefinitionId,
+    rev_reg_def: RevocationRegistryDefinition,
+    rev_reg_def_private: RevocationRegistryDefinitionPrivate,
+    status_list: RevocationStatusList,
+    next_index: u32,
+}
+
+// Restored state goes through `RevocationRegistryManager::from_parts` to be validated
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevocationRegistryManagerParts {
+    rev_reg_def_id: RevocationRegistryDefinitionId,
+    rev_reg_def: RevocationRegistryDefinition,
+    rev_reg_def_private: RevocationRegistryDefinitionPrivate,
+    status_list: RevocationStatusList,
+    next_index: u32,
+}
+
+impl TryFrom<RevocationRegistryManagerParts> for RevocationRegistryManager {
+    type Error = Error;
+
+    fn try_from(parts: RevocationRegistryManagerParts) -> Result<Self> {
+        Self::from_parts(
+            parts.rev_reg_def_id,
+            parts.rev_reg_def,
+            parts.rev_reg_def_private,
+            parts.status_list,
+            parts.next_index,
+        )
+    }
+}
+
+impl RevocationRegistryManager {
+    // Index 0 is not accepted by the CL accumulator
+    const FIRST_INDEX: u32 = 1;
+
+    /// Create a new revocation registry along with its initial revocation status list
+    #[allow(clippy::too_many_arguments)]
+    pub fn create<TW>(
+        cred_def: &CredentialDefinition,
+        cred_def_id: CredentialDefinitionId,
+        rev_reg_def_id: RevocationRegistryDefinitionId,
+        tag: &str,
+        max_cred_num: u32,
+        issuance_by_default: bool,
+        timestamp: Option<u64>,
+        tails_writer: &mut TW,
+    ) -> Result<Self>
+    where
+        TW: TailsWriter,
+    {
+        let (rev_reg_def, rev_reg_def_private) = create_revocation_registry_def(
+            cred_def,
+            cred_def_id,
+            tag,
+            RegistryType::CL_ACCUM,
+            max_cred_num,
+            tails_writer,
+        )?;
+        let status_list = create_revocation_status_list(
+            cred_def,
+            rev_reg_def_id.clone(),
+            &rev_reg_def,
+            &rev_reg_def_private,
+            issuance_by_default,
+            timestamp,
+        )?;
+        Self::from_parts(
+            rev_reg_def_id,
+            rev_reg_def,
+            rev_reg_def_private,
+            status_list,
+            Self::FIRST_INDEX,
+        )
+    }
+
+    /// Manage an existing revocation registry, `next_index` being the first index which
+    /// has not been allocated to a credential yet
+    pub fn from_parts(
+        rev_reg_def_id: RevocationRegistryDefinitionId,
+        rev_reg_def: RevocationRegistryDefinition,
+        rev_reg_def_private: RevocationRegistryDefinitionPrivate,
+        status_list: RevocationStatusList,
+        next_index: u32,
+    ) -> Result<Self> {
+        if status_list.id().as_ref() != Some(&rev_reg_def_id) {
+            return Err(err_msg!(
+                "Revocation status list does not belong to revocation registry {}",
+                rev_reg_def_id
+            ));
+        }
+        if status_list.state().len() != rev_reg_def.value.max_cred_num as usize {
+            return Err(err_msg!(
+                "Revocation status list size does not match the revocation registry definition"
+            ));
+        }
+        if next_index < Self::FIRST_INDEX || next_index > rev_reg_def.value.max_cred_num {
+            return Err(err_msg!(
+                "Invalid next revocation registry index {}",
+                next_index
+            ));
+        }
+        Ok(Self {
+            rev_reg_def_id,
+            rev_reg_def,
+            rev_reg_def_private,
+            status_list,
+            next_index,
+        })
+    }
+
+    #[must_use]
+    pub const fn rev_reg_def_id(&self) -> &RevocationRegistryDefinitionId {
+        &self.rev_reg_def_id
+    }
+
+    #[must_use]
+    pub const fn rev_reg_def(&self) -> &RevocationRegistryDefinition {
+        &self.rev_reg_def
+    }
+
+    #[must_use]
+    pub const fn status_list(&self) -> &RevocationStatusList {
+        &self.status_list
+    }
+
+    /// Number of credentials the registry is able to hold
+    #[must_use]
+    pub const fn capacity(&self) -> u32 {
+        self.rev_reg_def
+            .value
+            .max_cred_num
+            .saturating_sub(Self::FIRST_INDEX)
+    }
+
+    /// Number of indices allocated to credentials so far
+    #[must_use]
+    pub const fn issued_count(&self) -> u32 {
+        self.next_index.saturating_sub(Self::FIRST_INDEX)
+    }
+
+    /// Number of credentials which can still be issued from the registry
+    #[must_use]
+    pub const fn remaining(&self) -> u32 {
+        self.capacity().saturating_sub(self.issued_count())
+    }
+
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Issue a credential from the registry using the next unused index.
+    ///
+    /// If the registry was created with `issuance_by_default` set to `false`, the index is
+    /// also marked as issued in the revocation status list, which has to be published again.
+    pub fn issue_credential(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        cred_def_private: &CredentialDefinitionPrivate,
+        cred_offer: &CredentialOffer,
+        cred_request: &CredentialRequest,
+        cred_values: CredentialValues,
+        timestamp: Option<u64>,
+    ) -> Result<Credential> {
+        self.issue_credential_with(cred_def, timestamp, |revocation_config| {
+            create_credential(
+                cred_def,
+                cred_def_private,
+                cred_offer,
+                cred_request,
+                cred_values,
+                Some(revocation_config),
+            )
+        })
+    }
+
+    /// Issue a credential of any format from the registry using the next unused index.
+    ///
+    /// `create` receives the revocation configuration to sign the credential with, the
+    /// index is only consumed when it succeeds.
+    pub fn issue_credential_with<T>(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        timestamp: Option<u64>,
+        create: impl FnOnce(CredentialRevocationConfig) -> Result<T>,
+    ) -> Result<T> {
+        if self.is_full() {
+            return Err(err_msg!(
+                RevocationRegistryFull,
+                "Revocation registry {} is full",
+                self.rev_reg_def_id
+            ));
+        }
+        let registry_idx = self.next_index;
+        let credential = create(CredentialRevocationConfig {
+            reg_def: &self.rev_reg_def,
+            reg_def_private: &self.rev_reg_def_private,
+            status_list: &self.status_list,
+            registry_idx,
+        })?;
+        self.next_index += 1;
+
+        // Issuance on demand, the index is revoked until the credential is issued
+        if self.status_list.get(registry_idx as usize) == Some(true) {
+            self.status_list = update_revocation_status_list(
+                cred_def,
+                &self.rev_reg_def,
+                &self.rev_reg_def_private,
+                &self.status_list,
+                Some(BTreeSet::from([registry_idx])),
+                None,
+                timestamp,
+            )?;
+        }
+
+        Ok(credential)
+    }
+
+    /// Revoke the credentials issued at the given indices, returning the updated revocation
+    /// status list
+    pub fn revoke(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        indices: BTreeSet<u32>,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        if let Some(idx) = indices
+            .iter()
+            .find(|&&idx| idx < Self::FIRST_INDEX || idx >= self.next_index)
+        {
+            return Err(err_msg!(
+                "Revocation registry index {} has not been allocated",
+                idx
+            ));
+        }
+        self.status_list = update_revocation_status_list(
+            cred_def,
+            &self.rev_reg_def,
+            &self.rev_reg_def_private,
+            &self.status_list,
+            None,
+            Some(indices),
+            timestamp,
+        )?;
+        Ok(&self.status_list)
+    }
+
+    /// Revoke a credential issued from the registry, returning the updated revocation status
+    /// list
+    pub fn revoke_credential(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        credential: &Credential,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        let idx = self.credential_index(
+            credential.rev_reg_id.as_ref(),
+            credential.signature.extract_index(),
+        )?;
+        self.revoke(cred_def, BTreeSet::from([idx]), timestamp)
+    }
+
+    /// Revoke a W3C credential issued from the registry, returning the updated revocation
+    /// status list
+    #[cfg(feature = "w3c")]
+    pub fn revoke_w3c_credential(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        credential: &W3CCredential,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        let proof = credential.get_credential_signature_proof()?;
+        let idx =
+            self.credential_index(proof.rev_reg_id.as_ref(), proof.signature.extract_index())?;
+        self.revoke(cred_def, BTreeSet::from([idx]), timestamp)
+    }
+
+    fn credential_index(
+        &self,
+        rev_reg_id: Option<&RevocationRegistryDefinitionId>,
+        idx: Option<u32>,
+    ) -> Result<u32> {
+        if rev_reg_id != Some(&self.rev_reg_def_id) {
+            return Err(err_msg!(
+                "Credential was not issued from revocation registry {}",
+                self.rev_reg_def_id
+            ));
+        }
+        idx.ok_or_else(|| err_msg!("Credential does not have a revocation registry index"))
+    }
+}
+
+/// The revocation registries of a credential definition, rotating to a new registry as the
+/// active one fills up.
+///
+/// Once the share of the active registry in use reaches the configured fill threshold, the
+/// next registry is created ahead of time so that it can be published before it is needed.
+/// Issuance moves over to it when the active registry is full, while earlier registries remain
+/// available for revocation. Ledger objects which have to be published are queued until they
+/// are retrieved with [`RevocationRegistrySet::take_publications`].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationRegistrySet {
+    cred_def_id: CredentialDefinitionId,
+    config: RevocationRegistrySetConfig,
+    registries: Vec<RevocationRegistryManager>,
+    active: usize,
+    publications: Vec<RegistryPublication>,
+}
+
+impl RevocationRegistrySet {
+    /// Create the set along with its first revocation registry
+    pub fn create<TW>(
+        cred_def: &CredentialDefinition,
+        cred_def_id: CredentialDefinitionId,
+        config: RevocationRegistrySetConfig,
+        timestamp: Option<u64>,
+        tails_writer: &mut TW,
+    ) -> Result<Self>
+    where
+        TW: TailsWriter,
+    {
+        config.validate()?;
+        let mut set = Self {
+            cred_def_id,
+            config,
+            registries: Vec::new(),
+            active: 0,
+            publications: Vec::new(),
+        };
+        set.add_registry(cred_def, timestamp, tails_writer)?;
+        Ok(set)
+    }
+
+    /// The registry new credentials are issued from
+    #[must_use]
+    pub fn active(&self) -> &RevocationRegistryManager {
+        &self.registries[self.active]
+    }
+
+    /// All registries of the set, in creation order
+    #[must_use]
+    pub fn registries(&self) -> &[RevocationRegistryManager] {
+        &self.registries
+    }
+
+    #[must_use]
+    pub fn registry(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Option<&RevocationRegistryManager> {
+        self.registries
+            .iter()
+            .find(|registry| registry.rev_reg_def_id() == rev_reg_def_id)
+    }
+
+    /// Remove and return the ledger objects which have to be published, in the order they
+    /// were produced
+    pub fn take_publications(&mut self) -> Vec<RegistryPublication> {
+        std::mem::take(&mut self.publications)
+    }
+
+    /// Issue a credential from the active registry
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_credential<TW>(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        cred_def_private: &CredentialDefinitionPrivate,
+        cred_offer: &CredentialOffer,
+        cred_request: &CredentialRequest,
+        cred_values: CredentialValues,
+        timestamp: Option<u64>,
+        tails_writer: &mut TW,
+    ) -> Result<Credential>
+    where
+        TW: TailsWriter,
+    {
+        self.issue_credential_with(cred_def, timestamp, tails_writer, |revocation_config| {
+            create_credential(
+                cred_def,
+                cred_def_private,
+                cred_offer,
+                cred_request,
+                cred_values,
+                Some(revocation_config),
+            )
+        })
+    }
+
+    /// Issue a credential of any format from the active registry, see
+    /// [`RevocationRegistryManager::issue_credential_with`].
+    ///
+    /// The tails writer is used when a new registry has to be created.
+    pub fn issue_credential_with<T, TW>(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        timestamp: Option<u64>,
+        tails_writer: &mut TW,
+        create: impl FnOnce(CredentialRevocationConfig) -> Result<T>,
+    ) -> Result<T>
+    where
+        TW: TailsWriter,
+    {
+        if self.registries[self.active].is_full() {
+            if self.active + 1 == self.registries.len() {
+                self.add_registry(cred_def, timestamp, tails_writer)?;
+            }
+            self.active += 1;
+        }
+
+        let registry = &mut self.registries[self.active];
+        let issued_on_demand = registry.status_list.get(registry.next_index as usize) == Some(true);
+        let credential = registry.issue_credential_with(cred_def, timestamp, create)?;
+        if issued_on_demand {
+            self.publications
+                .push(RegistryPublication::RevocationStatusList {
+                    list: Box::new(registry.status_list().clone()),
+                });
+        }
+
+        let registry = &self.registries[self.active];
+        let threshold = self.config.fill_threshold * f64::from(registry.capacity());
+        if self.active + 1 == self.registries.len()
+            && f64::from(registry.issued_count()) >= threshold
+        {
+            self.add_registry(cred_def, timestamp, tails_writer)?;
+        }
+
+        Ok(credential)
+    }
+
+    /// Revoke credentials of one of the registries, returning its updated revocation status
+    /// list
+    pub fn revoke(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        indices: BTreeSet<u32>,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        let idx = self.registry_position(Some(rev_reg_def_id))?;
+        let list = self.registries[idx].revoke(cred_def, indices, timestamp)?;
+        self.publications
+            .push(RegistryPublication::RevocationStatusList {
+                list: Box::new(list.clone()),
+            });
+        Ok(self.registries[idx].status_list())
+    }
+
+    /// Revoke a credential issued from one of the registries, returning the updated
+    /// revocation status list
+    pub fn revoke_credential(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        credential: &Credential,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        let idx = self.registry_position(credential.rev_reg_id.as_ref())?;
+        let list = self.registries[idx].revoke_credential(cred_def, credential, timestamp)?;
+        self.publications
+            .push(RegistryPublication::RevocationStatusList {
+                list: Box::new(list.clone()),
+            });
+        Ok(self.registries[idx].status_list())
+    }
+
+    /// Revoke a W3C credential issued from one of the registries, returning the updated
+    /// revocation status list
+    #[cfg(feature = "w3c")]
+    pub fn revoke_w3c_credential(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        credential: &W3CCredential,
+        timestamp: Option<u64>,
+    ) -> Result<&RevocationStatusList> {
+        let proof = credential.get_credential_signature_proof()?;
+        let idx = self.registry_position(proof.rev_reg_id.as_ref())?;
+        let list = self.registries[idx].revoke_w3c_credential(cred_def, credential, timestamp)?;
+        self.publications
+            .push(RegistryPublication::RevocationStatusList {
+                list: Box::new(list.clone()),
+            });
+        Ok(self.registries[idx].status_list())
+    }
+
+    fn registry_position(
+        &self,
+        rev_reg_def_id: Option<&RevocationRegistryDefinitionId>,
+    ) -> Result<usize> {
+        self.registries
+            .iter()
+            .position(|registry| Some(registry.rev_reg_def_id()) == rev_reg_def_id)
+            .ok_or_else(|| {
+                err_msg!(
+                    "Revocation registry {:?} is not part of the set for {}",
+                    rev_reg_def_id,
+                    self.cred_def_id
+                )
+            })
+    }
+
+    fn add_registry<TW>(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        timestamp: Option<u64>,
+        tails_writer: &mut TW,
+    ) -> Result<()>
+    where
+        TW: TailsWriter,
+    {
+        let tag = format!("{}{}", self.config.tag_prefix, self.registries.len() + 1);
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new(
+            self.config
+                .id_template
+                .replace(RevocationRegistrySetConfig::TAG_PLACEHOLDER, &tag),
+        )?;
+        let registry = RevocationRegistryManager::create(
+            cred_def,
+            self.cred_def_id.clone(),
+            rev_reg_def_id.clone(),
+            &tag,
+            self.config.max_cred_num,
+            self.config.issuance_by_default,
+            timestamp,
+            tails_writer,
+        )?;
+        self.publications
+            .push(RegistryPublication::RevocationRegistryDefinition {
+                id: rev_reg_def_id,
+                definition: Box::new(registry.rev_reg_def().clone()),
+            });
+        self.publications
+            .push(RegistryPublication::RevocationStatusList {
+                list: Box::new(registry.status_list().clone()),
+            });
+        self.registries.push(registry);
+        Ok(())
+    }
+}
+
 pub(crate) struct CLCredentialIssuer<'a> {
     cred_def: &'a CredentialDefinition,
     cred_def_private: &'a CredentialDefinitionPrivate,
@@ -775,11 +1703,40 @@ impl<'a> CLCredentialIssuer<'a> {
         Option<CryptoRevocationRegistry>,
         Option<Witness>,
     )> {
-        let cred_public_key = self.cred_def.get_public_key().map_err(err_map!(
+        let cred_public_key = self.public_key()?;
+        self.create_credential_with_key(
+            &cred_public_key,
+            cred_offer,
+            cred_request,
+            cred_values,
+            revocation_config,
+        )
+    }
+
+    pub(crate) fn public_key(&self) -> Result<CredentialPublicKey> {
+        self.cred_def.get_public_key().map_err(err_map!(
             Unexpected,
             "Error fetching public key from credential definition"
-        ))?;
+        ))
+    }
 
+    /// Create a credential using the public key already derived from the credential
+    /// definition
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn create_credential_with_key(
+        &self,
+        cred_public_key: &CredentialPublicKey,
+        cred_offer: &CredentialOffer,
+        cred_request: &CredentialRequest,
+        cred_values: &CredentialValues,
+        revocation_config: Option<CredentialRevocationConfig>,
+    ) -> Result<(
+        anoncreds_clsignatures::CredentialSignature,
+        SignatureCorrectnessProof,
+        Option<RevocationRegistryDefinitionId>,
+        Option<CryptoRevocationRegistry>,
+        Option<Witness>,
+    )> {
         let cred_values = build_credential_values(cred_values, None)?;
 
         if let Some(rev_config) = revocation_config {
@@ -824,7 +1781,7 @@ impl<'a> CLCredentialIssuer<'a> {
                     cred_offer.nonce.as_native(),
                     cred_request.nonce.as_native(),
                     &cred_values,
-                    &cred_public_key,
+                    cred_public_key,
                     &self.cred_def_private.value,
                     rev_config.registry_idx,
                     rev_reg_def.max_cred_num,
@@ -847,7 +1804,7 @@ impl<'a> CLCredentialIssuer<'a> {
                 cred_offer.nonce.as_native(),
                 cred_request.nonce.as_native(),
                 &cred_values,
-                &cred_public_key,
+                cred_public_key,
                 &self.cred_def_private.value,
             )?;
             Ok((signature, correctness_proof, None, None, None))
@@ -894,6 +1851,858 @@ mod tests {
         Ok(())
     }
 
+    mod revocation_registry_manager {
+        use super::*;
+
+        fn _manager(
+            issuance_by_default: bool,
+        ) -> (CredentialDefinition, RevocationRegistryManager) {
+            let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+            let attr_names = AttributeNames::from(vec!["name".to_owned(), "age".to_owned()]);
+            let schema =
+                create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+            let (cred_def, _, _) = create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig {
+                    support_revocation: true,
+                },
+            )
+            .unwrap();
+            let manager = RevocationRegistryManager::create(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                "rev:reg".try_into().unwrap(),
+                "default",
+                4,
+                issuance_by_default,
+                Some(10),
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap();
+            (cred_def, manager)
+        }
+
+        fn _issue(
+            manager: &mut RevocationRegistryManager,
+            cred_def: &CredentialDefinition,
+        ) -> Result<u32> {
+            manager.issue_credential_with(cred_def, Some(20), |config| Ok(config.registry_idx))
+        }
+
+        #[test]
+        fn manager_allocates_each_index_once() {
+            let (cred_def, mut manager) = _manager(true);
+            assert_eq!(manager.capacity(), 3);
+
+            let _err = manager
+                .issue_credential_with(&cred_def, None, |_| -> Result<()> {
+                    Err(err_msg!("Signing failed"))
+                })
+                .unwrap_err();
+            assert_eq!(manager.issued_count(), 0);
+
+            assert_eq!(_issue(&mut manager, &cred_def).unwrap(), 1);
+            assert_eq!(_issue(&mut manager, &cred_def).unwrap(), 2);
+            assert_eq!(manager.remaining(), 1);
+            assert_eq!(_issue(&mut manager, &cred_def).unwrap(), 3);
+            assert!(manager.is_full());
+
+            let err = _issue(&mut manager, &cred_def).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::RevocationRegistryFull);
+        }
+
+        #[test]
+        fn manager_marks_index_issued_on_demand() {
+            let (cred_def, mut manager) = _manager(false);
+            assert_eq!(manager.status_list().get(1), Some(true));
+
+            _issue(&mut manager, &cred_def).unwrap();
+
+            assert_eq!(manager.status_list().get(1), Some(false));
+            assert_eq!(manager.status_list().get(2), Some(true));
+            assert_eq!(manager.status_list().timestamp(), Some(20));
+        }
+
+        #[test]
+        fn manager_revokes_allocated_indices_only() {
+            let (cred_def, mut manager) = _manager(true);
+            _issue(&mut manager, &cred_def).unwrap();
+
+            let _err = manager
+                .revoke(&cred_def, BTreeSet::from([2]), None)
+                .unwrap_err();
+
+            let list = manager
+                .revoke(&cred_def, BTreeSet::from([1]), Some(30))
+                .unwrap();
+            assert_eq!(list.get(1), Some(true));
+            assert_eq!(list.timestamp(), Some(30));
+        }
+
+        #[test]
+        fn manager_state_round_trips_through_json() {
+            let (cred_def, mut manager) = _manager(true);
+            _issue(&mut manager, &cred_def).unwrap();
+
+            let json = serde_json::to_string(&manager).unwrap();
+            let mut restored: RevocationRegistryManager = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored.rev_reg_def_id(), manager.rev_reg_def_id());
+            assert_eq!(restored.issued_count(), 1);
+            assert_eq!(_issue(&mut restored, &cred_def).unwrap(), 2);
+        }
+
+        #[test]
+        fn manager_rejects_invalid_state_from_json() {
+            let (_, manager) = _manager(true);
+            let json = serde_json::to_value(&manager).unwrap();
+
+            for next_index in [0, manager.rev_reg_def().value.max_cred_num + 1] {
+                let mut json = json.clone();
+                json["nextIndex"] = next_index.into();
+                let _err = serde_json::from_value::<RevocationRegistryManager>(json).unwrap_err();
+            }
+        }
+
+        #[test]
+        fn manager_rejects_mismatched_status_list() {
+            let (_, manager) = _manager(true);
+            let RevocationRegistryManager {
+                rev_reg_def,
+                rev_reg_def_private,
+                status_list,
+                ..
+            } = manager;
+
+            let _err = RevocationRegistryManager::from_parts(
+                "other:reg".try_into().unwrap(),
+                rev_reg_def,
+                rev_reg_def_private,
+                status_list,
+                1,
+            )
+            .unwrap_err();
+        }
+    }
+
+    mod revocation_status_list_audit {
+        use super::*;
+
+        struct Registry {
+            cred_def: CredentialDefinition,
+            rev_reg_def: RevocationRegistryDefinition,
+            rev_reg_priv: RevocationRegistryDefinitionPrivate,
+            list: RevocationStatusList,
+        }
+
+        fn _registry(issuance_by_default: bool) -> Registry {
+            let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+            let attr_names = AttributeNames::from(vec!["name".to_owned(), "age".to_owned()]);
+            let schema =
+                create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+            let (cred_def, _, _) = create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig {
+                    support_revocation: true,
+                },
+            )
+            .unwrap();
+            let (rev_reg_def, rev_reg_priv) = create_revocation_registry_def(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                "default",
+                RegistryType::CL_ACCUM,
+                5,
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap();
+            let list = create_revocation_status_list(
+                &cred_def,
+                "rev:reg".try_into().unwrap(),
+                &rev_reg_def,
+                &rev_reg_priv,
+                issuance_by_default,
+                Some(10),
+            )
+            .unwrap();
+            Registry {
+                cred_def,
+                rev_reg_def,
+                rev_reg_priv,
+                list,
+            }
+        }
+
+        fn _audit(
+            registry: &Registry,
+            list: &RevocationStatusList,
+            issuance_by_default: bool,
+        ) -> RevocationStatusListAudit {
+            audit_revocation_status_list(
+                &registry.cred_def,
+                &registry.rev_reg_def,
+                &registry.rev_reg_priv,
+                &registry.rev_reg_def.value.tails_location,
+                list,
+                issuance_by_default,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn audit_accepts_updated_lists() {
+            for issuance_by_default in [true, false] {
+                let registry = _registry(issuance_by_default);
+                assert!(_audit(&registry, &registry.list, issuance_by_default).is_consistent());
+
+                let list = update_revocation_status_list(
+                    &registry.cred_def,
+                    &registry.rev_reg_def,
+                    &registry.rev_reg_priv,
+                    &registry.list,
+                    Some(BTreeSet::from([1, 2, 3])),
+                    None,
+                    Some(20),
+                )
+                .unwrap();
+                let list = update_revocation_status_list(
+                    &registry.cred_def,
+                    &registry.rev_reg_def,
+                    &registry.rev_reg_priv,
+                    &list,
+                    None,
+                    Some(BTreeSet::from([2])),
+                    Some(30),
+                )
+                .unwrap();
+                assert!(_audit(&registry, &list, issuance_by_default).is_consistent());
+                // the issuance mode determines the initial accumulator
+                assert!(!_audit(&registry, &list, !issuance_by_default).accum_matches());
+            }
+        }
+
+        #[test]
+        fn audit_detects_and_rebuild_repairs_drifted_accumulator() {
+            for issuance_by_default in [true, false] {
+                let registry = _registry(issuance_by_default);
+                let issued = update_revocation_status_list(
+                    &registry.cred_def,
+                    &registry.rev_reg_def,
+                    &registry.rev_reg_priv,
+                    &registry.list,
+                    Some(BTreeSet::from([1, 2])),
+                    None,
+                    Some(20),
+                )
+                .unwrap();
+                let expected = update_revocation_status_list(
+                    &registry.cred_def,
+                    &registry.rev_reg_def,
+                    &registry.rev_reg_priv,
+                    &issued,
+                    None,
+                    Some(BTreeSet::from([1])),
+                    Some(20),
+                )
+                .unwrap();
+
+                // revocation states persisted without the matching accumulator
+                let mut drifted = issued.clone();
+                drifted
+                    .update(None, None, Some(BTreeSet::from([1])), None)
+                    .unwrap();
+                let audit = _audit(&registry, &drifted, issuance_by_default);
+                assert!(audit.tails_valid);
+                assert!(!audit.accum_matches());
+                assert!(!audit.is_consistent());
+
+                let rebuilt = rebuild_revocation_status_list(
+                    &registry.cred_def,
+                    &registry.rev_reg_def,
+                    &registry.rev_reg_priv,
+                    &drifted,
+                    issuance_by_default,
+                    Some(30),
+                )
+                .unwrap();
+                assert!(_audit(&registry, &rebuilt, issuance_by_default).is_consistent());
+                assert_eq!(rebuilt.state(), expected.state());
+                assert_eq!(rebuilt.timestamp(), Some(30));
+                let rebuilt_accum: Option<CryptoRevocationRegistry> = (&rebuilt).into();
+                let expected_accum: Option<CryptoRevocationRegistry> = (&expected).into();
+                assert_eq!(rebuilt_accum, expected_accum);
+            }
+        }
+
+        #[test]
+        fn audit_reports_invalid_tails_file() {
+            let registry = _registry(true);
+            let tails_path = std::env::temp_dir().join("anoncreds-audit-invalid-tails");
+            std::fs::write(&tails_path, [0u8, 2, 1, 2, 3]).unwrap();
+
+            let audit = audit_revocation_status_list(
+                &registry.cred_def,
+                &registry.rev_reg_def,
+                &registry.rev_reg_priv,
+                tails_path.to_str().unwrap(),
+                &registry.list,
+                true,
+            )
+            .unwrap();
+            std::fs::remove_file(&tails_path).unwrap();
+            assert!(audit.accum_matches());
+            assert!(!audit.tails_valid);
+        }
+    }
+
+    mod create_credentials_batch {
+        use super::*;
+        use crate::prover;
+        use crate::types::MakeCredentialValues;
+
+        #[test]
+        fn batch_issues_revocable_credentials_with_one_status_list_update() {
+            for issuance_by_default in [true, false] {
+                let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+                let attr_names = AttributeNames::from(vec!["name".to_owned()]);
+                let schema =
+                    create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+                let (cred_def, cred_def_priv, key_proof) = create_credential_definition(
+                    "schema:id".try_into().unwrap(),
+                    &schema,
+                    issuer_id,
+                    "default",
+                    SignatureType::CL,
+                    CredentialDefinitionConfig {
+                        support_revocation: true,
+                    },
+                )
+                .unwrap();
+                let (rev_reg_def, rev_reg_priv) = create_revocation_registry_def(
+                    &cred_def,
+                    "cred:def".try_into().unwrap(),
+                    "default",
+                    RegistryType::CL_ACCUM,
+                    5,
+                    &mut TailsFileWriter::new(None),
+                )
+                .unwrap();
+                let status_list = create_revocation_status_list(
+                    &cred_def,
+                    "rev:reg".try_into().unwrap(),
+                    &rev_reg_def,
+                    &rev_reg_priv,
+                    issuance_by_default,
+                    Some(10),
+                )
+                .unwrap();
+
+                let link_secret = prover::create_link_secret().unwrap();
+                let offers = (0..4)
+                    .map(|_| {
+                        create_credential_offer(
+                            "schema:id".try_into().unwrap(),
+                            "cred:def".try_into().unwrap(),
+                            &key_proof,
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                let requests = offers
+                    .iter()
+                    .map(|offer| {
+                        prover::create_credential_request(
+                            Some("entropy"),
+                            None,
+                            &cred_def,
+                            &link_secret,
+                            "default",
+                            offer,
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                let items = offers
+                    .iter()
+                    .zip(&requests)
+                    .zip([None, Some(2), Some(2), None])
+                    .map(|((offer, (request, _)), registry_idx)| {
+                        let mut values = MakeCredentialValues::default();
+                        values.add_raw("name", "Alex").unwrap();
+                        CredentialBatchItem {
+                            cred_offer: offer,
+                            cred_request: request,
+                            cred_values: values.into(),
+                            registry_idx,
+                        }
+                    })
+                    .collect();
+
+                let batch = create_credentials_batch(
+                    &cred_def,
+                    &cred_def_priv,
+                    items,
+                    Some(CredentialBatchRevocationConfig {
+                        reg_def: &rev_reg_def,
+                        reg_def_private: &rev_reg_priv,
+                        status_list: &status_list,
+                        first_idx: 1,
+                    }),
+                    Some(20),
+                )
+                .unwrap();
+
+                // the duplicate index fails, free indices skip the requested one
+                assert!(batch.credentials[2].is_err());
+                let indices = batch
+                    .credentials
+                    .iter()
+                    .map(|credential| {
+                        credential
+                            .as_ref()
+                            .ok()
+                            .and_then(|credential| credential.signature.extract_index())
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(indices, [Some(1), Some(2), None, Some(3)]);
+
+                let status_list = batch.status_list.unwrap();
+                assert_eq!(status_list.timestamp(), Some(20));
+                for idx in 1..=3 {
+                    assert_eq!(status_list.get(idx), Some(false));
+                }
+                assert!(
+                    audit_revocation_status_list(
+                        &cred_def,
+                        &rev_reg_def,
+                        &rev_reg_priv,
+                        &rev_reg_def.value.tails_location,
+                        &status_list,
+                        issuance_by_default,
+                    )
+                    .unwrap()
+                    .is_consistent()
+                );
+
+                for (credential, (_, metadata)) in batch.credentials.into_iter().zip(&requests) {
+                    if let Ok(mut credential) = credential {
+                        prover::process_credential(
+                            &mut credential,
+                            metadata,
+                            &link_secret,
+                            &cred_def,
+                            Some(&rev_reg_def),
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn batch_rejects_revocation_index_without_registry() {
+            let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+            let attr_names = AttributeNames::from(vec!["name".to_owned()]);
+            let schema =
+                create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+            let (cred_def, cred_def_priv, key_proof) = create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig::default(),
+            )
+            .unwrap();
+            let offer = create_credential_offer(
+                "schema:id".try_into().unwrap(),
+                "cred:def".try_into().unwrap(),
+                &key_proof,
+            )
+            .unwrap();
+            let link_secret = prover::create_link_secret().unwrap();
+            let (request, _) = prover::create_credential_request(
+                Some("entropy"),
+                None,
+                &cred_def,
+                &link_secret,
+                "default",
+                &offer,
+            )
+            .unwrap();
+            let items = [None, Some(1)]
+                .into_iter()
+                .map(|registry_idx| {
+                    let mut values = MakeCredentialValues::default();
+                    values.add_raw("name", "Alex").unwrap();
+                    CredentialBatchItem {
+                        cred_offer: &offer,
+                        cred_request: &request,
+                        cred_values: values.into(),
+                        registry_idx,
+                    }
+                })
+                .collect();
+
+            let batch =
+                create_credentials_batch(&cred_def, &cred_def_priv, items, None, None).unwrap();
+            assert!(batch.status_list.is_none());
+            assert!(batch.credentials[0].is_ok());
+            assert!(batch.credentials[1].is_err());
+        }
+
+        #[test]
+        fn batch_checks_revocation_indices_against_registry_range() {
+            let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+            let attr_names = AttributeNames::from(vec!["name".to_owned()]);
+            let schema =
+                create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+            let (cred_def, cred_def_priv, key_proof) = create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig {
+                    support_revocation: true,
+                },
+            )
+            .unwrap();
+            let (rev_reg_def, rev_reg_priv) = create_revocation_registry_def(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                "default",
+                RegistryType::CL_ACCUM,
+                5,
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap();
+            let status_list = create_revocation_status_list(
+                &cred_def,
+                "rev:reg".try_into().unwrap(),
+                &rev_reg_def,
+                &rev_reg_priv,
+                true,
+                Some(10),
+            )
+            .unwrap();
+            let offer = create_credential_offer(
+                "schema:id".try_into().unwrap(),
+                "cred:def".try_into().unwrap(),
+                &key_proof,
+            )
+            .unwrap();
+            let link_secret = prover::create_link_secret().unwrap();
+            let (request, _) = prover::create_credential_request(
+                Some("entropy"),
+                None,
+                &cred_def,
+                &link_secret,
+                "default",
+                &offer,
+            )
+            .unwrap();
+            let batch = |registry_indices: &[Option<u32>], first_idx| {
+                let items = registry_indices
+                    .iter()
+                    .map(|&registry_idx| {
+                        let mut values = MakeCredentialValues::default();
+                        values.add_raw("name", "Alex").unwrap();
+                        CredentialBatchItem {
+                            cred_offer: &offer,
+                            cred_request: &request,
+                            cred_values: values.into(),
+                            registry_idx,
+                        }
+                    })
+                    .collect();
+                create_credentials_batch(
+                    &cred_def,
+                    &cred_def_priv,
+                    items,
+                    Some(CredentialBatchRevocationConfig {
+                        reg_def: &rev_reg_def,
+                        reg_def_private: &rev_reg_priv,
+                        status_list: &status_list,
+                        first_idx,
+                    }),
+                    None,
+                )
+            };
+
+            // indices start at 1 and end before the size of the status list
+            let _err = batch(&[None], 0).unwrap_err();
+            let _err = batch(&[None], 5).unwrap_err();
+            let _err = batch(&[None, Some(5)], 1).unwrap_err();
+            let _err = batch(&[Some(0)], 1).unwrap_err();
+
+            // free indices run out at the end of the registry
+            let batch = batch(&[None, None], 4).unwrap();
+            assert_eq!(
+                batch.credentials[0]
+                    .as_ref()
+                    .unwrap()
+                    .signature
+                    .extract_index(),
+                Some(4)
+            );
+            assert!(batch.credentials[1].is_err());
+        }
+    }
+
+    mod revocation_registry_set {
+        use super::*;
+
+        fn _set(fill_threshold: f64) -> (CredentialDefinition, RevocationRegistrySet) {
+            let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+            let attr_names = AttributeNames::from(vec!["name".to_owned(), "age".to_owned()]);
+            let schema =
+                create_schema("schema:name", "1.0", issuer_id.clone(), attr_names).unwrap();
+            let (cred_def, _, _) = create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig {
+                    support_revocation: true,
+                },
+            )
+            .unwrap();
+            let set = RevocationRegistrySet::create(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                RevocationRegistrySetConfig {
+                    id_template: "rev:reg:{tag}".to_owned(),
+                    tag_prefix: "default-".to_owned(),
+                    max_cred_num: 4,
+                    issuance_by_default: true,
+                    fill_threshold,
+                },
+                Some(10),
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap();
+            (cred_def, set)
+        }
+
+        fn _issue(
+            set: &mut RevocationRegistrySet,
+            cred_def: &CredentialDefinition,
+        ) -> Result<(String, u32)> {
+            set.issue_credential_with(
+                cred_def,
+                Some(20),
+                &mut TailsFileWriter::new(None),
+                |config| Ok((config.reg_def.tag.clone(), config.registry_idx)),
+            )
+        }
+
+        fn _published_registries(publications: &[RegistryPublication]) -> Vec<String> {
+            publications
+                .iter()
+                .filter_map(|publication| match publication {
+                    RegistryPublication::RevocationRegistryDefinition { id, .. } => {
+                        Some(id.to_string())
+                    }
+                    RegistryPublication::RevocationStatusList { .. } => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn set_publishes_first_registry() {
+            let (_, mut set) = _set(1.0);
+            let publications = set.take_publications();
+
+            assert_eq!(publications.len(), 2);
+            assert_eq!(_published_registries(&publications), ["rev:reg:default-1"]);
+            assert_eq!(
+                set.active().rev_reg_def_id().to_string(),
+                "rev:reg:default-1"
+            );
+            assert!(set.take_publications().is_empty());
+        }
+
+        #[test]
+        fn set_creates_next_registry_at_threshold() {
+            let (cred_def, mut set) = _set(0.5);
+            set.take_publications();
+
+            _issue(&mut set, &cred_def).unwrap();
+            assert_eq!(set.registries().len(), 1);
+
+            _issue(&mut set, &cred_def).unwrap();
+            assert_eq!(set.registries().len(), 2);
+            assert_eq!(
+                _published_registries(&set.take_publications()),
+                ["rev:reg:default-2"]
+            );
+            assert_eq!(
+                set.active().rev_reg_def_id().to_string(),
+                "rev:reg:default-1"
+            );
+        }
+
+        #[test]
+        fn set_switches_registry_when_full() {
+            let (cred_def, mut set) = _set(1.0);
+
+            for idx in 1..=3 {
+                assert_eq!(
+                    _issue(&mut set, &cred_def).unwrap(),
+                    ("default-1".to_owned(), idx)
+                );
+            }
+            assert_eq!(set.registries().len(), 2);
+            assert_eq!(
+                _issue(&mut set, &cred_def).unwrap(),
+                ("default-2".to_owned(), 1)
+            );
+            assert_eq!(set.registries().len(), 2);
+        }
+
+        #[test]
+        fn set_revokes_from_previous_registry() {
+            let (cred_def, mut set) = _set(1.0);
+            for _ in 0..4 {
+                _issue(&mut set, &cred_def).unwrap();
+            }
+            set.take_publications();
+
+            let first_id = "rev:reg:default-1".try_into().unwrap();
+            let list = set
+                .revoke(&cred_def, &first_id, BTreeSet::from([2]), Some(30))
+                .unwrap();
+            assert_eq!(list.get(2), Some(true));
+
+            let publications = set.take_publications();
+            assert!(matches!(
+                publications.as_slice(),
+                [RegistryPublication::RevocationStatusList { list }] if list.timestamp() == Some(30)
+            ));
+
+            let unknown_id = "rev:reg:other".try_into().unwrap();
+            let _err = set
+                .revoke(&cred_def, &unknown_id, BTreeSet::from([1]), None)
+                .unwrap_err();
+        }
+
+        #[test]
+        fn set_state_round_trips_through_json() {
+            let (cred_def, mut set) = _set(1.0);
+            for _ in 0..3 {
+                _issue(&mut set, &cred_def).unwrap();
+            }
+
+            let json = serde_json::to_string(&set).unwrap();
+            let mut restored: RevocationRegistrySet = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored.take_publications().len(), 4);
+            assert_eq!(
+                _issue(&mut restored, &cred_def).unwrap(),
+                ("default-2".to_owned(), 1)
+            );
+        }
+
+        #[test]
+        fn set_rejects_invalid_config() {
+            let (cred_def, _) = _set(1.0);
+            let config = RevocationRegistrySetConfig {
+                id_template: "rev:reg".to_owned(),
+                tag_prefix: "default-".to_owned(),
+                max_cred_num: 4,
+                issuance_by_default: true,
+                fill_threshold: 1.0,
+            };
+            let _err = RevocationRegistrySet::create(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                config,
+                None,
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_create_credential_with_schema_checks_typed_values() {
+        use crate::types::MakeCredentialValues;
+
+        let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+        let attr_names = AttributeNames::from(vec!["name".to_owned(), "birthdate".to_owned()]);
+        let schema = create_schema_with_attr_encodings(
+            "schema:name",
+            "1.0",
+            issuer_id.clone(),
+            attr_names,
+            HashMap::from([("birthdate".to_owned(), AttributeEncoding::Date)]),
+        )
+        .unwrap();
+        let (cred_def, cred_def_priv, key_proof) = create_credential_definition(
+            "schema:id".try_into().unwrap(),
+            &schema,
+            issuer_id,
+            "default",
+            SignatureType::CL,
+            CredentialDefinitionConfig::default(),
+        )
+        .unwrap();
+        let offer = create_credential_offer(
+            "schema:id".try_into().unwrap(),
+            "cred:def".try_into().unwrap(),
+            &key_proof,
+        )
+        .unwrap();
+        let link_secret = crate::prover::create_link_secret().unwrap();
+        let (request, _) = crate::prover::create_credential_request(
+            Some("entropy"),
+            None,
+            &cred_def,
+            &link_secret,
+            "default",
+            &offer,
+        )
+        .unwrap();
+        let issue = |birthdate: &str, typed: bool| {
+            let mut values = MakeCredentialValues::default();
+            values.add_raw("name", "Alex").unwrap();
+            if typed {
+                values
+                    .add_typed("birthdate", birthdate, &AttributeEncoding::Date)
+                    .unwrap();
+            } else {
+                values.add_raw("birthdate", birthdate).unwrap();
+            }
+            create_credential_with_schema(
+                &cred_def,
+                &cred_def_priv,
+                &schema,
+                &offer,
+                &request,
+                values.into(),
+                None,
+            )
+        };
+
+        let credential = issue("2000-02-29", true).unwrap();
+        assert_eq!(credential.values.0["birthdate"].encoded, "730179");
+        // malformed dates are rejected
+        assert!(issue("2001-02-29", false).is_err());
+        assert!(issue("29/02/2000", false).is_err());
+        // valid dates must still use the declared encoding
+        assert!(issue("2000-02-29", false).is_err());
+    }
+
     #[test]
     fn test_encode_attribute() {
         assert_eq!(
@@ -1,6 +1,8 @@
 pub(crate) mod helpers;
 pub mod issuer;
+pub mod nonce_registry;
 pub mod prover;
+pub mod resolver;
 pub mod tails;
 pub mod types;
 pub mod verifier;
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind as IoErrorKind;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::random;
+
+use crate::data_types::nonce::Nonce;
+use crate::error::Result;
+use crate::services::helpers::new_nonce;
+
+/// Record of the presentation request nonces issued by a verifier
+///
+/// Each nonce is valid for a limited time after it is registered and can be consumed only
+/// once, which protects the `*_with_nonce_registry` verifier functions against replayed
+/// presentations.
+pub trait NonceRegistry {
+    /// Record a nonce issued at the given timestamp
+    fn register_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()>;
+
+    /// Mark a nonce as used at the given timestamp, failing with a `ProofRejected` error
+    /// if it was never issued, has expired or was already used
+    fn consume_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()>;
+
+    fn register(&self, nonce: &Nonce) -> Result<()> {
+        self.register_at(nonce, current_timestamp()?)
+    }
+
+    fn consume(&self, nonce: &Nonce) -> Result<()> {
+        self.consume_at(nonce, current_timestamp()?)
+    }
+
+    /// Generate and register a new presentation request nonce
+    fn issue(&self) -> Result<Nonce> {
+        let nonce = new_nonce()?;
+        self.register(&nonce)?;
+        Ok(nonce)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct NonceEntry {
+    expires_at: u64,
+    used: bool,
+}
+
+/// Issued nonces by decimal value, expired entries are dropped when new nonces are registered
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct NonceEntries(HashMap<String, NonceEntry>);
+
+impl NonceEntries {
+    fn register(&mut self, nonce: &Nonce, timestamp: u64, ttl: u64) -> Result<()> {
+        self.0.retain(|_, entry| entry.expires_at > timestamp);
+        if self.0.contains_key(nonce.as_ref()) {
+            return Err(err_msg!("Nonce is already registered: {}", nonce));
+        }
+        self.0.insert(
+            nonce.to_string(),
+            NonceEntry {
+                expires_at: timestamp.saturating_add(ttl),
+                used: false,
+            },
+        );
+        Ok(())
+    }
+
+    fn consume(&mut self, nonce: &Nonce, timestamp: u64) -> Result<()> {
+        let entry = self.0.get_mut(nonce.as_ref()).ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Presentation request nonce was never issued: {}",
+                nonce
+            )
+        })?;
+        if entry.used {
+            return Err(err_msg!(
+                ProofRejected,
+                "Presentation request nonce was already used: {}",
+                nonce
+            ));
+        }
+        if entry.expires_at <= timestamp {
+            return Err(err_msg!(
+                ProofRejected,
+                "Presentation request nonce has expired: {}",
+                nonce
+            ));
+        }
+        entry.used = true;
+        Ok(())
+    }
+}
+
+/// Nonce registry keeping issued nonces in memory
+#[derive(Debug)]
+pub struct InMemoryNonceRegistry {
+    ttl: u64,
+    entries: Mutex<NonceEntries>,
+}
+
+impl InMemoryNonceRegistry {
+    /// Create a registry accepting nonces for `ttl`, rounded up to whole seconds, after they
+    /// are registered
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl: ttl_secs(ttl),
+            entries: Mutex::default(),
+        }
+    }
+}
+
+impl NonceRegistry for InMemoryNonceRegistry {
+    fn register_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()> {
+        lock(&self.entries)?.register(nonce, timestamp, self.ttl)
+    }
+
+    fn consume_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()> {
+        lock(&self.entries)?.consume(nonce, timestamp)
+    }
+}
+
+/// Nonce registry persisting issued nonces to a JSON file
+///
+/// The file is rewritten on every change. Access is serialized within the process only,
+/// so a file must not be shared by several registries at the same time.
+#[derive(Debug)]
+pub struct FileNonceRegistry {
+    path: PathBuf,
+    ttl: u64,
+    lock: Mutex<()>,
+}
+
+impl FileNonceRegistry {
+    /// Create a registry stored at `path`, accepting nonces for `ttl`, rounded up to whole
+    /// seconds, after they are registered
+    pub fn new<P: Into<PathBuf>>(path: P, ttl: Duration) -> Self {
+        Self {
+            path: path.into(),
+            ttl: ttl_secs(ttl),
+            lock: Mutex::default(),
+        }
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut NonceEntries) -> Result<T>) -> Result<T> {
+        let _guard = lock(&self.lock)?;
+        let mut entries = self.load()?;
+        let res = f(&mut entries)?;
+        self.store(&entries)?;
+        Ok(res)
+    }
+
+    fn load(&self) -> Result<NonceEntries> {
+        let json = match fs::read(&self.path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == IoErrorKind::NotFound => {
+                return Ok(NonceEntries::default());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_slice(&json).map_err(err_map!(
+            "Error parsing nonce registry {}",
+            self.path.display()
+        ))
+    }
+
+    // Write to a temporary file first, so that the registry is never left truncated
+    fn store(&self, entries: &NonceEntries) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json =
+            serde_json::to_vec(entries).map_err(err_map!("Error serializing nonce registry"))?;
+        let temp_path = self
+            .path
+            .with_extension(format!("{:020}.tmp", random::<u64>()));
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, &self.path).map_err(|err| {
+            let _ = fs::remove_file(&temp_path);
+            err_msg!(
+                IOError,
+                "Error moving nonce registry temp file {:?}: {}",
+                temp_path,
+                err
+            )
+        })
+    }
+}
+
+impl NonceRegistry for FileNonceRegistry {
+    fn register_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()> {
+        self.update(|entries| entries.register(nonce, timestamp, self.ttl))
+    }
+
+    fn consume_at(&self, nonce: &Nonce, timestamp: u64) -> Result<()> {
+        self.update(|entries| entries.consume(nonce, timestamp))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>> {
+    mutex
+        .lock()
+        .map_err(|_| err_msg!(InvalidState, "Nonce registry lock is poisoned"))
+}
+
+// Nonces are tracked with second precision, so a partial second counts as a whole one
+fn ttl_secs(ttl: Duration) -> u64 {
+    ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0)
+}
+
+fn current_timestamp() -> Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err_msg!("Invalid system time: {}", err))?;
+    Ok(now.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    const TTL: Duration = Duration::from_secs(300);
+
+    fn assert_rejected(res: Result<()>, reason: &str) {
+        let err = res.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ProofRejected);
+        assert!(err.to_string().contains(reason), "{err}");
+    }
+
+    #[test]
+    fn in_memory_registry_consumes_nonce_once() {
+        let registry = InMemoryNonceRegistry::new(TTL);
+        let nonce = new_nonce().unwrap();
+        registry.register_at(&nonce, 1000).unwrap();
+        registry.register_at(&nonce, 1000).unwrap_err();
+
+        registry.consume_at(&nonce, 1299).unwrap();
+        assert_rejected(registry.consume_at(&nonce, 1299), "already used");
+        assert_rejected(
+            registry.consume_at(&new_nonce().unwrap(), 1000),
+            "never issued",
+        );
+    }
+
+    #[test]
+    fn in_memory_registry_rejects_expired_nonce() {
+        let registry = InMemoryNonceRegistry::new(TTL);
+        let nonce = new_nonce().unwrap();
+        registry.register_at(&nonce, 1000).unwrap();
+        assert_rejected(registry.consume_at(&nonce, 1300), "expired");
+
+        // expired nonces are dropped once another nonce is registered
+        registry.register_at(&new_nonce().unwrap(), 1300).unwrap();
+        assert_rejected(registry.consume_at(&nonce, 1300), "never issued");
+    }
+
+    #[test]
+    fn registry_rounds_up_sub_second_ttl() {
+        let registry = InMemoryNonceRegistry::new(Duration::from_millis(500));
+        let nonce = new_nonce().unwrap();
+        registry.register_at(&nonce, 1000).unwrap();
+        registry.consume_at(&nonce, 1000).unwrap();
+
+        let registry = InMemoryNonceRegistry::new(Duration::from_millis(1500));
+        let nonce = new_nonce().unwrap();
+        registry.register_at(&nonce, 1000).unwrap();
+        assert_rejected(registry.consume_at(&nonce, 1002), "expired");
+    }
+
+    #[test]
+    fn file_registry_persists_nonces() {
+        let root = std::env::temp_dir().join(format!("anoncreds-nonces-{}", rand::random::<u64>()));
+        let path = root.join("nonces.json");
+        let nonce = FileNonceRegistry::new(&path, TTL).issue().unwrap();
+
+        FileNonceRegistry::new(&path, TTL).consume(&nonce).unwrap();
+        assert_rejected(
+            FileNonceRegistry::new(&path, TTL).consume(&nonce),
+            "already used",
+        );
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
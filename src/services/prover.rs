@@ -1,30 +1,41 @@
-use super::tails::TailsFileReader;
+use super::tails::{CachingTailsAccessor, TailsFileReader};
 use super::types::{
     Credential, CredentialOffer, CredentialRequest, CredentialRequestMetadata, LinkSecret,
     Presentation, PresentationRequest, RevocationRegistryDefinition,
 };
 use crate::cl::{
-    CredentialPublicKey, Issuer, Prover, RevocationRegistry, RevocationRegistryDelta, Witness,
+    CredentialPublicKey, Issuer, Prover, RevocationRegistry, RevocationRegistryDelta,
+    RevocationTailsAccessor, Witness,
 };
 use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
 use crate::data_types::credential::AttributeValues;
-use crate::data_types::pres_request::PresentationRequestPayload;
+use crate::data_types::pres_request::{
+    AttributeInfo, NonRevokedInterval, PredicateInfo, PresentationRequestPayload, RangeInfo,
+};
 use crate::data_types::presentation::AttributeValue;
 use crate::data_types::presentation::Identifier;
 use crate::data_types::presentation::RequestedProof;
 use crate::data_types::presentation::RevealedAttributeGroupInfo;
 use crate::data_types::presentation::RevealedAttributeInfo;
 use crate::data_types::presentation::SubProofReferent;
-use crate::data_types::rev_status_list::RevocationStatusList;
+use crate::data_types::rev_status_list::{RevocationStatusList, index_deltas};
 use crate::data_types::schema::{Schema, SchemaId};
 use crate::error::{Error, Result};
 use crate::services::helpers::{
     attr_common_view, build_credential_schema, build_credential_values,
-    build_non_credential_schema, build_sub_proof_request, get_non_revoked_interval, new_nonce,
+    build_non_credential_schema, build_predicate, build_sub_proof_request,
+    check_predicate_satisfiable, check_predicate_value, encode_credential_attribute,
+    get_non_revoked_interval, get_requested_non_revoked_interval, new_nonce,
+};
+use crate::services::resolver::{AnoncredsResolver, ResolvedObjects};
+use crate::services::verifier::{
+    TagOperator, gather_filter_info, parse_attr_tag, process_filter, process_query,
 };
 use crate::types::{
-    CredentialRevocationState, CredentialValues, PresentCredential, PresentCredentials,
+    CredentialMatch, CredentialMatches, CredentialRevocationState, CredentialValues,
+    PresentCredential, PresentCredentials, SelectedCredentials, SelectionPolicy,
 };
+use crate::utils::query::Query;
 use crate::utils::validation::Validatable;
 
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
@@ -33,9 +44,8 @@ use anoncreds_clsignatures::{
     SignatureCorrectnessProof,
 };
 use bitvec::bitvec;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
-use std::ops::BitXor;
 
 /// Create a new random link secret which is cryptographically strong and pseudo-random
 ///
@@ -478,6 +488,573 @@ pub fn create_presentation(
     Ok(full_proof)
 }
 
+/// Create a presentation, fetching the schemas and credential definitions of the presented
+/// credentials from a resolver
+///
+/// Revocation objects are not fetched: proving non-revocation only needs the revocation
+/// states passed along with `credentials`, which are created beforehand with
+/// `create_or_update_revocation_state` from the registry definition and status list.
+pub fn create_presentation_with_resolver<R>(
+    pres_req: &PresentationRequest,
+    credentials: PresentCredentials<Credential>,
+    self_attested: Option<HashMap<String, String>>,
+    link_secret: &LinkSecret,
+    resolver: &R,
+) -> Result<Presentation>
+where
+    R: AnoncredsResolver + ?Sized,
+{
+    let identifiers = credentials
+        .0
+        .iter()
+        .map(|present| Identifier {
+            schema_id: present.cred.schema_id.clone(),
+            cred_def_id: present.cred.cred_def_id.clone(),
+            rev_reg_id: None,
+            timestamp: None,
+        })
+        .collect::<Vec<Identifier>>();
+    let resolved = ResolvedObjects::resolve(resolver, &identifiers)?;
+
+    create_presentation(
+        pres_req,
+        credentials,
+        self_attested,
+        link_secret,
+        &resolved.schemas,
+        &resolved.cred_defs,
+    )
+}
+
+/// Credential formats which can be matched against a presentation request
+pub trait MatchCredential {
+    /// Schema, credential definition and revocation registry identifiers of the credential
+    fn identifier(&self) -> Result<Identifier>;
+
+    /// Raw value of the named attribute, if the credential contains it
+    fn raw_value(&self, attr_name: &str) -> Option<String>;
+
+    /// Encoded value of the named attribute, as compared in predicate proofs
+    ///
+    /// By default the raw value is encoded with the encoding declared by the credential
+    /// schema, if any.
+    fn encoded_value(&self, attr_name: &str, schema: Option<&Schema>) -> Option<String> {
+        let raw_value = self.raw_value(attr_name)?;
+        match schema {
+            Some(schema) => schema.encode_attribute(attr_name, &raw_value),
+            None => encode_credential_attribute(&raw_value),
+        }
+        .ok()
+    }
+}
+
+impl MatchCredential for Credential {
+    fn identifier(&self) -> Result<Identifier> {
+        Ok(Identifier {
+            schema_id: self.schema_id.clone(),
+            cred_def_id: self.cred_def_id.clone(),
+            rev_reg_id: self.rev_reg_id.clone(),
+            timestamp: None,
+        })
+    }
+
+    fn raw_value(&self, attr_name: &str) -> Option<String> {
+        get_credential_values_for_attribute(&self.values.0, attr_name)
+            .map(|values| values.raw.clone())
+    }
+
+    fn encoded_value(&self, attr_name: &str, _schema: Option<&Schema>) -> Option<String> {
+        get_credential_values_for_attribute(&self.values.0, attr_name)
+            .map(|values| values.encoded.clone())
+    }
+}
+
+/// Find the credentials able to fill each referent of a presentation request.
+///
+/// A credential matches an attribute referent when it contains the requested attribute (or
+/// every attribute of a `names` group) and satisfies the referent restrictions. It matches a
+/// predicate referent when its encoded attribute value also satisfies the predicate, as
+/// checked by [`check_credential_predicate`], using the typed encodings declared by the
+/// credential schema. For
+/// revocable credentials the `non_revoked` interval applying to the referent is returned
+/// with the match, the revocation state used in the presentation must fall within it.
+///
+/// Candidates are identified by their position in `credentials`. The schemas and credential
+/// definitions of the credentials are needed to evaluate restrictions.
+pub fn match_credentials<T>(
+    pres_req: &PresentationRequest,
+    credentials: &[T],
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+) -> Result<CredentialMatches>
+where
+    T: MatchCredential,
+{
+    trace!(
+        "match_credentials >>> pres_req: {:?}, credentials: {}, schemas: {:?}, cred_defs: {:?}",
+        pres_req,
+        credentials.len(),
+        schemas,
+        cred_defs
+    );
+
+    let pres_req_val = pres_req.value();
+    let identifiers = credentials
+        .iter()
+        .map(MatchCredential::identifier)
+        .collect::<Result<Vec<Identifier>>>()?;
+
+    let find_matches = |restrictions: Option<&Query>,
+                        non_revoked: Option<&NonRevokedInterval>,
+                        is_match: &dyn Fn(&T, &Identifier) -> bool|
+     -> Result<Vec<CredentialMatch>> {
+        let mut matches = Vec::new();
+        for (index, (credential, identifier)) in credentials.iter().zip(&identifiers).enumerate() {
+            if is_match(credential, identifier)
+                && matches_restrictions(credential, identifier, restrictions, schemas, cred_defs)?
+            {
+                matches.push(CredentialMatch {
+                    index,
+                    non_revoked: identifier.rev_reg_id.as_ref().and_then(|rev_reg_id| {
+                        get_requested_non_revoked_interval(
+                            Some(rev_reg_id),
+                            non_revoked,
+                            pres_req_val.non_revoked.as_ref(),
+                            None,
+                        )
+                    }),
+                });
+            }
+        }
+        Ok(matches)
+    };
+
+    let mut res = CredentialMatches::default();
+
+    for (referent, info) in &pres_req_val.requested_attributes {
+        let names = if let Some(name) = &info.name {
+            vec![name.as_str()]
+        } else if let Some(names) = &info.names {
+            names.iter().map(String::as_str).collect()
+        } else {
+            return Err(err_msg!(
+                r#"Proof Request attribute restriction should contain "name" or "names" param. Current proof request: {:?}"#,
+                pres_req_val
+            ));
+        };
+        let matches = find_matches(
+            info.restrictions.as_ref(),
+            info.non_revoked.as_ref(),
+            &|credential, _| {
+                names
+                    .iter()
+                    .all(|name| credential.raw_value(name).is_some())
+            },
+        )?;
+        res.requested_attributes.insert(referent.clone(), matches);
+    }
+
+    for (referent, info) in &pres_req_val.requested_predicates {
+        let predicate = build_predicate(info);
+        let matches = find_matches(
+            info.restrictions.as_ref(),
+            info.non_revoked.as_ref(),
+            &|credential, identifier| {
+                credential
+                    .encoded_value(&info.name, schemas.get(&identifier.schema_id))
+                    .is_some_and(|encoded| check_predicate_value(&predicate, &encoded).is_ok())
+            },
+        )?;
+        res.requested_predicates.insert(referent.clone(), matches);
+    }
+
+    trace!("match_credentials <<< res: {:?}", res);
+
+    Ok(res)
+}
+
+fn matches_restrictions<T: MatchCredential>(
+    credential: &T,
+    identifier: &Identifier,
+    restrictions: Option<&Query>,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+) -> Result<bool> {
+    let Some(restrictions) = restrictions else {
+        return Ok(true);
+    };
+    let filter = gather_filter_info(identifier, schemas, cred_defs)?;
+    let no_attr_values = HashMap::new();
+
+    // Unlike the verifier, which only sees revealed values, the holder knows every raw value
+    // so attribute tags are checked against the credential itself.
+    let check_tag = |tag: &str, operator: TagOperator, tag_value: &str| match parse_attr_tag(tag) {
+        Some((attr_name, "value")) => match credential.raw_value(attr_name) {
+            Some(raw_value) if operator.matches(tag, &raw_value, tag_value) => Ok(()),
+            _ => Err(err_msg!(
+                ProofRejected,
+                "\"{}\" value does not match: expected: {} \"{}\"",
+                tag,
+                operator,
+                tag_value
+            )),
+        },
+        Some((attr_name, _)) => credential
+            .raw_value(attr_name)
+            .map(|_| ())
+            .ok_or_else(|| err_msg!(ProofRejected, "Attribute \"{}\" is missing", attr_name)),
+        None => process_filter(&no_attr_values, tag, operator, tag_value, &filter),
+    };
+
+    Ok(process_query(restrictions, &check_tag).is_ok())
+}
+
+/// Check that a credential can prove a requested predicate before creating a presentation.
+///
+/// The predicate is evaluated against the encoded attribute value, so dates issued with a
+/// typed encoding are compared in that encoding. An error describes why the predicate
+/// cannot be proven.
+pub fn check_credential_predicate(
+    credential: &Credential,
+    predicate: &PredicateInfo,
+) -> Result<()> {
+    trace!(
+        "check_credential_predicate >>> credential: {:?}, predicate: {:?}",
+        credential, predicate
+    );
+
+    check_predicate_values(&credential.values, predicate)?;
+
+    trace!("check_credential_predicate <<<");
+
+    Ok(())
+}
+
+fn check_predicate_values(values: &CredentialValues, predicate: &PredicateInfo) -> Result<()> {
+    check_predicate_satisfiable(&build_predicate(predicate), values)
+}
+
+impl<T: MatchCredential + ?Sized> MatchCredential for &T {
+    fn identifier(&self) -> Result<Identifier> {
+        (**self).identifier()
+    }
+
+    fn raw_value(&self, attr_name: &str) -> Option<String> {
+        (**self).raw_value(attr_name)
+    }
+
+    fn encoded_value(&self, attr_name: &str, schema: Option<&Schema>) -> Option<String> {
+        (**self).encoded_value(attr_name, schema)
+    }
+}
+
+/// Builds the [`PresentCredentials`] for a presentation request from a set of candidate
+/// credentials.
+///
+/// Each referent is filled by a candidate able to satisfy it, chosen according to the
+/// [`SelectionPolicy`] unless a candidate was selected explicitly for the referent. For
+/// referents requesting non-revocation, a revocable credential is only used when its
+/// revocation state was taken within the requested interval. Attribute referents without
+/// restrictions which no candidate can fill are returned to be self-attested.
+#[derive(Debug)]
+pub struct PresentCredentialsBuilder<'p, T> {
+    candidates: Vec<(&'p T, Option<&'p CredentialRevocationState>)>,
+    policy: SelectionPolicy,
+    selected: HashMap<String, usize>,
+}
+
+impl<T> Default for PresentCredentialsBuilder<'_, T> {
+    fn default() -> Self {
+        Self {
+            candidates: Vec::new(),
+            policy: SelectionPolicy::default(),
+            selected: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Referent {
+    Attribute(String),
+    Predicate(String),
+}
+
+impl Referent {
+    fn name(&self) -> &str {
+        match self {
+            Self::Attribute(name) | Self::Predicate(name) => name,
+        }
+    }
+}
+
+impl<'p, T: MatchCredential> PresentCredentialsBuilder<'p, T> {
+    /// Add a candidate credential along with its revocation state, returning its index
+    pub fn add_credential(
+        &mut self,
+        cred: &'p T,
+        rev_state: Option<&'p CredentialRevocationState>,
+    ) -> usize {
+        self.candidates.push((cred, rev_state));
+        self.candidates.len() - 1
+    }
+
+    pub fn set_policy(&mut self, policy: SelectionPolicy) {
+        self.policy = policy;
+    }
+
+    /// Fill `referent` with the candidate at `index`, regardless of the selection policy
+    pub fn select_credential(&mut self, referent: impl Into<String>, index: usize) {
+        self.selected.insert(referent.into(), index);
+    }
+
+    pub fn build(
+        self,
+        pres_req: &PresentationRequest,
+        schemas: &HashMap<SchemaId, Schema>,
+        cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    ) -> Result<SelectedCredentials<'p, T>> {
+        trace!(
+            "PresentCredentialsBuilder::build >>> pres_req: {:?}, candidates: {}, policy: {:?}, selected: {:?}",
+            pres_req,
+            self.candidates.len(),
+            self.policy,
+            self.selected
+        );
+
+        let credentials = self
+            .candidates
+            .iter()
+            .map(|(cred, _)| *cred)
+            .collect::<Vec<&T>>();
+        let matches = match_credentials(pres_req, &credentials, schemas, cred_defs)?;
+        let pres_req_val = pres_req.value();
+
+        // Candidates for each referent, along with whether the revocation state is needed
+        let eligible = |matches: &CredentialMatch| match &matches.non_revoked {
+            None => Some((matches.index, false)),
+            Some(interval) => self.candidates[matches.index]
+                .1
+                .filter(|rev_state| interval.is_valid(rev_state.timestamp).is_ok())
+                .map(|_| (matches.index, true)),
+        };
+        let mut candidates = matches
+            .requested_attributes
+            .iter()
+            .map(|(referent, m)| (Referent::Attribute(referent.clone()), m))
+            .chain(
+                matches
+                    .requested_predicates
+                    .iter()
+                    .map(|(referent, m)| (Referent::Predicate(referent.clone()), m)),
+            )
+            .map(|(referent, m)| (referent, m.iter().filter_map(eligible).collect()))
+            .collect::<BTreeMap<Referent, Vec<(usize, bool)>>>();
+
+        // Both bounds of a requested range must be proven by the same credential
+        let range_bounds = pres_req_val
+            .requested_ranges
+            .keys()
+            .map(|referent| {
+                let (lower, upper) = RangeInfo::predicate_referents(referent);
+                (
+                    referent,
+                    Referent::Predicate(lower),
+                    Referent::Predicate(upper),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (_, lower, upper) in &range_bounds {
+            let indices = |referent: &Referent| {
+                candidates
+                    .get(referent)
+                    .map(|c| {
+                        c.iter()
+                            .map(|(index, _)| *index)
+                            .collect::<HashSet<usize>>()
+                    })
+                    .unwrap_or_default()
+            };
+            let (lower_indices, upper_indices) = (indices(lower), indices(upper));
+            for (referent, other) in [(lower, &upper_indices), (upper, &lower_indices)] {
+                if let Some(referent_candidates) = candidates.get_mut(referent) {
+                    referent_candidates.retain(|(index, _)| other.contains(index));
+                }
+            }
+        }
+
+        let mut assigned: BTreeMap<&Referent, (usize, bool)> = BTreeMap::new();
+
+        for (referent, index) in &self.selected {
+            let mut found = false;
+            for (key, referent_candidates) in &candidates {
+                if key.name() != referent {
+                    continue;
+                }
+                found = true;
+                let candidate = referent_candidates
+                    .iter()
+                    .find(|(idx, _)| idx == index)
+                    .ok_or_else(|| {
+                        err_msg!("Credential {} cannot fill referent {}", index, referent)
+                    })?;
+                assigned.insert(key, *candidate);
+            }
+            if !found {
+                return Err(err_msg!(
+                    "Referent {} not found in presentation request",
+                    referent
+                ));
+            }
+        }
+
+        // A selected range bound carries the other bound along with it
+        for (range_referent, lower, upper) in &range_bounds {
+            let (bound, other) = match (assigned.get(lower), assigned.get(upper)) {
+                (Some(l), Some(u)) if l.0 != u.0 => {
+                    return Err(err_msg!(
+                        "Bounds of requested range {} must be filled by the same credential",
+                        range_referent
+                    ));
+                }
+                (Some(candidate), None) => (*candidate, upper),
+                (None, Some(candidate)) => (*candidate, lower),
+                _ => continue,
+            };
+            if let Some((key, _)) = candidates.get_key_value(other) {
+                assigned.insert(key, bound);
+            }
+        }
+
+        let mut self_attested = Vec::new();
+        let mut unmatched = Vec::new();
+        for (referent, referent_candidates) in &candidates {
+            if !referent_candidates.is_empty() || assigned.contains_key(referent) {
+                continue;
+            }
+            match referent {
+                Referent::Attribute(name)
+                    if is_self_attestable(&pres_req_val.requested_attributes[name]) =>
+                {
+                    self_attested.push(name.clone());
+                }
+                _ => unmatched.push(referent.name()),
+            }
+        }
+        if !unmatched.is_empty() {
+            return Err(err_msg!(
+                "No credential can fill referents: {}",
+                unmatched.join(", ")
+            ));
+        }
+
+        let mut pending = candidates
+            .iter()
+            .filter(|(referent, c)| !c.is_empty() && !assigned.contains_key(referent))
+            .collect::<Vec<_>>();
+
+        match self.policy {
+            SelectionPolicy::FewestCredentials => {
+                while !pending.is_empty() {
+                    // Reuse credentials which are presented already, then pick the one
+                    // filling the most remaining referents
+                    let used = assigned
+                        .values()
+                        .map(|(index, _)| *index)
+                        .collect::<HashSet<usize>>();
+                    let mut coverage: BTreeMap<usize, usize> = BTreeMap::new();
+                    for (_, referent_candidates) in &pending {
+                        for (index, _) in referent_candidates.iter() {
+                            *coverage.entry(*index).or_default() += 1;
+                        }
+                    }
+                    let best = coverage
+                        .into_iter()
+                        .max_by_key(|(index, count)| {
+                            (used.contains(index), *count, std::cmp::Reverse(*index))
+                        })
+                        .map(|(index, _)| index)
+                        .ok_or_else(|| err_msg!(InvalidState, "No candidate credential left"))?;
+                    pending.retain(|(referent, referent_candidates)| {
+                        match referent_candidates.iter().find(|(idx, _)| *idx == best) {
+                            Some(candidate) => {
+                                assigned.insert(referent, *candidate);
+                                false
+                            }
+                            None => true,
+                        }
+                    });
+                }
+            }
+            SelectionPolicy::LastAdded => {
+                for (referent, referent_candidates) in pending {
+                    if let Some(candidate) = referent_candidates.iter().max_by_key(|(idx, _)| idx) {
+                        assigned.insert(referent, *candidate);
+                    }
+                }
+            }
+            SelectionPolicy::NonRevocable => {
+                let revocable = credentials
+                    .iter()
+                    .map(|cred| Ok(cred.identifier()?.rev_reg_id.is_some()))
+                    .collect::<Result<Vec<bool>>>()?;
+                for (referent, referent_candidates) in pending {
+                    if let Some(candidate) = referent_candidates
+                        .iter()
+                        .find(|(idx, _)| !revocable[*idx])
+                        .or_else(|| referent_candidates.first())
+                    {
+                        assigned.insert(referent, *candidate);
+                    }
+                }
+            }
+        }
+
+        let mut by_credential: BTreeMap<usize, Vec<(&Referent, bool)>> = BTreeMap::new();
+        for (referent, (index, needs_rev_state)) in assigned {
+            by_credential
+                .entry(index)
+                .or_default()
+                .push((referent, needs_rev_state));
+        }
+
+        let mut present = PresentCredentials::default();
+        for (index, referents) in by_credential {
+            let (cred, rev_state) = self.candidates[index];
+            let rev_state = rev_state.filter(|_| referents.iter().any(|(_, needs)| *needs));
+            let mut add = present.add_credential(
+                cred,
+                rev_state.map(|rev_state| rev_state.timestamp),
+                rev_state,
+            );
+            for (referent, _) in referents {
+                match referent {
+                    Referent::Attribute(name) => add.add_requested_attribute(name.clone(), true),
+                    Referent::Predicate(name) => add.add_requested_predicate(name.clone()),
+                }
+            }
+        }
+
+        trace!(
+            "PresentCredentialsBuilder::build <<< self_attested: {:?}",
+            self_attested
+        );
+
+        Ok(SelectedCredentials {
+            present,
+            self_attested,
+        })
+    }
+}
+
+fn is_self_attestable(info: &AttributeInfo) -> bool {
+    info.names.is_none()
+        && match info.restrictions.as_ref() {
+            Some(Query::And(array) | Query::Or(array)) => array.is_empty(),
+            None => true,
+            Some(_) => false,
+        }
+}
+
 /// Create a [`CredentialRevocationState`] based on a [`Witness`], [`RevocationStatusList`] and
 /// timestamp.
 pub fn create_revocation_state_with_witness(
@@ -585,6 +1162,31 @@ pub fn create_or_update_revocation_state(
     rev_state: Option<&CredentialRevocationState>, // for witness update
     old_rev_status_list: Option<&RevocationStatusList>, // for witness update
 ) -> Result<CredentialRevocationState> {
+    let tails_reader = TailsFileReader::new(tails_path)?;
+    create_or_update_revocation_state_with_accessor(
+        &tails_reader,
+        rev_reg_def,
+        rev_status_list,
+        rev_reg_idx,
+        rev_state,
+        old_rev_status_list,
+    )
+}
+
+/// Create or update the revocation state of a credential, see
+/// [`create_or_update_revocation_state`], reading the tails through `tails_accessor` instead
+/// of opening a tails file
+pub fn create_or_update_revocation_state_with_accessor<TA>(
+    tails_accessor: &TA,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_status_list: &RevocationStatusList,
+    rev_reg_idx: u32,
+    rev_state: Option<&CredentialRevocationState>, // for witness update
+    old_rev_status_list: Option<&RevocationStatusList>, // for witness update
+) -> Result<CredentialRevocationState>
+where
+    TA: RevocationTailsAccessor,
+{
     trace!(
         "create_or_update_revocation_state >>> revoc_reg_def: {:?}, \
     rev_status_list: {:?}, rev_reg_idx: {},  rev_state: {:?}, old_rev_status_list {:?}",
@@ -600,60 +1202,30 @@ pub fn create_or_update_revocation_state(
         err_msg!("Timestamp is required to create or update the revocation state")
     })?;
 
-    let mut issued = HashSet::<u32>::new();
-    let mut revoked = HashSet::<u32>::new();
-    let tails_reader = TailsFileReader::new(tails_path)?;
-    let witness = if let (Some(source_rev_state), Some(source_rev_list)) =
-        (rev_state, old_rev_status_list)
-    {
-        create_index_deltas(
-            &rev_status_list
-                .state_owned()
-                .bitxor(source_rev_list.state()),
-            rev_status_list.state(),
-            &mut issued,
-            &mut revoked,
-        );
-
-        let source_rev_reg: Option<RevocationRegistry> = source_rev_list.into();
-
-        let rev_reg_delta = RevocationRegistryDelta::from_parts(
-            source_rev_reg.as_ref(),
-            &rev_reg,
-            &issued,
-            &revoked,
-        );
-
-        let mut witness = source_rev_state.witness.clone();
-        witness.update(
-            rev_reg_idx,
-            rev_reg_def.value.max_cred_num,
-            &rev_reg_delta,
-            &tails_reader,
-        )?;
-        witness
-    } else {
-        let list_size = usize::try_from(rev_reg_def.value.max_cred_num)
-            .map_err(|e| Error::from_msg(crate::ErrorKind::InvalidState, e.to_string()))?;
-        // Issuance by default
-        let bit: usize = 0;
-        let list = bitvec![bit; list_size];
-        create_index_deltas(
-            &rev_status_list.state_owned().bitxor(list),
-            rev_status_list.state(),
-            &mut issued,
-            &mut revoked,
-        );
-        let rev_reg_delta = RevocationRegistryDelta::from_parts(None, &rev_reg, &issued, &revoked);
-        Witness::new(
-            rev_reg_idx,
-            rev_reg_def.value.max_cred_num,
+    let max_cred_num = rev_reg_def.value.max_cred_num;
+    let witness =
+        if let (Some(source_rev_state), Some(source_rev_list)) = (rev_state, old_rev_status_list) {
+            let rev_reg_delta = revocation_registry_delta(
+                &rev_reg,
+                rev_status_list,
+                Some(source_rev_list),
+                max_cred_num,
+            )?;
+            let mut witness = source_rev_state.witness.clone();
+            witness.update(rev_reg_idx, max_cred_num, &rev_reg_delta, tails_accessor)?;
+            witness
+        } else {
+            let rev_reg_delta =
+                revocation_registry_delta(&rev_reg, rev_status_list, None, max_cred_num)?;
             // issuance by default
-            true,
-            &rev_reg_delta,
-            &tails_reader,
-        )?
-    };
+            Witness::new(
+                rev_reg_idx,
+                max_cred_num,
+                true,
+                &rev_reg_delta,
+                tails_accessor,
+            )?
+        };
 
     Ok(CredentialRevocationState {
         witness,
@@ -662,21 +1234,142 @@ pub fn create_or_update_revocation_state(
     })
 }
 
-fn create_index_deltas(
-    delta: &bitvec::vec::BitVec,
-    list: &bitvec::vec::BitVec,
-    issued: &mut HashSet<u32>,
-    revoked: &mut HashSet<u32>,
-) {
-    for i in delta.iter_ones() {
-        if list[i] {
-            // true means cred has been revoked
-            revoked.insert(i as u32);
+/// Create or update the revocation states of several credentials issued from the same
+/// revocation registry, in the order of `credentials`.
+///
+/// Each `(rev_reg_idx, rev_state)` entry is handled as by [`create_or_update_revocation_state`]:
+/// the revocation state is updated when both `rev_state` and `old_rev_status_list` are given,
+/// and created otherwise. The index deltas between the status lists are only computed once, and
+/// each tail is read from the tails file at most once for the whole batch.
+pub fn create_or_update_revocation_states(
+    tails_path: &str,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_status_list: &RevocationStatusList,
+    old_rev_status_list: Option<&RevocationStatusList>,
+    credentials: &[(u32, Option<&CredentialRevocationState>)],
+) -> Result<Vec<CredentialRevocationState>> {
+    let tails_reader = TailsFileReader::new(tails_path)?;
+    create_or_update_revocation_states_with_accessor(
+        &tails_reader,
+        rev_reg_def,
+        rev_status_list,
+        old_rev_status_list,
+        credentials,
+    )
+}
+
+/// Create or update the revocation states of several credentials, see
+/// [`create_or_update_revocation_states`], reading the tails through `tails_accessor` instead
+/// of opening a tails file
+pub fn create_or_update_revocation_states_with_accessor<TA>(
+    tails_accessor: &TA,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_status_list: &RevocationStatusList,
+    old_rev_status_list: Option<&RevocationStatusList>,
+    credentials: &[(u32, Option<&CredentialRevocationState>)],
+) -> Result<Vec<CredentialRevocationState>>
+where
+    TA: RevocationTailsAccessor,
+{
+    trace!(
+        "create_or_update_revocation_states >>> revoc_reg_def: {:?}, \
+    rev_status_list: {:?}, old_rev_status_list {:?}, credentials: {:?}",
+        rev_reg_def, rev_status_list, old_rev_status_list, credentials,
+    );
+
+    let rev_reg: Option<RevocationRegistry> = rev_status_list.into();
+    let rev_reg = rev_reg.ok_or_else(|| {
+        err_msg!("revocation registry is required to create or update the revocation state")
+    })?;
+
+    let timestamp = rev_status_list.timestamp().ok_or_else(|| {
+        err_msg!("Timestamp is required to create or update the revocation state")
+    })?;
+
+    let tails_accessor = CachingTailsAccessor::new(tails_accessor);
+    let max_cred_num = rev_reg_def.value.max_cred_num;
+    let mut update_delta = None;
+    let mut issuance_delta = None;
+
+    let mut rev_states = Vec::with_capacity(credentials.len());
+    for (rev_reg_idx, rev_state) in credentials {
+        let witness = if let (Some(source_rev_state), Some(source_rev_list)) =
+            (rev_state, old_rev_status_list)
+        {
+            let rev_reg_delta = match update_delta {
+                Some(ref rev_reg_delta) => rev_reg_delta,
+                None => update_delta.insert(revocation_registry_delta(
+                    &rev_reg,
+                    rev_status_list,
+                    Some(source_rev_list),
+                    max_cred_num,
+                )?),
+            };
+            let mut witness = source_rev_state.witness.clone();
+            witness.update(*rev_reg_idx, max_cred_num, rev_reg_delta, &tails_accessor)?;
+            witness
         } else {
-            // false means cred has not been
-            issued.insert(i as u32);
-        }
+            let rev_reg_delta = match issuance_delta {
+                Some(ref rev_reg_delta) => rev_reg_delta,
+                None => issuance_delta.insert(revocation_registry_delta(
+                    &rev_reg,
+                    rev_status_list,
+                    None,
+                    max_cred_num,
+                )?),
+            };
+            // issuance by default
+            Witness::new(
+                *rev_reg_idx,
+                max_cred_num,
+                true,
+                rev_reg_delta,
+                &tails_accessor,
+            )?
+        };
+
+        rev_states.push(CredentialRevocationState {
+            witness,
+            rev_reg: rev_reg.clone(),
+            timestamp,
+        });
     }
+
+    trace!(
+        "create_or_update_revocation_states <<< rev_states: {:?}",
+        rev_states
+    );
+
+    Ok(rev_states)
+}
+
+/// Build the registry delta from `source_rev_list` to `rev_status_list`, or from a registry
+/// with every credential issued when there is no source list
+fn revocation_registry_delta(
+    rev_reg: &RevocationRegistry,
+    rev_status_list: &RevocationStatusList,
+    source_rev_list: Option<&RevocationStatusList>,
+    max_cred_num: u32,
+) -> Result<RevocationRegistryDelta> {
+    let (issued, revoked) = if let Some(source_rev_list) = source_rev_list {
+        index_deltas(source_rev_list.state(), rev_status_list.state())
+    } else {
+        let list_size = usize::try_from(max_cred_num)
+            .map_err(|e| Error::from_msg(crate::ErrorKind::InvalidState, e.to_string()))?;
+        // Issuance by default
+        let bit: usize = 0;
+        let list = bitvec![bit; list_size];
+        index_deltas(&list, rev_status_list.state())
+    };
+    let issued: HashSet<u32> = issued.into_iter().collect();
+    let revoked: HashSet<u32> = revoked.into_iter().collect();
+    let source_rev_reg: Option<RevocationRegistry> = source_rev_list.and_then(Into::into);
+    Ok(RevocationRegistryDelta::from_parts(
+        source_rev_reg.as_ref(),
+        rev_reg,
+        &issued,
+        &revoked,
+    ))
 }
 
 fn get_credential_values_for_attribute(
@@ -768,6 +1461,7 @@ fn update_requested_proof(
         }
     }
 
+    proof_req.check_requested_ranges(req_predicates_for_credential)?;
     for predicate_referent in req_predicates_for_credential {
         requested_proof.predicates.insert(
             predicate_referent.clone(),
@@ -873,7 +1567,6 @@ impl<'a> CLProofBuilder<'a> {
         )?;
 
         let credential_schema = build_credential_schema(schema)?;
-        let credential_values = build_credential_values(credential_values, Some(link_secret))?;
 
         let attributes = present.get_revealed_attributes();
         let predicates = &present.requested_predicates;
@@ -884,6 +1577,11 @@ impl<'a> CLProofBuilder<'a> {
         let (predicates_for_credential, pred_nonrevoked_interval) = self
             .presentation_request
             .get_requested_predicates(predicates)?;
+        for predicate in &predicates_for_credential {
+            check_predicate_satisfiable(predicate, credential_values)?;
+        }
+
+        let credential_values = build_credential_values(credential_values, Some(link_secret))?;
 
         let non_revoked_interval = get_non_revoked_interval(
             attrs_nonrevoked_interval,
@@ -925,9 +1623,8 @@ impl<'a> CLProofBuilder<'a> {
     }
 
     pub(crate) fn build(&mut self) -> Result<Proof> {
-        let proof = self
-            .proof_builder
-            .finalize(self.presentation_request.nonce.as_native())?;
+        let nonce = self.presentation_request.proof_nonce()?;
+        let proof = self.proof_builder.finalize(nonce.as_native())?;
         Ok(proof)
     }
 
@@ -1026,6 +1723,529 @@ mod tests {
         }
     }
 
+    mod check_credential_predicate {
+        use super::*;
+        use crate::data_types::date::{CalendarDate, DateEncoding};
+        use crate::types::MakeCredentialValues;
+
+        fn _cred_values(dob: &str) -> CredentialValues {
+            let mut values = MakeCredentialValues::default();
+            values.add_raw("date_of_birth", dob).unwrap();
+            values.into()
+        }
+
+        fn _adult_predicate() -> PredicateInfo {
+            PredicateInfo::for_minimum_age(
+                "Date_Of_Birth",
+                18,
+                "2024-06-15".parse::<CalendarDate>().unwrap(),
+                DateEncoding::YearMonthDay,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn check_predicate_values_works() {
+            check_predicate_values(&_cred_values("20060615"), &_adult_predicate()).unwrap();
+        }
+
+        #[test]
+        fn check_predicate_values_fails_for_unsatisfied_predicate() {
+            let err =
+                check_predicate_values(&_cred_values("20060616"), &_adult_predicate()).unwrap_err();
+            assert!(err.to_string().contains("does not satisfy"));
+        }
+
+        #[test]
+        fn check_predicate_values_fails_for_non_numeric_value() {
+            check_predicate_values(&_cred_values("2006-06-15"), &_adult_predicate()).unwrap_err();
+        }
+    }
+
+    mod match_credentials {
+        use super::*;
+        use crate::data_types::date::DateEncoding;
+
+        struct MockCredential {
+            rev_reg_id: Option<&'static str>,
+            values: HashMap<&'static str, &'static str>,
+        }
+
+        impl MatchCredential for MockCredential {
+            fn identifier(&self) -> Result<Identifier> {
+                Ok(Identifier {
+                    schema_id: SchemaId::new_unchecked("schema:id"),
+                    cred_def_id: CredentialDefinitionId::new_unchecked("creddef:id"),
+                    rev_reg_id: self
+                        .rev_reg_id
+                        .map(RevocationRegistryDefinitionId::new_unchecked),
+                    timestamp: None,
+                })
+            }
+
+            fn raw_value(&self, attr_name: &str) -> Option<String> {
+                self.values
+                    .iter()
+                    .find(|(key, _)| attr_common_view(key) == attr_common_view(attr_name))
+                    .map(|(_, value)| value.to_string())
+            }
+        }
+
+        fn _credentials() -> Vec<MockCredential> {
+            vec![
+                MockCredential {
+                    rev_reg_id: None,
+                    values: hashmap!("name" => "Alex", "age" => "28"),
+                },
+                MockCredential {
+                    rev_reg_id: Some("revreg:id"),
+                    values: hashmap!("Name" => "John", "age" => "17", "role" => "Developer"),
+                },
+            ]
+        }
+
+        fn _pres_req(value: serde_json::Value) -> PresentationRequest {
+            let mut pres_req = serde_json::json!({
+                "nonce": "123432421212",
+                "name": "proof_req_1",
+                "version": "0.1",
+                "requested_attributes": {},
+                "requested_predicates": {},
+            });
+            pres_req
+                .as_object_mut()
+                .unwrap()
+                .extend(value.as_object().unwrap().clone());
+            serde_json::from_value(pres_req).unwrap()
+        }
+
+        fn _indices(matches: &[CredentialMatch]) -> Vec<usize> {
+            matches.iter().map(|m| m.index).collect()
+        }
+
+        #[test]
+        fn match_credentials_works_for_attributes_and_names() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_attributes": {
+                    "name": { "name": "name" },
+                    "group": { "names": ["name", "role"] },
+                    "missing": { "name": "height" },
+                }
+            }));
+
+            let res =
+                match_credentials(&pres_req, &_credentials(), &HashMap::new(), &HashMap::new())
+                    .unwrap();
+
+            assert_eq!(_indices(&res.requested_attributes["name"]), vec![0, 1]);
+            assert_eq!(_indices(&res.requested_attributes["group"]), vec![1]);
+            assert!(res.requested_attributes["missing"].is_empty());
+            assert_eq!(res.unmatched_attributes(), vec!["missing"]);
+        }
+
+        #[test]
+        fn match_credentials_works_for_predicates() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_predicates": {
+                    "adult": { "name": "age", "p_type": ">=", "p_value": 18 },
+                    "minor": { "name": "age", "p_type": "<", "p_value": 18 },
+                    "not_numeric": { "name": "name", "p_type": ">", "p_value": 0 },
+                }
+            }));
+
+            let res =
+                match_credentials(&pres_req, &_credentials(), &HashMap::new(), &HashMap::new())
+                    .unwrap();
+
+            assert_eq!(_indices(&res.requested_predicates["adult"]), vec![0]);
+            assert_eq!(_indices(&res.requested_predicates["minor"]), vec![1]);
+            assert_eq!(res.unmatched_predicates(), vec!["not_numeric"]);
+        }
+
+        #[test]
+        fn match_credentials_compares_predicates_in_schema_encoding() {
+            let schema: Schema = serde_json::from_value(serde_json::json!({
+                "name": "gvt",
+                "version": "1.0",
+                "attrNames": ["name", "birthdate"],
+                "issuerId": "mock:uri",
+                "attrEncodings": { "birthdate": { "type": "date" } }
+            }))
+            .unwrap();
+            let schemas = hashmap!(SchemaId::new_unchecked("schema:id") => schema);
+            let credentials = vec![
+                MockCredential {
+                    rev_reg_id: None,
+                    values: hashmap!("name" => "Alex", "birthdate" => "2000-02-29"),
+                },
+                MockCredential {
+                    rev_reg_id: None,
+                    values: hashmap!("name" => "John", "birthdate" => "2010-01-01"),
+                },
+            ];
+            let adult = PredicateInfo::for_minimum_age(
+                "birthdate",
+                18,
+                "2024-06-15".parse().unwrap(),
+                DateEncoding::DaysFromCe,
+            )
+            .unwrap();
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_predicates": { "adult": adult }
+            }));
+
+            let res =
+                match_credentials(&pres_req, &credentials, &schemas, &HashMap::new()).unwrap();
+            assert_eq!(_indices(&res.requested_predicates["adult"]), vec![0]);
+
+            // without the schema encoding the date cannot be compared
+            let res = match_credentials(&pres_req, &credentials, &HashMap::new(), &HashMap::new())
+                .unwrap();
+            assert_eq!(res.unmatched_predicates(), vec!["adult"]);
+        }
+
+        #[test]
+        fn match_credentials_returns_non_revoked_interval_for_revocable_credentials() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_attributes": {
+                    "global": { "name": "name" },
+                    "local": { "name": "name", "non_revoked": { "from": 5, "to": 10 } },
+                },
+                "non_revoked": { "to": 20 },
+            }));
+
+            let res =
+                match_credentials(&pres_req, &_credentials(), &HashMap::new(), &HashMap::new())
+                    .unwrap();
+
+            let global = &res.requested_attributes["global"];
+            assert_eq!(global[0].non_revoked, None);
+            assert_eq!(
+                global[1].non_revoked,
+                Some(NonRevokedInterval::new(None, Some(20)))
+            );
+            let local = &res.requested_attributes["local"];
+            assert_eq!(local[0].non_revoked, None);
+            assert_eq!(
+                local[1].non_revoked,
+                Some(NonRevokedInterval::new(Some(5), Some(10)))
+            );
+        }
+
+        // Referents filled by each credential, and self-attested referents
+        type Selection = (Vec<(usize, Vec<String>)>, Vec<String>);
+
+        fn _build(
+            policy: SelectionPolicy,
+            selected: &[(&str, usize)],
+            pres_req: &PresentationRequest,
+            credentials: &[MockCredential],
+        ) -> Result<Selection> {
+            let mut builder = PresentCredentialsBuilder::default();
+            builder.set_policy(policy);
+            for credential in credentials {
+                builder.add_credential(credential, None);
+            }
+            for (referent, index) in selected {
+                builder.select_credential(*referent, *index);
+            }
+            let selected = builder.build(pres_req, &HashMap::new(), &HashMap::new())?;
+            let present = selected
+                .present
+                .0
+                .iter()
+                .map(|present| {
+                    let index = credentials
+                        .iter()
+                        .position(|cred| std::ptr::eq(cred, present.cred))
+                        .unwrap();
+                    let mut referents = present
+                        .requested_attributes
+                        .iter()
+                        .map(|(referent, _)| referent.clone())
+                        .chain(present.requested_predicates.iter().cloned())
+                        .collect::<Vec<String>>();
+                    referents.sort();
+                    (index, referents)
+                })
+                .collect();
+            Ok((present, selected.self_attested))
+        }
+
+        fn _names_and_roles() -> PresentationRequest {
+            _pres_req(serde_json::json!({
+                "requested_attributes": {
+                    "name": { "name": "name" },
+                    "role": { "name": "role" },
+                    "phone": { "name": "phone" },
+                }
+            }))
+        }
+
+        #[test]
+        fn present_credentials_builder_works_for_fewest_credentials() {
+            let (present, self_attested) = _build(
+                SelectionPolicy::FewestCredentials,
+                &[],
+                &_names_and_roles(),
+                &_credentials(),
+            )
+            .unwrap();
+
+            assert_eq!(present, vec![(1, vec!["name".into(), "role".into()])]);
+            assert_eq!(self_attested, vec!["phone".to_string()]);
+        }
+
+        #[test]
+        fn present_credentials_builder_works_for_non_revocable_and_last_added() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_attributes": { "name": { "name": "name" } }
+            }));
+
+            let (present, _) = _build(
+                SelectionPolicy::NonRevocable,
+                &[],
+                &pres_req,
+                &_credentials(),
+            )
+            .unwrap();
+            assert_eq!(present, vec![(0, vec!["name".into()])]);
+
+            let (present, _) =
+                _build(SelectionPolicy::LastAdded, &[], &pres_req, &_credentials()).unwrap();
+            assert_eq!(present, vec![(1, vec!["name".into()])]);
+        }
+
+        #[test]
+        fn present_credentials_builder_works_for_selected_credential() {
+            let (present, _) = _build(
+                SelectionPolicy::FewestCredentials,
+                &[("name", 0)],
+                &_names_and_roles(),
+                &_credentials(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                present,
+                vec![(0, vec!["name".into()]), (1, vec!["role".into()])]
+            );
+        }
+
+        #[test]
+        fn present_credentials_builder_keeps_range_bounds_on_one_credential() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_ranges": {
+                    "working_age": { "name": "age", "lower": 18, "upper": 30 }
+                }
+            }));
+            let mut credentials = _credentials();
+            credentials.push(MockCredential {
+                rev_reg_id: None,
+                values: hashmap!("name" => "Mary", "age" => "45"),
+            });
+            let (lower, upper) = RangeInfo::predicate_referents("working_age");
+
+            // the credentials added last satisfy one bound each, only the first satisfies both
+            let (present, _) =
+                _build(SelectionPolicy::LastAdded, &[], &pres_req, &credentials).unwrap();
+            assert_eq!(present, vec![(0, vec![lower.clone(), upper.clone()])]);
+
+            let (present, _) = _build(
+                SelectionPolicy::LastAdded,
+                &[(lower.as_str(), 0)],
+                &pres_req,
+                &credentials,
+            )
+            .unwrap();
+            assert_eq!(present, vec![(0, vec![lower.clone(), upper])]);
+
+            let _err = _build(
+                SelectionPolicy::LastAdded,
+                &[(lower.as_str(), 2)],
+                &pres_req,
+                &credentials,
+            )
+            .unwrap_err();
+        }
+
+        #[test]
+        fn present_credentials_builder_fails_for_invalid_selection() {
+            let _err = _build(
+                SelectionPolicy::FewestCredentials,
+                &[("role", 0)],
+                &_names_and_roles(),
+                &_credentials(),
+            )
+            .unwrap_err();
+            let _err = _build(
+                SelectionPolicy::FewestCredentials,
+                &[("unknown", 0)],
+                &_names_and_roles(),
+                &_credentials(),
+            )
+            .unwrap_err();
+        }
+
+        #[test]
+        fn present_credentials_builder_fails_without_revocation_state() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_attributes": {
+                    "name": { "name": "name" },
+                },
+                "requested_predicates": {
+                    "age": { "name": "age", "p_type": ">", "p_value": 0 },
+                },
+                "non_revoked": { "to": 20 },
+            }));
+            let credentials = vec![_credentials().remove(1)];
+
+            let _err = _build(
+                SelectionPolicy::FewestCredentials,
+                &[],
+                &pres_req,
+                &credentials,
+            )
+            .unwrap_err();
+        }
+
+        #[test]
+        fn match_credentials_fails_for_restrictions_without_cred_def() {
+            let pres_req = _pres_req(serde_json::json!({
+                "requested_attributes": {
+                    "name": { "name": "name", "restrictions": { "attr::name::value": "Alex" } },
+                }
+            }));
+
+            let _err =
+                match_credentials(&pres_req, &_credentials(), &HashMap::new(), &HashMap::new())
+                    .unwrap_err();
+        }
+    }
+
+    mod create_or_update_revocation_states {
+        use super::*;
+        use crate::issuer;
+        use crate::tails::TailsFileWriter;
+        use crate::types::{CredentialDefinitionConfig, SignatureType};
+        use std::collections::BTreeSet;
+
+        fn _witnesses(rev_states: &[CredentialRevocationState]) -> Vec<serde_json::Value> {
+            rev_states
+                .iter()
+                .map(|rev_state| serde_json::to_value(&rev_state.witness).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn batch_matches_single_credential_updates() {
+            let issuer_id: crate::data_types::issuer_id::IssuerId =
+                "sample:uri".try_into().unwrap();
+            let schema = issuer::create_schema(
+                "schema:name",
+                "1.0",
+                issuer_id.clone(),
+                vec!["name".to_owned()].into(),
+            )
+            .unwrap();
+            let (cred_def, _, _) = issuer::create_credential_definition(
+                "schema:id".try_into().unwrap(),
+                &schema,
+                issuer_id,
+                "default",
+                SignatureType::CL,
+                CredentialDefinitionConfig {
+                    support_revocation: true,
+                },
+            )
+            .unwrap();
+            let (rev_reg_def, rev_reg_def_priv) = issuer::create_revocation_registry_def(
+                &cred_def,
+                "cred:def".try_into().unwrap(),
+                "default",
+                crate::types::RegistryType::CL_ACCUM,
+                5,
+                &mut TailsFileWriter::new(None),
+            )
+            .unwrap();
+            let tails_path = rev_reg_def.value.tails_location.clone();
+            let list = issuer::create_revocation_status_list(
+                &cred_def,
+                "rev:reg".try_into().unwrap(),
+                &rev_reg_def,
+                &rev_reg_def_priv,
+                true,
+                Some(10),
+            )
+            .unwrap();
+            let indices = [1, 2, 4];
+
+            let created = create_or_update_revocation_states(
+                &tails_path,
+                &rev_reg_def,
+                &list,
+                None,
+                &indices.map(|idx| (idx, None)),
+            )
+            .unwrap();
+            let expected = indices
+                .iter()
+                .map(|idx| {
+                    create_or_update_revocation_state(
+                        &tails_path,
+                        &rev_reg_def,
+                        &list,
+                        *idx,
+                        None,
+                        None,
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(_witnesses(&created), _witnesses(&expected));
+
+            let new_list = issuer::update_revocation_status_list(
+                &cred_def,
+                &rev_reg_def,
+                &rev_reg_def_priv,
+                &list,
+                None,
+                Some(BTreeSet::from([2, 3])),
+                Some(20),
+            )
+            .unwrap();
+            let credentials = indices
+                .iter()
+                .copied()
+                .zip(created.iter().map(Some))
+                .collect::<Vec<_>>();
+            let updated = create_or_update_revocation_states(
+                &tails_path,
+                &rev_reg_def,
+                &new_list,
+                Some(&list),
+                &credentials,
+            )
+            .unwrap();
+            let expected = credentials
+                .iter()
+                .map(|(idx, rev_state)| {
+                    create_or_update_revocation_state(
+                        &tails_path,
+                        &rev_reg_def,
+                        &new_list,
+                        *idx,
+                        *rev_state,
+                        Some(&list),
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(_witnesses(&updated), _witnesses(&expected));
+            assert!(updated.iter().all(|rev_state| rev_state.timestamp == 20));
+        }
+    }
+
     mod using_prover_did_with_new_and_legacy_identifiers {
         use crate::{
             data_types::cred_def::{CredentialKeyCorrectnessProof, SignatureType},
@@ -0,0 +1,488 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind as IoErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use crate::data_types::presentation::Identifier;
+use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
+use crate::data_types::schema::{Schema, SchemaId};
+use crate::error::Result;
+use crate::types::{
+    RevocationRegistryDefinition, RevocationStatusList, RevocationStatusListHistory,
+};
+
+/// Source of the ledger objects referenced by credentials and presentations
+///
+/// Implementations fetch schemas, credential definitions, revocation registry definitions
+/// and revocation status lists on demand, so that callers of the `*_with_resolver`
+/// prover and verifier functions do not need to assemble them up front.
+pub trait AnoncredsResolver {
+    fn get_schema(&self, schema_id: &SchemaId) -> Result<Schema>;
+
+    fn get_cred_def(&self, cred_def_id: &CredentialDefinitionId) -> Result<CredentialDefinition>;
+
+    fn get_rev_reg_def(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<RevocationRegistryDefinition>;
+
+    /// Get the revocation status list which was current at the given timestamp
+    fn get_rev_status_list_at(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Result<RevocationStatusList>;
+}
+
+/// Ledger objects resolved for a set of credentials
+#[derive(Debug, Default)]
+pub(crate) struct ResolvedObjects {
+    pub schemas: HashMap<SchemaId, Schema>,
+    pub cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition>,
+    pub rev_reg_defs: HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>,
+    pub rev_status_lists: Vec<RevocationStatusList>,
+}
+
+impl ResolvedObjects {
+    pub(crate) fn resolve<'a, R>(
+        resolver: &R,
+        identifiers: impl IntoIterator<Item = &'a Identifier>,
+    ) -> Result<Self>
+    where
+        R: AnoncredsResolver + ?Sized,
+    {
+        let mut resolved = Self::default();
+        let mut status_lists: HashSet<(RevocationRegistryDefinitionId, u64)> = HashSet::new();
+
+        for identifier in identifiers {
+            if !resolved.schemas.contains_key(&identifier.schema_id) {
+                let schema = resolver.get_schema(&identifier.schema_id)?;
+                resolved
+                    .schemas
+                    .insert(identifier.schema_id.clone(), schema);
+            }
+            if !resolved.cred_defs.contains_key(&identifier.cred_def_id) {
+                let cred_def = resolver.get_cred_def(&identifier.cred_def_id)?;
+                resolved
+                    .cred_defs
+                    .insert(identifier.cred_def_id.clone(), cred_def);
+            }
+            if let Some(rev_reg_id) = identifier.rev_reg_id.as_ref() {
+                if !resolved.rev_reg_defs.contains_key(rev_reg_id) {
+                    let rev_reg_def = resolver.get_rev_reg_def(rev_reg_id)?;
+                    resolved
+                        .rev_reg_defs
+                        .insert(rev_reg_id.clone(), rev_reg_def);
+                }
+                if let Some(timestamp) = identifier.timestamp {
+                    if status_lists.insert((rev_reg_id.clone(), timestamp)) {
+                        let list = resolver.get_rev_status_list_at(rev_reg_id, timestamp)?;
+                        resolved
+                            .rev_status_lists
+                            .push(list.current_at(rev_reg_id, timestamp));
+                    }
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    pub(crate) fn rev_reg_defs(
+        &self,
+    ) -> Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>> {
+        (!self.rev_reg_defs.is_empty()).then_some(&self.rev_reg_defs)
+    }
+
+    pub(crate) fn rev_status_lists(&self) -> Option<Vec<RevocationStatusList>> {
+        (!self.rev_status_lists.is_empty()).then(|| self.rev_status_lists.clone())
+    }
+}
+
+/// Resolver keeping all ledger objects in memory
+#[derive(Debug, Default)]
+pub struct InMemoryResolver {
+    schemas: HashMap<SchemaId, Schema>,
+    cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>,
+    rev_status_lists: HashMap<RevocationRegistryDefinitionId, RevocationStatusListHistory>,
+}
+
+impl InMemoryResolver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_schema(&mut self, schema_id: SchemaId, schema: Schema) {
+        self.schemas.insert(schema_id, schema);
+    }
+
+    pub fn add_cred_def(
+        &mut self,
+        cred_def_id: CredentialDefinitionId,
+        cred_def: CredentialDefinition,
+    ) {
+        self.cred_defs.insert(cred_def_id, cred_def);
+    }
+
+    pub fn add_rev_reg_def(
+        &mut self,
+        rev_reg_def_id: RevocationRegistryDefinitionId,
+        rev_reg_def: RevocationRegistryDefinition,
+    ) {
+        self.rev_reg_defs.insert(rev_reg_def_id, rev_reg_def);
+    }
+
+    /// Add a revocation status list, keyed by its registry identifier and timestamp
+    pub fn add_rev_status_list(&mut self, rev_status_list: RevocationStatusList) -> Result<()> {
+        let (rev_reg_def_id, _) = status_list_key(&rev_status_list)?;
+        self.rev_status_lists
+            .entry(rev_reg_def_id)
+            .or_default()
+            .insert(rev_status_list)
+    }
+}
+
+impl AnoncredsResolver for InMemoryResolver {
+    fn get_schema(&self, schema_id: &SchemaId) -> Result<Schema> {
+        self.schemas
+            .get(schema_id)
+            .cloned()
+            .ok_or_else(|| err_msg!("Schema not found for ID: {}", schema_id))
+    }
+
+    fn get_cred_def(&self, cred_def_id: &CredentialDefinitionId) -> Result<CredentialDefinition> {
+        self.cred_defs
+            .get(cred_def_id)
+            .ok_or_else(|| err_msg!("Credential Definition not found for ID: {}", cred_def_id))?
+            .try_clone()
+    }
+
+    fn get_rev_reg_def(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<RevocationRegistryDefinition> {
+        self.rev_reg_defs
+            .get(rev_reg_def_id)
+            .cloned()
+            .ok_or_else(|| {
+                err_msg!(
+                    "Revocation Registry Definition not found for ID: {}",
+                    rev_reg_def_id
+                )
+            })
+    }
+
+    fn get_rev_status_list_at(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Result<RevocationStatusList> {
+        self.rev_status_lists
+            .get(rev_reg_def_id)
+            .and_then(|lists| lists.current_at(timestamp))
+            .cloned()
+            .ok_or_else(|| {
+                err_msg!(
+                    "Revocation Status List not found for ID and timestamp: {}, {}",
+                    rev_reg_def_id,
+                    timestamp
+                )
+            })
+    }
+}
+
+/// Resolver reading ledger objects from JSON files in a directory
+///
+/// Objects are stored as `schemas/<id>.json`, `cred_defs/<id>.json`,
+/// `rev_reg_defs/<id>.json` and `rev_status_lists/<id>/<timestamp>.json` below the root
+/// directory, where `<id>` is the percent-encoded object identifier.
+#[derive(Debug, Clone)]
+pub struct DirectoryResolver {
+    root_path: PathBuf,
+}
+
+impl DirectoryResolver {
+    pub fn new<P: Into<PathBuf>>(root_path: P) -> Self {
+        Self {
+            root_path: root_path.into(),
+        }
+    }
+
+    pub fn store_schema(&self, schema_id: &SchemaId, schema: &Schema) -> Result<()> {
+        write_json(&self.object_path("schemas", &schema_id.0), schema)
+    }
+
+    pub fn store_cred_def(
+        &self,
+        cred_def_id: &CredentialDefinitionId,
+        cred_def: &CredentialDefinition,
+    ) -> Result<()> {
+        write_json(&self.object_path("cred_defs", &cred_def_id.0), cred_def)
+    }
+
+    pub fn store_rev_reg_def(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<()> {
+        write_json(
+            &self.object_path("rev_reg_defs", &rev_reg_def_id.0),
+            rev_reg_def,
+        )
+    }
+
+    pub fn store_rev_status_list(&self, rev_status_list: &RevocationStatusList) -> Result<()> {
+        let (rev_reg_def_id, timestamp) = status_list_key(rev_status_list)?;
+        let path = self
+            .status_lists_path(&rev_reg_def_id)
+            .join(format!("{timestamp}.json"));
+        write_json(&path, rev_status_list)
+    }
+
+    fn object_path(&self, kind: &str, id: &str) -> PathBuf {
+        self.root_path
+            .join(kind)
+            .join(format!("{}.json", encode_file_name(id)))
+    }
+
+    fn status_lists_path(&self, rev_reg_def_id: &RevocationRegistryDefinitionId) -> PathBuf {
+        self.root_path
+            .join("rev_status_lists")
+            .join(encode_file_name(&rev_reg_def_id.0))
+    }
+}
+
+impl AnoncredsResolver for DirectoryResolver {
+    fn get_schema(&self, schema_id: &SchemaId) -> Result<Schema> {
+        read_json(&self.object_path("schemas", &schema_id.0))
+    }
+
+    fn get_cred_def(&self, cred_def_id: &CredentialDefinitionId) -> Result<CredentialDefinition> {
+        read_json(&self.object_path("cred_defs", &cred_def_id.0))
+    }
+
+    fn get_rev_reg_def(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<RevocationRegistryDefinition> {
+        read_json(&self.object_path("rev_reg_defs", &rev_reg_def_id.0))
+    }
+
+    fn get_rev_status_list_at(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Result<RevocationStatusList> {
+        let dir = self.status_lists_path(rev_reg_def_id);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == IoErrorKind::NotFound => {
+                return Err(err_msg!(
+                    "Revocation Status List not found for ID: {}",
+                    rev_reg_def_id
+                ));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut current = None;
+        for entry in entries {
+            let path = entry?.path();
+            let list_timestamp = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if let Some(list_timestamp) = list_timestamp {
+                if list_timestamp <= timestamp
+                    && current.as_ref().is_none_or(|(ts, _)| *ts < list_timestamp)
+                {
+                    current = Some((list_timestamp, path));
+                }
+            }
+        }
+
+        let (_, path) = current.ok_or_else(|| {
+            err_msg!(
+                "Revocation Status List not found for ID and timestamp: {}, {}",
+                rev_reg_def_id,
+                timestamp
+            )
+        })?;
+        read_json(&path)
+    }
+}
+
+fn status_list_key(
+    rev_status_list: &RevocationStatusList,
+) -> Result<(RevocationRegistryDefinitionId, u64)> {
+    let rev_reg_def_id = rev_status_list
+        .id()
+        .ok_or_else(|| err_msg!("Revocation Status List missing Id"))?;
+    let timestamp = rev_status_list
+        .timestamp()
+        .ok_or_else(|| err_msg!("Revocation Status List missing timestamp"))?;
+    Ok((rev_reg_def_id, timestamp))
+}
+
+// Identifiers may contain characters such as `:` and `/` which cannot be used in file names.
+// A leading dot is escaped too, so that `.` and `..` can not refer outside the directory.
+fn encode_file_name(id: &str) -> String {
+    let mut encoded = String::with_capacity(id.len());
+    for (index, byte) in id.bytes().enumerate() {
+        if byte.is_ascii_alphanumeric()
+            || matches!(byte, b'-' | b'_')
+            || (byte == b'.' && index > 0)
+        {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let json = match fs::read(path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == IoErrorKind::NotFound => {
+            return Err(err_msg!("Ledger object not found: {}", path.display()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    serde_json::from_slice(&json).map_err(err_map!("Error parsing {}", path.display()))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec(value).map_err(err_map!("Error serializing object"))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::issuer_id::IssuerId;
+    use crate::issuer::create_schema;
+
+    fn schema() -> (SchemaId, Schema) {
+        let issuer_id = IssuerId::new_unchecked("did:web:example.org");
+        let schema =
+            create_schema("gvt", "1.0", issuer_id, vec!["name".to_owned()].into()).unwrap();
+        (
+            SchemaId::new_unchecked("did:web:example.org/schemas/gvt:1.0"),
+            schema,
+        )
+    }
+
+    #[test]
+    fn encode_file_name_escapes_separators() {
+        assert_eq!(
+            encode_file_name("did:web:example.org/schemas/gvt"),
+            "did%3Aweb%3Aexample.org%2Fschemas%2Fgvt"
+        );
+    }
+
+    #[test]
+    fn encode_file_name_escapes_relative_paths() {
+        assert_eq!(encode_file_name("."), "%2E");
+        assert_eq!(encode_file_name(".."), "%2E.");
+        assert_eq!(encode_file_name("../schemas"), "%2E.%2Fschemas");
+
+        let resolver = DirectoryResolver::new("ledger");
+        assert_eq!(
+            resolver.status_lists_path(&RevocationRegistryDefinitionId::new_unchecked("..")),
+            Path::new("ledger/rev_status_lists/%2E.")
+        );
+    }
+
+    #[test]
+    fn in_memory_resolver_returns_stored_schema() {
+        let (schema_id, schema) = schema();
+        let mut resolver = InMemoryResolver::new();
+        resolver.add_schema(schema_id.clone(), schema);
+
+        assert_eq!(resolver.get_schema(&schema_id).unwrap().name, "gvt");
+        assert!(
+            resolver
+                .get_schema(&SchemaId::new_unchecked("missing"))
+                .is_err()
+        );
+    }
+
+    fn rev_status_list(timestamp: u64) -> RevocationStatusList {
+        serde_json::from_value(json!({
+            "revRegDefId": "mock:uri",
+            "issuerId": "mock:uri",
+            "revocationList": [0, 1],
+            "timestamp": timestamp,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn in_memory_resolver_returns_status_list_current_at_timestamp() {
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked("mock:uri");
+        let mut resolver = InMemoryResolver::new();
+        resolver.add_rev_status_list(rev_status_list(10)).unwrap();
+        resolver.add_rev_status_list(rev_status_list(20)).unwrap();
+
+        let list = resolver
+            .get_rev_status_list_at(&rev_reg_def_id, 15)
+            .unwrap();
+        assert_eq!(list.timestamp(), Some(10));
+        let list = resolver
+            .get_rev_status_list_at(&rev_reg_def_id, 20)
+            .unwrap();
+        assert_eq!(list.timestamp(), Some(20));
+        assert!(resolver.get_rev_status_list_at(&rev_reg_def_id, 5).is_err());
+    }
+
+    #[test]
+    fn directory_resolver_round_trips_schema() {
+        let root =
+            std::env::temp_dir().join(format!("anoncreds-resolver-{}", rand::random::<u64>()));
+        let (schema_id, schema) = schema();
+        let resolver = DirectoryResolver::new(&root);
+        resolver.store_schema(&schema_id, &schema).unwrap();
+
+        assert_eq!(resolver.get_schema(&schema_id).unwrap().name, "gvt");
+        assert!(
+            resolver
+                .get_schema(&SchemaId::new_unchecked("missing"))
+                .is_err()
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn directory_resolver_returns_status_list_current_at_timestamp() {
+        let root =
+            std::env::temp_dir().join(format!("anoncreds-resolver-{}", rand::random::<u64>()));
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked("mock:uri");
+        let resolver = DirectoryResolver::new(&root);
+        resolver
+            .store_rev_status_list(&rev_status_list(10))
+            .unwrap();
+        resolver
+            .store_rev_status_list(&rev_status_list(20))
+            .unwrap();
+
+        let list = resolver
+            .get_rev_status_list_at(&rev_reg_def_id, 15)
+            .unwrap();
+        assert_eq!(list.timestamp(), Some(10));
+        assert!(resolver.get_rev_status_list_at(&rev_reg_def_id, 5).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
@@ -8,19 +9,21 @@ use rand::random;
 use sha2::{Digest, Sha256};
 
 use crate::ErrorKind;
-use crate::cl::{
-    Error as ClError, ErrorKind as ClErrorKind, RevocationTailsAccessor, RevocationTailsGenerator,
-    Tail,
-};
+use crate::cl::{Error as ClError, ErrorKind as ClErrorKind, RevocationTailsGenerator, Tail};
+use crate::data_types::rev_reg_def::RevocationRegistryDefinition;
 use crate::error::Error;
 use crate::utils::base58;
 
+pub use crate::cl::RevocationTailsAccessor;
+
 const TAILS_BLOB_TAG_SZ: u8 = 2;
+const TAILS_VERSION: [u8; TAILS_BLOB_TAG_SZ as usize] = [0, 2];
 const TAIL_SIZE: usize = Tail::BYTES_REPR_SIZE;
 
+/// Tails accessor reading from a tails file on the local filesystem
 #[derive(Debug)]
 pub struct TailsFileReader {
-    file: RefCell<BufReader<File>>,
+    reader: TailsStreamReader<BufReader<File>>,
 }
 
 impl TailsFileReader {
@@ -28,27 +31,86 @@ impl TailsFileReader {
     where
         P: AsRef<Path>,
     {
-        let file = RefCell::new(BufReader::new(File::open(path)?));
-        let reader = Self { file };
-        Ok(reader)
+        let reader = TailsStreamReader::new(BufReader::new(File::open(path)?));
+        Ok(Self { reader })
+    }
+
+    /// Open a tails file after checking it against the revocation registry definition, see
+    /// [`verify_tails_file`]
+    pub fn new_verified<P>(
+        path: P,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        verify_tails_file(path, rev_reg_def)?;
+        Self::new(path)
+    }
+}
+
+impl RevocationTailsAccessor for TailsFileReader {
+    fn access_tail(
+        &self,
+        tail_id: u32,
+        accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), ClError> {
+        self.reader.access_tail(tail_id, accessor)
+    }
+}
+
+/// Tails accessor reading from any seekable source provided by the caller, such as an
+/// encrypted store or a cached download
+#[derive(Debug)]
+pub struct TailsStreamReader<R> {
+    source: RefCell<R>,
+}
+
+impl<R> TailsStreamReader<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(source: R) -> Self {
+        Self {
+            source: RefCell::new(source),
+        }
+    }
+
+    /// Use a tails source after checking it against the revocation registry definition, see
+    /// [`verify_tails_file`]
+    pub fn new_verified(
+        mut source: R,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<Self, Error> {
+        source.seek(SeekFrom::Start(0))?;
+        verify_tails(&mut source, rev_reg_def)?;
+        Ok(Self::new(source))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.source.into_inner()
     }
 
     fn read(&self, size: usize, offset: usize) -> Result<Vec<u8>, Error> {
         let mut buf = vec![0u8; size];
 
-        let mut file = self
-            .file
+        let mut source = self
+            .source
             .try_borrow_mut()
             .map_err(|err| Error::from(ErrorKind::IOError).with_cause(err))?;
 
-        file.seek(SeekFrom::Start(offset as u64))?;
-        file.read_exact(buf.as_mut_slice())?;
+        source.seek(SeekFrom::Start(offset as u64))?;
+        source.read_exact(buf.as_mut_slice())?;
 
         Ok(buf)
     }
 }
 
-impl RevocationTailsAccessor for TailsFileReader {
+impl<R> RevocationTailsAccessor for TailsStreamReader<R>
+where
+    R: Read + Seek,
+{
     fn access_tail(
         &self,
         tail_id: u32,
@@ -56,15 +118,10 @@ impl RevocationTailsAccessor for TailsFileReader {
     ) -> std::result::Result<(), ClError> {
         trace!("access_tail >>> tail_id: {:?}", tail_id);
 
-        let tail_bytes = self
-            .read(
-                TAIL_SIZE,
-                TAIL_SIZE * tail_id as usize + TAILS_BLOB_TAG_SZ as usize,
-            )
-            .map_err(|e| {
-                error!("IO error reading tails file: {e}");
-                ClError::new(ClErrorKind::InvalidState, "Could not read from tails file")
-            })?;
+        let tail_bytes = self.read(TAIL_SIZE, tail_offset(tail_id)).map_err(|e| {
+            error!("IO error reading tails file: {e}");
+            ClError::new(ClErrorKind::InvalidState, "Could not read from tails file")
+        })?;
 
         let tail = Tail::from_bytes(tail_bytes.as_slice())?;
         accessor(&tail);
@@ -74,6 +131,234 @@ impl RevocationTailsAccessor for TailsFileReader {
     }
 }
 
+/// Tails accessor holding the whole tails file in memory.
+///
+/// Unlike the file and stream readers it is `Sync` and performs no IO once loaded. The
+/// contents are checked against the revocation registry definition, see [`verify_tails_file`].
+#[derive(Clone, Debug)]
+pub struct TailsMemoryReader {
+    tails: Vec<u8>,
+}
+
+impl TailsMemoryReader {
+    pub fn new(tails: Vec<u8>, rev_reg_def: &RevocationRegistryDefinition) -> Result<Self, Error> {
+        verify_tails(&mut tails.as_slice(), rev_reg_def)?;
+        Ok(Self { tails })
+    }
+
+    pub fn from_file<P>(path: P, rev_reg_def: &RevocationRegistryDefinition) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(std::fs::read(path)?, rev_reg_def)
+    }
+
+    pub fn from_reader<R>(
+        mut reader: R,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<Self, Error>
+    where
+        R: Read,
+    {
+        let mut tails = Vec::new();
+        reader.read_to_end(&mut tails)?;
+        Self::new(tails, rev_reg_def)
+    }
+}
+
+impl RevocationTailsAccessor for TailsMemoryReader {
+    fn access_tail(
+        &self,
+        tail_id: u32,
+        accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), ClError> {
+        access_tail_in(&self.tails, tail_id, accessor)
+    }
+}
+
+/// Tails accessor backed by a read-only memory map of a tails file.
+///
+/// The file is not read up front, so its contents are not checked against the tails hash.
+/// It must not be modified or truncated while the reader is in use.
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct TailsMmapReader {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl TailsMmapReader {
+    pub fn new<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        // SAFETY: the map is read-only, and callers are required not to modify the
+        // tails file while it is mapped.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self { map })
+    }
+
+    /// Map a tails file after checking it against the revocation registry definition, see
+    /// [`verify_tails_file`]
+    pub fn new_verified<P>(
+        path: P,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = Self::new(path)?;
+        verify_tails(&mut &reader.map[..], rev_reg_def)?;
+        Ok(reader)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl RevocationTailsAccessor for TailsMmapReader {
+    fn access_tail(
+        &self,
+        tail_id: u32,
+        accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), ClError> {
+        access_tail_in(&self.map, tail_id, accessor)
+    }
+}
+
+const fn tail_offset(tail_id: u32) -> usize {
+    TAIL_SIZE * tail_id as usize + TAILS_BLOB_TAG_SZ as usize
+}
+
+fn access_tail_in(
+    tails: &[u8],
+    tail_id: u32,
+    accessor: &mut dyn FnMut(&Tail),
+) -> std::result::Result<(), ClError> {
+    trace!("access_tail >>> tail_id: {:?}", tail_id);
+
+    let offset = tail_offset(tail_id);
+    let tail_bytes = tails.get(offset..offset + TAIL_SIZE).ok_or_else(|| {
+        ClError::new(
+            ClErrorKind::InvalidState,
+            "Tail index is outside of the tails file",
+        )
+    })?;
+
+    let tail = Tail::from_bytes(tail_bytes)?;
+    accessor(&tail);
+
+    trace!("access_tail <<< res: ()");
+    Ok(())
+}
+
+/// Check that a tails file matches a revocation registry definition.
+///
+/// The file must start with the supported version tag, hold the number of tails generated for
+/// the maximum credential count of the registry, and hash to the registry tails hash.
+pub fn verify_tails_file<P>(
+    path: P,
+    rev_reg_def: &RevocationRegistryDefinition,
+) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = File::open(path)
+        .map_err(|e| err_msg!(IOError, "Error opening tails file {path:?}: {e}"))?;
+    verify_tails(&mut BufReader::new(file), rev_reg_def)
+}
+
+fn verify_tails<R>(source: &mut R, rev_reg_def: &RevocationRegistryDefinition) -> Result<(), Error>
+where
+    R: Read,
+{
+    let mut version = [0u8; TAILS_BLOB_TAG_SZ as usize];
+    source
+        .read_exact(&mut version)
+        .map_err(|e| err_msg!(InvalidState, "Error reading tails version tag: {e}"))?;
+    if version != TAILS_VERSION {
+        return Err(err_msg!(
+            InvalidState,
+            "Unsupported tails version tag {:?}, expected {:?}",
+            version,
+            TAILS_VERSION
+        ));
+    }
+
+    let mut hasher = Sha256::default();
+    hasher.update(version);
+    let size = std::io::copy(source, &mut hasher)? + version.len() as u64;
+    let max_cred_num = rev_reg_def.value.max_cred_num;
+    // tails are generated for indices 0 to 2 * max_cred_num
+    let expected_size = (2 * u64::from(max_cred_num) + 1) * TAIL_SIZE as u64 + version.len() as u64;
+    if size != expected_size {
+        return Err(err_msg!(
+            InvalidState,
+            "Tails size {} does not match the size {} expected for max_cred_num {}",
+            size,
+            expected_size,
+            max_cred_num
+        ));
+    }
+
+    let hash = base58::encode(hasher.finalize());
+    if hash != rev_reg_def.value.tails_hash {
+        return Err(err_msg!(
+            InvalidState,
+            "Tails hash {} does not match the revocation registry definition hash {}",
+            hash,
+            rev_reg_def.value.tails_hash
+        ));
+    }
+
+    Ok(())
+}
+
+/// Tails accessor keeping every tail it has read, so that computing several witnesses
+/// against the same revocation registry reads each tail from the wrapped accessor only once.
+#[derive(Debug)]
+pub(crate) struct CachingTailsAccessor<'a, A> {
+    inner: &'a A,
+    tails: RefCell<HashMap<u32, Tail>>,
+}
+
+impl<'a, A> CachingTailsAccessor<'a, A> {
+    pub(crate) fn new(inner: &'a A) -> Self {
+        Self {
+            inner,
+            tails: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<A> RevocationTailsAccessor for CachingTailsAccessor<'_, A>
+where
+    A: RevocationTailsAccessor,
+{
+    fn access_tail(
+        &self,
+        tail_id: u32,
+        accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), ClError> {
+        let cached = self.tails.borrow().get(&tail_id).copied();
+        let tail = match cached {
+            Some(tail) => tail,
+            None => {
+                let mut loaded = None;
+                self.inner
+                    .access_tail(tail_id, &mut |tail| loaded = Some(*tail))?;
+                let tail = loaded.ok_or_else(|| {
+                    ClError::new(ClErrorKind::InvalidState, "Tail could not be read")
+                })?;
+                self.tails.borrow_mut().insert(tail_id, tail);
+                tail
+            }
+        };
+        accessor(&tail);
+        Ok(())
+    }
+}
+
 pub trait TailsWriter: std::fmt::Debug {
     fn write(
         &mut self,
@@ -81,9 +366,11 @@ pub trait TailsWriter: std::fmt::Debug {
     ) -> Result<(String, String), Error>;
 }
 
+/// Tails writer storing the tails file under a root directory, named after its hash
 #[derive(Debug)]
 pub struct TailsFileWriter {
     root_path: PathBuf,
+    location_template: Option<String>,
 }
 
 impl TailsFileWriter {
@@ -92,6 +379,16 @@ impl TailsFileWriter {
             root_path: root_path
                 .map(PathBuf::from)
                 .unwrap_or_else(std::env::temp_dir),
+            location_template: None,
+        }
+    }
+
+    /// Store the tails file under `root_path`, but report its location as `location_template`
+    /// with `{hash}` replaced by the tails hash, for instance the URL it will be published at
+    pub fn with_location_template(root_path: Option<String>, location_template: String) -> Self {
+        Self {
+            location_template: Some(location_template),
+            ..Self::new(root_path)
         }
     }
 }
@@ -127,20 +424,11 @@ impl TailsWriter for TailsFileWriter {
             .map_err(|e| err_msg!(IOError, "Error creating tails temp file {temp_path:?}: {e}"))?;
         let temp_handle = TempFile(&temp_path);
         let mut buf = BufWriter::new(file);
-        let mut hasher = Sha256::default();
-        let version = &[0u8, 2u8];
-        buf.write_all(version)?;
-        hasher.update(version);
-        while let Some(tail) = generator.try_next()? {
-            let tail_bytes = tail.to_bytes()?;
-            buf.write_all(&tail_bytes)?;
-            hasher.update(&tail_bytes);
-        }
+        let hash = write_tails(generator, &mut buf)?;
         let mut file = buf
             .into_inner()
             .map_err(|e| err_msg!("Error flushing output file: {e}"))?;
         let tails_size = file.stream_position()?;
-        let hash = base58::encode(hasher.finalize());
         let target_path = self.root_path.join(&hash);
         drop(file);
         temp_handle.rename(&target_path)?;
@@ -149,6 +437,395 @@ impl TailsWriter for TailsFileWriter {
             "TailsFileWriter: wrote tails file [size {}]: {}",
             tails_size, target_path
         );
-        Ok((target_path, hash))
+        let location = match &self.location_template {
+            Some(location_template) => tails_location(location_template, &hash),
+            None => target_path,
+        };
+        Ok((location, hash))
+    }
+}
+
+/// Tails writer streaming the tails to a sink provided by the caller, such as an upload to
+/// object storage.
+///
+/// The reported location is `location_template` with `{hash}` replaced by the tails hash.
+#[derive(Debug)]
+pub struct TailsSinkWriter<W> {
+    sink: W,
+    location_template: String,
+}
+
+impl<W> TailsSinkWriter<W>
+where
+    W: Write,
+{
+    pub fn new(sink: W, location_template: String) -> Self {
+        Self {
+            sink,
+            location_template,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+}
+
+impl<W> TailsWriter for TailsSinkWriter<W>
+where
+    W: Write + Debug,
+{
+    fn write(
+        &mut self,
+        generator: &mut RevocationTailsGenerator,
+    ) -> Result<(String, String), Error> {
+        let hash = write_tails(generator, &mut self.sink)?;
+        self.sink.flush()?;
+        Ok((tails_location(&self.location_template, &hash), hash))
+    }
+}
+
+/// Tails writer keeping the tails of the last registry written in memory.
+///
+/// The reported location is `location_template` with `{hash}` replaced by the tails hash.
+#[derive(Debug)]
+pub struct TailsBufferWriter {
+    tails: Vec<u8>,
+    location_template: String,
+}
+
+impl TailsBufferWriter {
+    pub fn new(location_template: String) -> Self {
+        Self {
+            tails: Vec::new(),
+            location_template,
+        }
+    }
+
+    #[must_use]
+    pub fn tails(&self) -> &[u8] {
+        &self.tails
+    }
+
+    pub fn into_tails(self) -> Vec<u8> {
+        self.tails
+    }
+}
+
+impl TailsWriter for TailsBufferWriter {
+    fn write(
+        &mut self,
+        generator: &mut RevocationTailsGenerator,
+    ) -> Result<(String, String), Error> {
+        self.tails.clear();
+        let hash = write_tails(generator, &mut self.tails)?;
+        Ok((tails_location(&self.location_template, &hash), hash))
+    }
+}
+
+/// Tails writer passing the tails to a callback in chunks of at most `chunk_size` bytes.
+///
+/// The reported location is `location_template` with `{hash}` replaced by the tails hash.
+pub struct TailsChunkWriter<F> {
+    callback: F,
+    chunk_size: usize,
+    location_template: String,
+}
+
+impl<F> TailsChunkWriter<F>
+where
+    F: FnMut(&[u8]) -> Result<(), Error>,
+{
+    pub fn new(location_template: String, chunk_size: usize, callback: F) -> Self {
+        Self {
+            callback,
+            chunk_size: chunk_size.max(1),
+            location_template,
+        }
+    }
+}
+
+impl<F> Debug for TailsChunkWriter<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TailsChunkWriter")
+            .field("chunk_size", &self.chunk_size)
+            .field("location_template", &self.location_template)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> TailsWriter for TailsChunkWriter<F>
+where
+    F: FnMut(&[u8]) -> Result<(), Error>,
+{
+    fn write(
+        &mut self,
+        generator: &mut RevocationTailsGenerator,
+    ) -> Result<(String, String), Error> {
+        struct ChunkSink<'a, F> {
+            callback: &'a mut F,
+            chunk: Vec<u8>,
+            chunk_size: usize,
+            error: Option<Error>,
+        }
+        impl<F> ChunkSink<'_, F>
+        where
+            F: FnMut(&[u8]) -> Result<(), Error>,
+        {
+            fn emit(&mut self, len: usize) -> std::io::Result<()> {
+                (self.callback)(&self.chunk[..len]).map_err(|e| {
+                    self.error = Some(e);
+                    std::io::Error::other("Tails chunk callback failed")
+                })?;
+                self.chunk.drain(..len);
+                Ok(())
+            }
+        }
+        impl<F> Write for ChunkSink<'_, F>
+        where
+            F: FnMut(&[u8]) -> Result<(), Error>,
+        {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.chunk.extend_from_slice(buf);
+                while self.chunk.len() >= self.chunk_size {
+                    self.emit(self.chunk_size)?;
+                }
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                if !self.chunk.is_empty() {
+                    self.emit(self.chunk.len())?;
+                }
+                Ok(())
+            }
+        }
+
+        let mut sink = ChunkSink {
+            callback: &mut self.callback,
+            chunk: Vec::with_capacity(self.chunk_size),
+            chunk_size: self.chunk_size,
+            error: None,
+        };
+        let hash = write_tails(generator, &mut sink)
+            .and_then(|hash| sink.flush().map(|()| hash).map_err(Error::from))
+            .map_err(|e| sink.error.take().unwrap_or(e))?;
+        Ok((tails_location(&self.location_template, &hash), hash))
+    }
+}
+
+/// Write the version tag and the tails to `sink`, returning the tails hash
+fn write_tails<W>(generator: &mut RevocationTailsGenerator, sink: &mut W) -> Result<String, Error>
+where
+    W: Write,
+{
+    let mut hasher = Sha256::default();
+    let version = &TAILS_VERSION;
+    sink.write_all(version)?;
+    hasher.update(version);
+    while let Some(tail) = generator.try_next()? {
+        let tail_bytes = tail.to_bytes()?;
+        sink.write_all(&tail_bytes)?;
+        hasher.update(&tail_bytes);
+    }
+    Ok(base58::encode(hasher.finalize()))
+}
+
+fn tails_location(location_template: &str, hash: &str) -> String {
+    location_template.replace("{hash}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::cred_def::CredentialDefinition;
+    use crate::data_types::issuer_id::IssuerId;
+    use crate::issuer;
+    use crate::types::{CredentialDefinitionConfig, RegistryType, SignatureType};
+
+    fn _cred_def() -> CredentialDefinition {
+        let issuer_id: IssuerId = "sample:uri".try_into().unwrap();
+        let schema = issuer::create_schema(
+            "schema:name",
+            "1.0",
+            issuer_id.clone(),
+            vec!["name".to_owned()].into(),
+        )
+        .unwrap();
+        let (cred_def, _, _) = issuer::create_credential_definition(
+            "schema:id".try_into().unwrap(),
+            &schema,
+            issuer_id,
+            "default",
+            SignatureType::CL,
+            CredentialDefinitionConfig {
+                support_revocation: true,
+            },
+        )
+        .unwrap();
+        cred_def
+    }
+
+    fn _rev_reg_def_with_writer(
+        tails_writer: &mut impl TailsWriter,
+    ) -> Result<RevocationRegistryDefinition, Error> {
+        let (rev_reg_def, _) = issuer::create_revocation_registry_def(
+            &_cred_def(),
+            "cred:def".try_into().unwrap(),
+            "default",
+            RegistryType::CL_ACCUM,
+            3,
+            tails_writer,
+        )?;
+        Ok(rev_reg_def)
+    }
+
+    fn _rev_reg_def() -> RevocationRegistryDefinition {
+        _rev_reg_def_with_writer(&mut TailsFileWriter::new(None)).unwrap()
+    }
+
+    fn _tails(accessor: &impl RevocationTailsAccessor) -> Vec<Tail> {
+        (0..6)
+            .map(|tail_id| {
+                let mut res = None;
+                accessor
+                    .access_tail(tail_id, &mut |tail| res = Some(*tail))
+                    .unwrap();
+                res.unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn readers_return_same_tails() {
+        let rev_reg_def = _rev_reg_def();
+        let tails_path = &rev_reg_def.value.tails_location;
+        let expected = _tails(&TailsFileReader::new(tails_path).unwrap());
+
+        let bytes = std::fs::read(tails_path).unwrap();
+        let stream_reader = TailsStreamReader::new(std::io::Cursor::new(bytes));
+        assert_eq!(_tails(&stream_reader), expected);
+
+        let memory_reader = TailsMemoryReader::from_file(tails_path, &rev_reg_def).unwrap();
+        assert_eq!(_tails(&memory_reader), expected);
+
+        #[cfg(feature = "mmap")]
+        assert_eq!(_tails(&TailsMmapReader::new(tails_path).unwrap()), expected);
+    }
+
+    #[test]
+    fn memory_reader_checks_tails_hash() {
+        let rev_reg_def = _rev_reg_def();
+        let mut bytes = std::fs::read(&rev_reg_def.value.tails_location).unwrap();
+        bytes[10] ^= 1;
+
+        let _err = TailsMemoryReader::new(bytes, &rev_reg_def).unwrap_err();
+    }
+
+    #[test]
+    fn verify_tails_file_works() {
+        let rev_reg_def = _rev_reg_def();
+        let tails_path = &rev_reg_def.value.tails_location;
+        verify_tails_file(tails_path, &rev_reg_def).unwrap();
+        TailsFileReader::new_verified(tails_path, &rev_reg_def).unwrap();
+    }
+
+    #[test]
+    fn verify_tails_file_rejects_invalid_files() {
+        let rev_reg_def = _rev_reg_def();
+        let bytes = std::fs::read(&rev_reg_def.value.tails_location).unwrap();
+        let temp_path = std::env::temp_dir().join(format!("{:020}.tails", random::<u64>()));
+        let verify = |contents: &[u8]| {
+            std::fs::write(&temp_path, contents).unwrap();
+            verify_tails_file(&temp_path, &rev_reg_def)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[1] = 1;
+        assert!(verify(&wrong_version).contains("version tag"));
+        assert!(verify(&bytes[..bytes.len() - 1]).contains("size"));
+        let mut wrong_content = bytes.clone();
+        wrong_content[10] ^= 1;
+        assert!(verify(&wrong_content).contains("hash"));
+
+        std::fs::remove_file(&temp_path).unwrap();
+    }
+
+    #[test]
+    fn stream_reader_verifies_source() {
+        let rev_reg_def = _rev_reg_def();
+        let mut bytes = std::fs::read(&rev_reg_def.value.tails_location).unwrap();
+        let mut source = std::io::Cursor::new(bytes.clone());
+        source.set_position(20);
+        let reader = TailsStreamReader::new_verified(source, &rev_reg_def).unwrap();
+        assert_eq!(_tails(&reader).len(), 6);
+
+        bytes.truncate(100);
+        let _err =
+            TailsStreamReader::new_verified(std::io::Cursor::new(bytes), &rev_reg_def).unwrap_err();
+    }
+
+    #[test]
+    fn writers_produce_verifiable_tails() {
+        let mut buffer_writer = TailsBufferWriter::new("https://tails.example/{hash}".to_owned());
+        let rev_reg_def = _rev_reg_def_with_writer(&mut buffer_writer).unwrap();
+        assert_eq!(
+            rev_reg_def.value.tails_location,
+            format!("https://tails.example/{}", rev_reg_def.value.tails_hash)
+        );
+        let tails = buffer_writer.into_tails();
+        TailsMemoryReader::new(tails.clone(), &rev_reg_def).unwrap();
+
+        let mut sink_writer = TailsSinkWriter::new(Vec::new(), "{hash}.tails".to_owned());
+        let rev_reg_def = _rev_reg_def_with_writer(&mut sink_writer).unwrap();
+        assert_eq!(
+            rev_reg_def.value.tails_location,
+            format!("{}.tails", rev_reg_def.value.tails_hash)
+        );
+        TailsMemoryReader::new(sink_writer.into_inner(), &rev_reg_def).unwrap();
+
+        let mut chunks = Vec::new();
+        let mut chunk_writer = TailsChunkWriter::new("{hash}".to_owned(), 100, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        });
+        let rev_reg_def = _rev_reg_def_with_writer(&mut chunk_writer).unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 100));
+        TailsMemoryReader::new(chunks.concat(), &rev_reg_def).unwrap();
+    }
+
+    #[test]
+    fn chunk_writer_returns_callback_error() {
+        let mut chunk_writer = TailsChunkWriter::new("{hash}".to_owned(), 100, |_| {
+            Err(err_msg!(Input, "Upload failed"))
+        });
+        let err = _rev_reg_def_with_writer(&mut chunk_writer).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Input);
+    }
+
+    #[test]
+    fn file_writer_uses_location_template() {
+        let mut file_writer = TailsFileWriter::with_location_template(
+            None,
+            "https://tails.example/{hash}".to_owned(),
+        );
+        let rev_reg_def = _rev_reg_def_with_writer(&mut file_writer).unwrap();
+        assert_eq!(
+            rev_reg_def.value.tails_location,
+            format!("https://tails.example/{}", rev_reg_def.value.tails_hash)
+        );
+        let tails_path = std::env::temp_dir().join(&rev_reg_def.value.tails_hash);
+        verify_tails_file(tails_path, &rev_reg_def).unwrap();
+    }
+
+    #[test]
+    fn memory_reader_rejects_tail_outside_file() {
+        let rev_reg_def = _rev_reg_def();
+        let memory_reader =
+            TailsMemoryReader::from_file(&rev_reg_def.value.tails_location, &rev_reg_def).unwrap();
+
+        let _err = memory_reader.access_tail(100, &mut |_| ()).unwrap_err();
     }
 }
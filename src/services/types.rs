@@ -1,17 +1,28 @@
-use crate::cl::{RevocationRegistry as CryptoRevocationRegistry, Witness};
+use crate::cl::{Accumulator, RevocationRegistry as CryptoRevocationRegistry, Witness};
+use crate::data_types::pres_request::NonRevokedInterval;
+use crate::data_types::presentation::Identifier;
+use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
 pub use crate::data_types::{
+    attr_encoding::AttributeEncoding,
     cred_def::{CredentialDefinitionPrivate, CredentialKeyCorrectnessProof, SignatureType},
     cred_offer::CredentialOffer,
     cred_request::{CredentialRequest, CredentialRequestMetadata},
     credential::{AttributeValues, Credential, CredentialValues},
+    date::{CalendarDate, DateEncoding, DateOffset},
     link_secret::LinkSecret,
-    pres_request::PresentationRequest,
+    pres_request::{
+        AttributeInfo, LintDiagnostic, LintSeverity, PredicateInfo, PredicateTypes,
+        PresentationRequest, PresentationRequestBuilder, PresentationRequestVersion, RangeInfo,
+    },
     presentation::Presentation,
     rev_reg::RevocationRegistry,
     rev_reg_def::{
         RegistryType, RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate,
     },
-    rev_status_list::RevocationStatusList,
+    rev_status_list::{
+        RevocationListEncoding, RevocationStatusList, RevocationStatusListDelta,
+        RevocationStatusListHistory,
+    },
     schema::AttributeNames,
 };
 use crate::services::helpers::encode_credential_attribute;
@@ -20,7 +31,7 @@ use crate::{
     invalid,
     utils::validation::Validatable,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CredentialDefinitionConfig {
@@ -36,6 +47,84 @@ impl CredentialDefinitionConfig {
 
 impl Validatable for CredentialDefinitionConfig {}
 
+/// Configuration of the revocation registries created by a `RevocationRegistrySet`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationRegistrySetConfig {
+    /// Identifier of each registry, where `{tag}` is replaced with the registry tag
+    pub id_template: String,
+    /// Registry tags are made of this prefix followed by a sequence number
+    pub tag_prefix: String,
+    pub max_cred_num: u32,
+    pub issuance_by_default: bool,
+    /// Share of the active registry in use, between 0 and 1, from which the next registry is
+    /// created
+    pub fill_threshold: f64,
+}
+
+impl RevocationRegistrySetConfig {
+    pub(crate) const TAG_PLACEHOLDER: &'static str = "{tag}";
+}
+
+impl Validatable for RevocationRegistrySetConfig {
+    fn validate(&self) -> std::result::Result<(), ValidationError> {
+        if !self.id_template.contains(Self::TAG_PLACEHOLDER) {
+            return Err(invalid!(
+                "Revocation registry id template must contain {}",
+                Self::TAG_PLACEHOLDER
+            ));
+        }
+        if self.max_cred_num < 2 {
+            return Err(invalid!(
+                "Revocation registry max_cred_num must be at least 2"
+            ));
+        }
+        if !(self.fill_threshold > 0.0 && self.fill_threshold <= 1.0) {
+            return Err(invalid!(
+                "Revocation registry fill threshold must be within (0, 1]"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Ledger object produced by a `RevocationRegistrySet` which has to be published
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RegistryPublication {
+    RevocationRegistryDefinition {
+        id: RevocationRegistryDefinitionId,
+        definition: Box<RevocationRegistryDefinition>,
+    },
+    RevocationStatusList {
+        list: Box<RevocationStatusList>,
+    },
+}
+
+/// Outcome of checking an issuer's revocation status list against its registry
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationStatusListAudit {
+    /// Accumulator contained in the revocation status list
+    pub accum: Option<Accumulator>,
+    /// Accumulator recomputed from the revocation states in the list
+    pub expected_accum: Accumulator,
+    /// Whether the tails file matches the revocation registry definition
+    pub tails_valid: bool,
+}
+
+impl RevocationStatusListAudit {
+    #[must_use]
+    pub fn accum_matches(&self) -> bool {
+        self.accum == Some(self.expected_accum)
+    }
+
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.accum_matches() && self.tails_valid
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct MakeCredentialValues(pub(crate) CredentialValues);
 
@@ -67,6 +156,21 @@ impl MakeCredentialValues {
             .insert(name.into(), AttributeValues { raw, encoded });
         Ok(())
     }
+
+    /// Add an attribute using the typed encoding declared for it in the schema.
+    pub fn add_typed(
+        &mut self,
+        name: impl Into<String>,
+        raw: impl Into<String>,
+        encoding: &AttributeEncoding,
+    ) -> Result<(), Error> {
+        let raw = raw.into();
+        let encoded = encoding.encode(&raw)?;
+        self.0
+            .0
+            .insert(name.into(), AttributeValues { raw, encoded });
+        Ok(())
+    }
 }
 
 impl From<MakeCredentialValues> for CredentialValues {
@@ -190,6 +294,13 @@ impl<'a, 'p, T> AddCredential<'a, 'p, T> {
         self.present.requested_predicates.insert(referent.into());
     }
 
+    /// Prove both bounds of a requested range with this credential.
+    pub fn add_requested_range(&mut self, referent: &str) {
+        let (lower_referent, upper_referent) = RangeInfo::predicate_referents(referent);
+        self.present.requested_predicates.insert(lower_referent);
+        self.present.requested_predicates.insert(upper_referent);
+    }
+
     /// This method is intended for testing only, normally the link secret is
     /// passed directly when creating a presentation.
     #[doc(hidden)]
@@ -252,3 +363,241 @@ impl<'a> std::fmt::Debug for CredentialRevocationConfig<'a> {
         )
     }
 }
+
+/// Credential to issue as part of a batch
+pub struct CredentialBatchItem<'a> {
+    pub cred_offer: &'a CredentialOffer,
+    pub cred_request: &'a CredentialRequest,
+    pub cred_values: CredentialValues,
+    /// Revocation index of the credential, or `None` to assign the next free index of
+    /// the batch when a revocation registry is used
+    pub registry_idx: Option<u32>,
+}
+
+impl<'a> std::fmt::Debug for CredentialBatchItem<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CredentialBatchItem {{ cred_offer.nonce: {:?}, cred_request: {:?}, cred_values: {:?}, idx: {:?} }}",
+            self.cred_offer.nonce,
+            self.cred_request,
+            secret!(&self.cred_values),
+            secret!(self.registry_idx),
+        )
+    }
+}
+
+/// Revocation registry shared by all credentials of a batch
+pub struct CredentialBatchRevocationConfig<'a> {
+    pub reg_def: &'a RevocationRegistryDefinition,
+    pub reg_def_private: &'a RevocationRegistryDefinitionPrivate,
+    pub status_list: &'a RevocationStatusList,
+    /// First index assigned to credentials without an explicit revocation index, starting
+    /// from 1
+    pub first_idx: u32,
+}
+
+impl<'a> std::fmt::Debug for CredentialBatchRevocationConfig<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CredentialBatchRevocationConfig {{ reg_def: {:?}, private: {:?}, status_list: {:?}, first_idx: {} }}",
+            self.reg_def,
+            secret!(self.reg_def_private),
+            self.status_list,
+            secret!(self.first_idx),
+        )
+    }
+}
+
+/// Outcome of issuing a batch of credentials
+#[derive(Debug)]
+pub struct CredentialBatch {
+    /// Issued credential, or the reason it could not be issued, for each item in order
+    pub credentials: Vec<Result<Credential, Error>>,
+    /// Revocation status list updated once for all credentials issued with a revocation
+    /// index, which has to be published before holders can use them
+    pub status_list: Option<RevocationStatusList>,
+}
+
+/// Outcome of a single check performed while verifying a presentation
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum CheckResult {
+    Passed,
+    Failed(String),
+    /// The check does not apply, or could not run because a prerequisite is missing
+    #[default]
+    Skipped,
+}
+
+impl CheckResult {
+    #[must_use]
+    pub const fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+impl From<Result<(), Error>> for CheckResult {
+    fn from(result: Result<(), Error>) -> Self {
+        match result {
+            Ok(()) => Self::Passed,
+            Err(err) => Self::Failed(err.to_string()),
+        }
+    }
+}
+
+/// How a requested referent was answered in the presentation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentKind {
+    Revealed,
+    Unrevealed,
+    SelfAttested,
+    Predicate,
+    Missing,
+}
+
+/// Per-referent outcome of presentation verification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferentReport {
+    pub kind: ReferentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_proof_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Identifier>,
+    /// Revealed values match the encoded values in the CL sub-proof
+    pub revealed_value: CheckResult,
+    /// The credential satisfies the requested restrictions
+    pub restrictions: CheckResult,
+    /// The revocation timestamp lies within the requested non-revoked interval
+    pub non_revoked: CheckResult,
+    /// The CL sub-proof matches the request and could be loaded for verification. Fails
+    /// for a referent which is missing from the presentation.
+    pub sub_proof: CheckResult,
+}
+
+impl ReferentReport {
+    pub(crate) fn new(
+        kind: ReferentKind,
+        sub_proof_index: Option<u32>,
+        identifier: Option<Identifier>,
+    ) -> Self {
+        Self {
+            kind,
+            sub_proof_index,
+            identifier,
+            revealed_value: CheckResult::Skipped,
+            restrictions: CheckResult::Skipped,
+            non_revoked: CheckResult::Skipped,
+            sub_proof: CheckResult::Skipped,
+        }
+    }
+
+    /// Report a referent which is not answered by the presentation
+    pub(crate) fn missing(message: String) -> Self {
+        let mut report = Self::new(ReferentKind::Missing, None, None);
+        report.sub_proof = CheckResult::Failed(message);
+        report
+    }
+
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.kind != ReferentKind::Missing
+            && !self.revealed_value.is_failed()
+            && !self.restrictions.is_failed()
+            && !self.non_revoked.is_failed()
+            && !self.sub_proof.is_failed()
+    }
+}
+
+/// Structured result of presentation verification
+///
+/// Unlike the plain boolean returned by `verify_presentation`, the report records
+/// the outcome of every check so that a rejected presentation can be explained.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub valid: bool,
+    pub requested_attributes: HashMap<String, ReferentReport>,
+    pub requested_predicates: HashMap<String, ReferentReport>,
+    /// Identifiers resolved for each credential used in the presentation
+    pub identifiers: Vec<Identifier>,
+    /// Verification of the aggregated CL proof
+    pub proof: CheckResult,
+    /// Failures which do not relate to a single referent
+    pub errors: Vec<String>,
+}
+
+impl VerificationReport {
+    pub(crate) fn finalize(&mut self) {
+        self.valid = self.errors.is_empty()
+            && self.proof == CheckResult::Passed
+            && self
+                .requested_attributes
+                .values()
+                .chain(self.requested_predicates.values())
+                .all(ReferentReport::is_valid);
+    }
+}
+
+/// A credential which is able to fill a referent of a presentation request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialMatch {
+    /// Position of the credential in the list passed to `match_credentials`
+    pub index: usize,
+    /// Interval the revocation state must be taken from, if the credential is revocable
+    /// and the request asks for non-revocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_revoked: Option<NonRevokedInterval>,
+}
+
+/// Candidate credentials for each referent of a presentation request
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialMatches {
+    pub requested_attributes: HashMap<String, Vec<CredentialMatch>>,
+    pub requested_predicates: HashMap<String, Vec<CredentialMatch>>,
+}
+
+impl CredentialMatches {
+    /// Referents of requested attributes which no credential is able to fill
+    pub fn unmatched_attributes(&self) -> Vec<&str> {
+        Self::unmatched(&self.requested_attributes)
+    }
+
+    /// Referents of requested predicates which no credential is able to satisfy
+    pub fn unmatched_predicates(&self) -> Vec<&str> {
+        Self::unmatched(&self.requested_predicates)
+    }
+
+    fn unmatched(referents: &HashMap<String, Vec<CredentialMatch>>) -> Vec<&str> {
+        let mut unmatched = referents
+            .iter()
+            .filter(|(_, matches)| matches.is_empty())
+            .map(|(referent, _)| referent.as_str())
+            .collect::<Vec<&str>>();
+        unmatched.sort_unstable();
+        unmatched
+    }
+}
+
+/// Strategy used to choose between several credentials able to fill the same referent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionPolicy {
+    /// Fill the request with as few credentials as possible
+    #[default]
+    FewestCredentials,
+    /// Prefer the candidate added last to the builder. The result depends on the order in
+    /// which credentials are added, which is up to the caller.
+    LastAdded,
+    /// Prefer credentials which cannot be revoked, avoiding non-revocation proofs
+    NonRevocable,
+}
+
+/// Credentials chosen to fill a presentation request
+#[derive(Debug)]
+pub struct SelectedCredentials<'p, T> {
+    pub present: PresentCredentials<'p, T>,
+    /// Attribute referents which no credential can fill and which must be self-attested
+    pub self_attested: Vec<String>,
+}
@@ -4,14 +4,20 @@ use super::types::Presentation;
 use super::types::PresentationRequest;
 use super::types::RevocationRegistryDefinition;
 use super::types::RevocationStatusList;
+use super::types::{CheckResult, ReferentKind, ReferentReport, VerificationReport};
 use crate::cl::{CredentialPublicKey, RevocationRegistry, Verifier};
+use crate::data_types::attr_encoding::AttributeEncoding;
 use crate::data_types::cred_def::CredentialDefinition;
 use crate::data_types::cred_def::CredentialDefinitionId;
 use crate::data_types::issuer_id::IssuerId;
 use crate::data_types::nonce::Nonce;
 use crate::data_types::pres_request::PresentationRequestPayload;
-use crate::data_types::pres_request::{AttributeInfo, NonRevokedInterval};
-use crate::data_types::presentation::{Identifier, RequestedProof};
+use crate::data_types::pres_request::{
+    AttributeInfo, NonRevokedInterval, PredicateInfo, RangeInfo,
+};
+use crate::data_types::presentation::{
+    Identifier, RequestedProof, RevealedAttributeGroupInfo, RevealedAttributeInfo,
+};
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
 use crate::data_types::schema::Schema;
 use crate::data_types::schema::SchemaId;
@@ -19,12 +25,15 @@ use crate::error::Result;
 use crate::services::helpers::build_credential_schema;
 use crate::services::helpers::build_sub_proof_request;
 use crate::services::helpers::{build_non_credential_schema, get_requested_non_revoked_interval};
+use crate::services::nonce_registry::NonceRegistry;
+use crate::services::resolver::{AnoncredsResolver, ResolvedObjects};
 use crate::utils::query::Query;
 use crate::utils::validation::LEGACY_DID_IDENTIFIER;
 
 use anoncreds_clsignatures::{NonCredentialSchema, Proof, ProofVerifier, SubProof};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -74,10 +83,11 @@ pub fn verify_presentation(
         &received_unrevealed_attrs,
         &received_self_attested_attrs,
         &received_predicates,
+        &presentation.requested_proof,
     )?;
 
     // Ensures the encoded values are same as request
-    verify_revealed_attribute_values(pres_req, presentation)?;
+    verify_revealed_attribute_values(pres_req, presentation, schemas)?;
 
     // Ensures the restrictions set out in the request is met
     verify_requested_restrictions(
@@ -100,27 +110,14 @@ pub fn verify_presentation(
     )?;
 
     for (sub_proof_index, identifier) in presentation.identifiers.iter().enumerate() {
-        let attributes = presentation
-            .requested_proof
-            .get_attributes_for_credential(sub_proof_index as u32);
-        let predicates = presentation
-            .requested_proof
-            .get_predicates_for_credential(sub_proof_index as u32);
-
-        let (_, attrs_nonrevoked_interval) = pres_req.get_requested_attributes(&attributes)?;
-        let (_, pred_nonrevoked_interval) = pres_req.get_requested_predicates(&predicates)?;
-
-        {
-            check_non_revoked_interval(
-                proof_verifier.get_credential_definition(&identifier.cred_def_id)?,
-                attrs_nonrevoked_interval,
-                pred_nonrevoked_interval,
-                pres_req,
-                identifier.rev_reg_id.as_ref(),
-                nonrevoke_interval_override,
-                identifier.timestamp,
-            )?;
-        }
+        check_sub_proof_non_revoked_interval(
+            pres_req,
+            presentation,
+            sub_proof_index as u32,
+            identifier,
+            proof_verifier.get_credential_definition(&identifier.cred_def_id)?,
+            nonrevoke_interval_override,
+        )?;
 
         proof_verifier.add_sub_proof(
             &presentation.proof.proofs[sub_proof_index],
@@ -138,6 +135,282 @@ pub fn verify_presentation(
     Ok(valid)
 }
 
+/// Verify an incoming proof presentation, fetching the ledger objects referenced by its
+/// identifiers from a resolver
+pub fn verify_presentation_with_resolver<R>(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    resolver: &R,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool>
+where
+    R: AnoncredsResolver + ?Sized,
+{
+    let resolved = ResolvedObjects::resolve(resolver, &presentation.identifiers)?;
+
+    verify_presentation(
+        presentation,
+        pres_req,
+        &resolved.schemas,
+        &resolved.cred_defs,
+        resolved.rev_reg_defs(),
+        resolved.rev_status_lists(),
+        nonrevoke_interval_override,
+    )
+}
+
+/// Verify an incoming proof presentation, consuming the presentation request nonce from a
+/// registry
+///
+/// The presentation is rejected if the nonce was never issued by the registry, has expired
+/// or was already used. The nonce is consumed only once the proof is verified, so an
+/// invalid presentation does not use up the request, while a valid one answers it only once.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_presentation_with_nonce_registry<N>(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+    nonce_registry: &N,
+) -> Result<bool>
+where
+    N: NonceRegistry + ?Sized,
+{
+    let valid = verify_presentation(
+        presentation,
+        pres_req,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists,
+        nonrevoke_interval_override,
+    )?;
+
+    if valid {
+        nonce_registry.consume(&pres_req.value().nonce)?;
+    }
+
+    Ok(valid)
+}
+
+/// Verify an incoming proof presentation, reporting the outcome of every check
+///
+/// In contrast to `verify_presentation`, failed checks do not abort verification: they are
+/// collected per requested referent so that the reason for a rejection can be inspected.
+/// An error is only returned when the presentation is too malformed to be analyzed.
+pub fn verify_presentation_detailed(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<VerificationReport> {
+    trace!(
+        "verify_presentation_detailed >>> presentation: {:?}, pres_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_status_lists: {:?}",
+        presentation, pres_req, schemas, cred_defs, rev_reg_defs, rev_status_lists
+    );
+
+    // These values are from the prover and cannot be trusted
+    let received_revealed_attrs: HashMap<String, Identifier> =
+        received_revealed_attrs(presentation)?;
+    let received_unrevealed_attrs: HashMap<String, Identifier> =
+        received_unrevealed_attrs(presentation)?;
+    let received_predicates: HashMap<String, Identifier> = received_predicates(presentation)?;
+    let received_self_attested_attrs: HashSet<String> = received_self_attested_attrs(presentation);
+
+    let pres_req = pres_req.value();
+    let requested_proof = &presentation.requested_proof;
+
+    let mut report = VerificationReport {
+        identifiers: presentation.identifiers.clone(),
+        ..VerificationReport::default()
+    };
+
+    if let Err(err) = compare_attr_from_proof_and_request(
+        pres_req,
+        &received_revealed_attrs,
+        &received_unrevealed_attrs,
+        &received_self_attested_attrs,
+        &received_predicates,
+        requested_proof,
+    ) {
+        report.errors.push(err.to_string());
+    }
+
+    if let Err(err) = check_restriction_tags(pres_req) {
+        report.errors.push(err.to_string());
+    }
+
+    // Check the credentials behind each sub proof
+    let mut proof_verifier = CLProofVerifier::new(
+        pres_req,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists.as_ref(),
+    )?;
+    let mut non_revoked_results: HashMap<u32, CheckResult> = HashMap::new();
+    let mut sub_proof_results: HashMap<u32, CheckResult> = HashMap::new();
+
+    for (sub_proof_index, identifier) in presentation.identifiers.iter().enumerate() {
+        let sub_proof_index = sub_proof_index as u32;
+
+        let non_revoked = proof_verifier
+            .get_credential_definition(&identifier.cred_def_id)
+            .and_then(|cred_def| {
+                check_sub_proof_non_revoked_interval(
+                    pres_req,
+                    presentation,
+                    sub_proof_index,
+                    identifier,
+                    cred_def,
+                    nonrevoke_interval_override,
+                )
+            });
+        non_revoked_results.insert(sub_proof_index, non_revoked.into());
+
+        let sub_proof = presentation
+            .proof
+            .proofs
+            .get(sub_proof_index as usize)
+            .ok_or_else(|| {
+                err_msg!(
+                    ProofRejected,
+                    "CryptoProof not found by index \"{}\"",
+                    sub_proof_index,
+                )
+            })
+            .and_then(|sub_proof| {
+                proof_verifier.add_sub_proof(
+                    sub_proof,
+                    &identifier.schema_id,
+                    &identifier.cred_def_id,
+                    identifier.rev_reg_id.as_ref(),
+                    identifier.timestamp,
+                )
+            });
+        sub_proof_results.insert(sub_proof_index, sub_proof.into());
+    }
+
+    let sub_proof_report = |kind: ReferentKind, sub_proof_index: Option<u32>| {
+        let mut entry = ReferentReport::new(
+            kind,
+            sub_proof_index,
+            sub_proof_index.and_then(|idx| presentation.identifiers.get(idx as usize).cloned()),
+        );
+        if let Some(idx) = sub_proof_index {
+            entry.non_revoked = non_revoked_results.get(&idx).cloned().unwrap_or_default();
+            entry.sub_proof = sub_proof_results.get(&idx).cloned().unwrap_or_default();
+        }
+        entry
+    };
+
+    let proof_attr_identifiers: HashMap<String, Identifier> = received_revealed_attrs
+        .iter()
+        .chain(&received_unrevealed_attrs)
+        .map(|(r, id)| (r.to_string(), id.clone()))
+        .collect();
+
+    for (referent, info) in &pres_req.requested_attributes {
+        let mut entry = if let Some(attr_info) = requested_proof.revealed_attrs.get(referent) {
+            let mut entry =
+                sub_proof_report(ReferentKind::Revealed, Some(attr_info.sub_proof_index));
+            entry.revealed_value =
+                verify_revealed_attr(pres_req, presentation, referent, attr_info, schemas).into();
+            entry
+        } else if let Some(attr_infos) = requested_proof.revealed_attr_groups.get(referent) {
+            let mut entry =
+                sub_proof_report(ReferentKind::Revealed, Some(attr_infos.sub_proof_index));
+            entry.revealed_value =
+                verify_revealed_attr_group(pres_req, presentation, referent, attr_infos, schemas)
+                    .into();
+            entry
+        } else if let Some(attr_info) = requested_proof.unrevealed_attrs.get(referent) {
+            sub_proof_report(ReferentKind::Unrevealed, Some(attr_info.sub_proof_index))
+        } else if received_self_attested_attrs.contains(referent) {
+            sub_proof_report(ReferentKind::SelfAttested, None)
+        } else {
+            let entry = ReferentReport::missing(format!(
+                "Attribute with referent \"{referent}\" not found in presentation"
+            ));
+            report.requested_attributes.insert(referent.clone(), entry);
+            continue;
+        };
+
+        if !is_self_attested(referent, info, &received_self_attested_attrs)
+            && info.restrictions.is_some()
+        {
+            entry.restrictions = verify_attribute_restrictions(
+                pres_req,
+                schemas,
+                cred_defs,
+                requested_proof,
+                &proof_attr_identifiers,
+                referent,
+                info,
+            )
+            .into();
+        }
+        report.requested_attributes.insert(referent.clone(), entry);
+    }
+
+    for (referent, info) in &pres_req.requested_predicates {
+        let entry = if let Some(pred_info) = requested_proof.predicates.get(referent) {
+            let mut entry =
+                sub_proof_report(ReferentKind::Predicate, Some(pred_info.sub_proof_index));
+            if info.restrictions.is_some() {
+                entry.restrictions = verify_predicate_restrictions(
+                    pres_req,
+                    schemas,
+                    cred_defs,
+                    requested_proof,
+                    &received_predicates,
+                    referent,
+                    info,
+                )
+                .into();
+            }
+            entry
+        } else {
+            ReferentReport::missing(format!(
+                "Predicate with referent \"{referent}\" not found in presentation"
+            ))
+        };
+        report.requested_predicates.insert(referent.clone(), entry);
+    }
+
+    // The aggregated proof can only be checked once every sub proof has been loaded
+    report.proof = if sub_proof_results
+        .values()
+        .all(|res| *res == CheckResult::Passed)
+    {
+        match proof_verifier.verify(&presentation.proof) {
+            Ok(true) => CheckResult::Passed,
+            Ok(false) => CheckResult::Failed("Proof verification failed".to_string()),
+            Err(err) => CheckResult::Failed(err.to_string()),
+        }
+    } else {
+        CheckResult::Skipped
+    };
+
+    report.finalize();
+
+    trace!("verify_presentation_detailed <<< report: {:?}", report);
+
+    Ok(report)
+}
+
 /// Generates a cryptographically strong pseudo-random nonce with a length of 80 bits
 pub fn generate_nonce() -> Result<Nonce> {
     new_nonce()
@@ -149,6 +422,7 @@ pub(crate) fn compare_attr_from_proof_and_request(
     received_unrevealed_attrs: &HashMap<String, Identifier>,
     received_self_attested_attrs: &HashSet<String>,
     received_predicates: &HashMap<String, Identifier>,
+    requested_proof: &RequestedProof,
 ) -> Result<()> {
     let requested_attrs: HashSet<String> = pres_req.requested_attributes.keys().cloned().collect();
 
@@ -181,6 +455,23 @@ pub(crate) fn compare_attr_from_proof_and_request(
         ));
     }
 
+    for range_referent in pres_req.requested_ranges.keys() {
+        let (lower_referent, upper_referent) = RangeInfo::predicate_referents(range_referent);
+        match (
+            requested_proof.predicates.get(&lower_referent),
+            requested_proof.predicates.get(&upper_referent),
+        ) {
+            (Some(lower), Some(upper)) if lower.sub_proof_index == upper.sub_proof_index => {}
+            _ => {
+                return Err(err_msg!(
+                    ProofRejected,
+                    "Bounds of requested range \"{}\" are not proven by the same credential",
+                    range_referent
+                ));
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -240,92 +531,143 @@ fn get_proof_identifier(proof: &Presentation, index: u32) -> Result<Identifier>
         .ok_or_else(|| err_msg!("Identifier not found for index: {}", index))
 }
 
+/// Find the typed encoding declared for an attribute by the schema of a sub-proof.
+fn get_attribute_encoding<'a>(
+    schemas: &'a HashMap<SchemaId, Schema>,
+    proof: &Presentation,
+    sub_proof_index: u32,
+    attr_name: &str,
+) -> Result<Option<&'a AttributeEncoding>> {
+    let identifier = get_proof_identifier(proof, sub_proof_index)?;
+    let Some(schema) = schemas.get(&identifier.schema_id) else {
+        return Ok(None);
+    };
+    Ok(schema.attr_encoding(attr_name))
+}
+
 fn verify_revealed_attribute_values(
     pres_req: &PresentationRequestPayload,
     proof: &Presentation,
+    schemas: &HashMap<SchemaId, Schema>,
 ) -> Result<()> {
     for (attr_referent, attr_info) in &proof.requested_proof.revealed_attrs {
-        let attr_name = pres_req
-            .requested_attributes
-            .get(attr_referent)
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent
-                )
-            })?
-            .name
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?;
-        let sub_proof = proof
-            .proof
-            .proofs
-            .get(attr_info.sub_proof_index as usize)
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "CryptoProof not found by index \"{}\"",
-                    attr_info.sub_proof_index,
-                )
-            })?;
-        verify_revealed_attribute_value(attr_name.as_str(), sub_proof, &attr_info.encoded)?;
+        verify_revealed_attr(pres_req, proof, attr_referent, attr_info, schemas)?;
     }
 
     for (attr_referent, attr_infos) in &proof.requested_proof.revealed_attr_groups {
-        let sub_proof = proof
-            .proof
-            .proofs
-            .get(attr_infos.sub_proof_index as usize)
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "CryptoProof not found by index \"{}\"",
-                    attr_infos.sub_proof_index,
-                )
-            })?;
-        let attr_names = pres_req
-            .requested_attributes
-            .get(attr_referent)
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?
-            .names
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?;
-        if attr_infos.values.len() != attr_names.len() {
-            error!(
-                "Proof Revealed Attr Group does not match Proof Request Attribute Group, proof request attrs: {:?}, referent: {:?}, attr_infos: {:?}",
-                pres_req.requested_attributes, attr_referent, attr_infos
-            );
-            return Err(err_msg!(
-                "Proof Revealed Attr Group does not match Proof Request Attribute Group",
-            ));
-        }
-        for attr_name in attr_names {
-            let attr_info = &attr_infos.values.get(attr_name).ok_or_else(|| {
-                err_msg!("Proof Revealed Attr Group does not match Proof Request Attribute Group",)
-            })?;
-            verify_revealed_attribute_value(attr_name, sub_proof, &attr_info.encoded)?;
-        }
+        verify_revealed_attr_group(pres_req, proof, attr_referent, attr_infos, schemas)?;
+    }
+    Ok(())
+}
+
+fn verify_revealed_attr(
+    pres_req: &PresentationRequestPayload,
+    proof: &Presentation,
+    attr_referent: &str,
+    attr_info: &RevealedAttributeInfo,
+    schemas: &HashMap<SchemaId, Schema>,
+) -> Result<()> {
+    let attr_name = pres_req
+        .requested_attributes
+        .get(attr_referent)
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent
+            )
+        })?
+        .name
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?;
+    let sub_proof = proof
+        .proof
+        .proofs
+        .get(attr_info.sub_proof_index as usize)
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "CryptoProof not found by index \"{}\"",
+                attr_info.sub_proof_index,
+            )
+        })?;
+    let encoding = get_attribute_encoding(schemas, proof, attr_info.sub_proof_index, attr_name)?;
+    verify_revealed_attribute_value(
+        attr_name.as_str(),
+        sub_proof,
+        &attr_info.raw,
+        &attr_info.encoded,
+        encoding,
+    )
+}
+
+fn verify_revealed_attr_group(
+    pres_req: &PresentationRequestPayload,
+    proof: &Presentation,
+    attr_referent: &str,
+    attr_infos: &RevealedAttributeGroupInfo,
+    schemas: &HashMap<SchemaId, Schema>,
+) -> Result<()> {
+    let sub_proof = proof
+        .proof
+        .proofs
+        .get(attr_infos.sub_proof_index as usize)
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "CryptoProof not found by index \"{}\"",
+                attr_infos.sub_proof_index,
+            )
+        })?;
+    let attr_names = pres_req
+        .requested_attributes
+        .get(attr_referent)
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?
+        .names
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?;
+    if attr_infos.values.len() != attr_names.len() {
+        error!(
+            "Proof Revealed Attr Group does not match Proof Request Attribute Group, proof request attrs: {:?}, referent: {:?}, attr_infos: {:?}",
+            pres_req.requested_attributes, attr_referent, attr_infos
+        );
+        return Err(err_msg!(
+            "Proof Revealed Attr Group does not match Proof Request Attribute Group",
+        ));
+    }
+    for attr_name in attr_names {
+        let attr_info = &attr_infos.values.get(attr_name).ok_or_else(|| {
+            err_msg!("Proof Revealed Attr Group does not match Proof Request Attribute Group",)
+        })?;
+        let encoding =
+            get_attribute_encoding(schemas, proof, attr_infos.sub_proof_index, attr_name)?;
+        verify_revealed_attribute_value(
+            attr_name,
+            sub_proof,
+            &attr_info.raw,
+            &attr_info.encoded,
+            encoding,
+        )?;
     }
     Ok(())
 }
@@ -339,7 +681,9 @@ fn normalize_encoded_attr(attr: &str) -> String {
 pub(crate) fn verify_revealed_attribute_value(
     attr_name: &str,
     sub_proof: &SubProof,
+    raw: &str,
     encoded: &str,
+    encoding: Option<&AttributeEncoding>,
 ) -> Result<()> {
     let reveal_attr_encoded = normalize_encoded_attr(encoded);
 
@@ -366,6 +710,29 @@ pub(crate) fn verify_revealed_attribute_value(
         ));
     }
 
+    // a typed attribute must round-trip between its raw and encoded values
+    if let Some(encoding) = encoding {
+        let raw_encoded = encoding.encode(raw).map_err(|err| {
+            err_msg!(
+                ProofRejected,
+                "Raw value for \"{}\" is not a valid {} value: {}",
+                attr_name,
+                encoding,
+                err
+            )
+        })?;
+        if raw_encoded != reveal_attr_encoded {
+            return Err(err_msg!(
+                ProofRejected,
+                "Raw value \"{}\" for \"{}\" does not match its {} encoding \"{}\"",
+                raw,
+                attr_name,
+                encoding,
+                reveal_attr_encoded
+            ));
+        }
+    }
+
     Ok(())
 }
 
@@ -386,13 +753,53 @@ pub(crate) fn verify_requested_restrictions(
         .map(|(r, id)| (r.to_string(), id.clone()))
         .collect();
 
-    let requested_attrs: HashMap<String, AttributeInfo> = pres_req
-        .requested_attributes
-        .iter()
-        .filter(|&(referent, info)| !is_self_attested(referent, info, self_attested_attrs))
-        .map(|(referent, info)| (referent.to_string(), info.clone()))
-        .collect();
+    check_restriction_tags(pres_req)?;
+
+    for (referent, info) in &pres_req.requested_attributes {
+        if !is_self_attested(referent, info, self_attested_attrs) {
+            verify_attribute_restrictions(
+                pres_req,
+                schemas,
+                cred_defs,
+                requested_proof,
+                &proof_attr_identifiers,
+                referent,
+                info,
+            )?;
+        }
+    }
+
+    for (referent, info) in &pres_req.requested_predicates {
+        verify_predicate_restrictions(
+            pres_req,
+            schemas,
+            cred_defs,
+            requested_proof,
+            received_predicates,
+            referent,
+            info,
+        )?;
+    }
 
+    // both bounds of a range come from one credential, so checking the restrictions
+    // of the range against its lower bound covers the whole range
+    for (referent, range) in &pres_req.requested_ranges {
+        let (lower_referent, _) = RangeInfo::predicate_referents(referent);
+        verify_predicate_restrictions(
+            pres_req,
+            schemas,
+            cred_defs,
+            requested_proof,
+            received_predicates,
+            &lower_referent,
+            &range.lower_predicate(),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn check_restriction_tags(pres_req: &PresentationRequestPayload) -> Result<()> {
     let requested_attributes_queries = pres_req
         .requested_attributes
         .iter()
@@ -435,110 +842,129 @@ pub(crate) fn verify_requested_restrictions(
         ));
     }
 
-    for (referent, info) in &requested_attrs {
-        if let Some(ref query) = info.restrictions {
-            let identifier = proof_attr_identifiers.get(referent).ok_or_else(|| {
-                err_msg!(
-                    InvalidState,
-                    "Identifier not found for referent: {}",
-                    referent
-                )
-            })?;
-            let filter = gather_filter_info(identifier, schemas, cred_defs)?;
-
-            let attr_value_map: HashMap<String, Option<String>> = if let Some(name) =
-                info.name.as_ref()
-            {
-                let mut map = HashMap::new();
-                map.insert(
-                    name.clone(),
-                    requested_proof
-                        .revealed_attrs
-                        .get(referent)
-                        .map(|attr| attr.raw.to_string()),
-                );
-                map
-            } else if let Some(names) = info.names.as_ref() {
-                let mut map: HashMap<String, Option<String>> = HashMap::new();
-                let attrs = requested_proof
-                    .revealed_attr_groups
-                    .get(referent)
-                    .ok_or_else(|| err_msg!("Proof does not have referent from proof request"))?;
-                for name in names {
-                    let val = attrs.values.get(name).map(|attr| attr.raw.clone());
-                    map.insert(name.clone(), val);
-                }
-                map
-            } else {
-                error!(
-                    r#"Proof Request attribute restriction should contain "name" or "names" param. Current proof request: {:?}"#,
-                    pres_req
-                );
-                return Err(err_msg!(
-                    r#"Proof Request attribute restriction should contain "name" or "names" param"#,
-                ));
-            };
+    Ok(())
+}
+
+fn verify_attribute_restrictions(
+    pres_req: &PresentationRequestPayload,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    requested_proof: &RequestedProof,
+    proof_attr_identifiers: &HashMap<String, Identifier>,
+    referent: &str,
+    info: &AttributeInfo,
+) -> Result<()> {
+    let Some(ref query) = info.restrictions else {
+        return Ok(());
+    };
 
-            process_operator(&attr_value_map, query, &filter).map_err(err_map!(
-                "Requested restriction validation failed for \"{:?}\" attributes",
-                &attr_value_map
-            ))?;
+    let identifier = proof_attr_identifiers.get(referent).ok_or_else(|| {
+        err_msg!(
+            InvalidState,
+            "Identifier not found for referent: {}",
+            referent
+        )
+    })?;
+    let filter = gather_filter_info(identifier, schemas, cred_defs)?;
+
+    let attr_value_map: HashMap<String, Option<String>> = if let Some(name) = info.name.as_ref() {
+        let mut map = HashMap::new();
+        map.insert(
+            name.clone(),
+            requested_proof
+                .revealed_attrs
+                .get(referent)
+                .map(|attr| attr.raw.to_string()),
+        );
+        map
+    } else if let Some(names) = info.names.as_ref() {
+        let mut map: HashMap<String, Option<String>> = HashMap::new();
+        let attrs = requested_proof
+            .revealed_attr_groups
+            .get(referent)
+            .ok_or_else(|| err_msg!("Proof does not have referent from proof request"))?;
+        for name in names {
+            let val = attrs.values.get(name).map(|attr| attr.raw.clone());
+            map.insert(name.clone(), val);
         }
-    }
+        map
+    } else {
+        error!(
+            r#"Proof Request attribute restriction should contain "name" or "names" param. Current proof request: {:?}"#,
+            pres_req
+        );
+        return Err(err_msg!(
+            r#"Proof Request attribute restriction should contain "name" or "names" param"#,
+        ));
+    };
 
-    for (referent, info) in &pres_req.requested_predicates {
-        if let Some(ref query) = info.restrictions {
-            let identifier = received_predicates.get(referent).ok_or_else(|| {
-                err_msg!(
-                    InvalidState,
-                    "Identifier not found for referent: {}",
-                    referent
-                )
-            })?;
-            let filter = gather_filter_info(identifier, schemas, cred_defs)?;
+    process_operator(&attr_value_map, query, &filter).map_err(err_map!(
+        "Requested restriction validation failed for \"{:?}\" attributes",
+        &attr_value_map
+    ))
+}
 
-            // start with the predicate requested attribute, which is un-revealed
-            let mut attr_value_map: HashMap<String, Option<String>> = HashMap::new();
-            attr_value_map.insert(info.name.to_string(), None);
+fn verify_predicate_restrictions(
+    pres_req: &PresentationRequestPayload,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    requested_proof: &RequestedProof,
+    received_predicates: &HashMap<String, Identifier>,
+    referent: &str,
+    info: &PredicateInfo,
+) -> Result<()> {
+    let Some(ref query) = info.restrictions else {
+        return Ok(());
+    };
 
-            // include any revealed attributes for the same credential (based on sub_proof_index)
-            let pred_sub_proof_index = requested_proof
-                .predicates
-                .get(referent)
-                .unwrap()
-                .sub_proof_index;
-            for attr_referent in requested_proof.revealed_attrs.keys() {
-                let attr_info = requested_proof.revealed_attrs.get(attr_referent).unwrap();
-                let attr_sub_proof_index = attr_info.sub_proof_index;
-                if pred_sub_proof_index == attr_sub_proof_index {
-                    let attr_name = requested_attrs.get(attr_referent).unwrap().name.clone();
-                    if let Some(name) = attr_name {
-                        attr_value_map.insert(name, Some(attr_info.raw.clone()));
-                    }
-                }
+    let identifier = received_predicates.get(referent).ok_or_else(|| {
+        err_msg!(
+            InvalidState,
+            "Identifier not found for referent: {}",
+            referent
+        )
+    })?;
+    let filter = gather_filter_info(identifier, schemas, cred_defs)?;
+
+    // start with the predicate requested attribute, which is un-revealed
+    let mut attr_value_map: HashMap<String, Option<String>> = HashMap::new();
+    attr_value_map.insert(info.name.to_string(), None);
+
+    // include any revealed attributes for the same credential (based on sub_proof_index)
+    let pred_sub_proof_index = requested_proof
+        .predicates
+        .get(referent)
+        .ok_or_else(|| {
+            err_msg!(
+                InvalidState,
+                "Sub proof not found for referent: {}",
+                referent
+            )
+        })?
+        .sub_proof_index;
+    for (attr_referent, attr_info) in &requested_proof.revealed_attrs {
+        if pred_sub_proof_index == attr_info.sub_proof_index {
+            let attr_name = pres_req
+                .requested_attributes
+                .get(attr_referent)
+                .and_then(|info| info.name.clone());
+            if let Some(name) = attr_name {
+                attr_value_map.insert(name, Some(attr_info.raw.clone()));
             }
-            for attr_referent in requested_proof.revealed_attr_groups.keys() {
-                let attr_info = requested_proof
-                    .revealed_attr_groups
-                    .get(attr_referent)
-                    .unwrap();
-                let attr_sub_proof_index = attr_info.sub_proof_index;
-                if pred_sub_proof_index == attr_sub_proof_index {
-                    for name in attr_info.values.keys() {
-                        let raw_val = attr_info.values.get(name).unwrap().raw.clone();
-                        attr_value_map.insert(name.to_string(), Some(raw_val));
-                    }
-                }
+        }
+    }
+    for attr_info in requested_proof.revealed_attr_groups.values() {
+        if pred_sub_proof_index == attr_info.sub_proof_index {
+            for (name, value) in &attr_info.values {
+                attr_value_map.insert(name.to_string(), Some(value.raw.clone()));
             }
-
-            process_operator(&attr_value_map, query, &filter).map_err(err_map!(
-                "Requested restriction validation failed for \"{}\" predicate",
-                &info.name
-            ))?;
         }
     }
 
-    Ok(())
+    process_operator(&attr_value_map, query, &filter).map_err(err_map!(
+        "Requested restriction validation failed for \"{}\" predicate",
+        &info.name
+    ))
 }
 
 fn is_self_attested(
@@ -585,17 +1011,203 @@ pub(crate) fn process_operator(
     attr_value_map: &HashMap<String, Option<String>>,
     restriction_op: &Query,
     filter: &Filter,
+) -> Result<()> {
+    check_attr_value_comparisons(restriction_op, attr_value_map)?;
+    process_query(restriction_op, &|tag_name, operator, tag_value| {
+        process_filter(attr_value_map, tag_name, operator, tag_value, filter)
+    })
+}
+
+/// Reject `$gt`, `$gte`, `$lt`, `$lte` and `$like` conditions on `attr::<name>::value` tags
+/// which can not be evaluated: the attribute is not revealed, or an ordering compares
+/// values which are not both integers.
+///
+/// This is checked before the query is evaluated, so that such a condition is not turned
+/// into a satisfied one by an enclosing `$not` or `$or`.
+fn check_attr_value_comparisons(
+    restriction_op: &Query,
+    attr_value_map: &HashMap<String, Option<String>>,
+) -> Result<()> {
+    match restriction_op {
+        Query::Gt(tag_name, tag_value)
+        | Query::Gte(tag_name, tag_value)
+        | Query::Lt(tag_name, tag_value)
+        | Query::Lte(tag_name, tag_value)
+        | Query::Like(tag_name, tag_value) => {
+            let Some((attr_name, "value")) = parse_attr_tag(tag_name) else {
+                return Ok(());
+            };
+            match attr_value_map.get(attr_name) {
+                Some(None) => Err(err_msg!(
+                    ProofRejected,
+                    "\"{}\" can only be compared with $eq, as the attribute is not revealed",
+                    tag_name
+                )),
+                Some(Some(revealed_value)) if !matches!(restriction_op, Query::Like(..)) => {
+                    if is_integer(revealed_value) && is_integer(tag_value) {
+                        Ok(())
+                    } else {
+                        Err(err_msg!(
+                            ProofRejected,
+                            "\"{}\" values are only ordered as integers: actual: \"{}\", expected: \"{}\"",
+                            tag_name,
+                            revealed_value,
+                            tag_value
+                        ))
+                    }
+                }
+                _ => Ok(()),
+            }
+        }
+        Query::And(operators) | Query::Or(operators) => operators
+            .iter()
+            .try_for_each(|op| check_attr_value_comparisons(op, attr_value_map)),
+        Query::Not(operator) => check_attr_value_comparisons(operator, attr_value_map),
+        Query::Eq(..) | Query::Neq(..) | Query::In(..) | Query::Exist(..) => Ok(()),
+    }
+}
+
+fn is_integer(value: &str) -> bool {
+    value.parse::<i64>().is_ok()
+}
+
+/// Comparison made by a restriction between a tag and its requested value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TagOperator {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Like,
+    Exist,
+}
+
+impl TagOperator {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "$eq",
+            Self::Gt => "$gt",
+            Self::Gte => "$gte",
+            Self::Lt => "$lt",
+            Self::Lte => "$lte",
+            Self::Like => "$like",
+            Self::Exist => "$exist",
+        }
+    }
+
+    /// Check whether the `actual` value of `tag` satisfies the comparison with `expected`
+    pub(crate) fn matches(self, tag: &str, actual: &str, expected: &str) -> bool {
+        match self {
+            Self::Eq => actual == expected,
+            Self::Gt => compare_tag_values(tag, actual, expected).is_some_and(Ordering::is_gt),
+            Self::Gte => compare_tag_values(tag, actual, expected).is_some_and(Ordering::is_ge),
+            Self::Lt => compare_tag_values(tag, actual, expected).is_some_and(Ordering::is_lt),
+            Self::Lte => compare_tag_values(tag, actual, expected).is_some_and(Ordering::is_le),
+            Self::Like => like_matches(expected, actual),
+            Self::Exist => true,
+        }
+    }
+}
+
+impl std::fmt::Display for TagOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Schema versions are compared component by component, and attribute values numerically,
+/// not being ordered unless both are integers. Other identifier tags are compared
+/// numerically when both are integers and lexicographically otherwise.
+fn compare_tag_values(tag: &str, actual: &str, expected: &str) -> Option<Ordering> {
+    if tag == "schema_version" {
+        return Some(compare_versions(actual, expected));
+    }
+    match (actual.parse::<i64>(), expected.parse::<i64>()) {
+        (Ok(actual), Ok(expected)) => Some(actual.cmp(&expected)),
+        _ if parse_attr_tag(tag).is_some() => None,
+        _ => Some(actual.cmp(expected)),
+    }
+}
+
+fn compare_versions(actual: &str, expected: &str) -> Ordering {
+    let mut actual_parts = actual.split('.');
+    let mut expected_parts = expected.split('.');
+    loop {
+        let (actual_part, expected_part) = match (actual_parts.next(), expected_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            // missing components count as zero, so that "2" and "2.0" are the same version
+            (actual_part, expected_part) => {
+                (actual_part.unwrap_or("0"), expected_part.unwrap_or("0"))
+            }
+        };
+        let ordering = match (actual_part.parse::<u64>(), expected_part.parse::<u64>()) {
+            (Ok(actual_part), Ok(expected_part)) => actual_part.cmp(&expected_part),
+            _ => actual_part.cmp(expected_part),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+/// SQL `LIKE` matching, where `%` stands for any sequence of characters and `_` for any
+/// single character
+fn like_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // position of the last `%` in the pattern and of the value character it resumes from
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some(c) if *c == '_' || *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match backtrack {
+                Some((wildcard, resume)) => {
+                    backtrack = Some((wildcard, resume + 1));
+                    p = wildcard + 1;
+                    v = resume + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+/// Evaluate a restriction query, checking each tag condition with `check_tag`
+pub(crate) fn process_query(
+    restriction_op: &Query,
+    check_tag: &dyn Fn(&str, TagOperator, &str) -> Result<()>,
 ) -> Result<()> {
     match restriction_op {
         Query::Eq(tag_name, tag_value) => {
-            process_filter(attr_value_map, tag_name, tag_value, filter).map_err(err_map!(
-                "$eq operator validation failed for tag: \"{}\", value: \"{}\"",
-                tag_name,
-                tag_value
-            ))
+            process_comparison(check_tag, tag_name, TagOperator::Eq, tag_value)
+        }
+        Query::Gt(tag_name, tag_value) => {
+            process_comparison(check_tag, tag_name, TagOperator::Gt, tag_value)
+        }
+        Query::Gte(tag_name, tag_value) => {
+            process_comparison(check_tag, tag_name, TagOperator::Gte, tag_value)
+        }
+        Query::Lt(tag_name, tag_value) => {
+            process_comparison(check_tag, tag_name, TagOperator::Lt, tag_value)
+        }
+        Query::Lte(tag_name, tag_value) => {
+            process_comparison(check_tag, tag_name, TagOperator::Lte, tag_value)
+        }
+        Query::Like(tag_name, tag_value) => {
+            process_comparison(check_tag, tag_name, TagOperator::Like, tag_value)
         }
         Query::Neq(tag_name, tag_value) => {
-            if process_filter(attr_value_map, tag_name, tag_value, filter).is_err() {
+            if check_tag(tag_name, TagOperator::Eq, tag_value).is_err() {
                 Ok(())
             } else {
                 Err(err_msg!(
@@ -609,7 +1221,7 @@ pub(crate) fn process_operator(
         Query::In(tag_name, tag_values) => {
             let res = tag_values
                 .iter()
-                .any(|val| process_filter(attr_value_map, tag_name, val, filter).is_ok());
+                .any(|val| check_tag(tag_name, TagOperator::Eq, val).is_ok());
             if res {
                 Ok(())
             } else {
@@ -621,16 +1233,23 @@ pub(crate) fn process_operator(
                 ))
             }
         }
+        Query::Exist(tag_names) => tag_names
+            .iter()
+            .try_for_each(|tag_name| check_tag(tag_name, TagOperator::Exist, ""))
+            .map_err(err_map!(
+                "$exist operator validation failed for tags: \"{:?}\"",
+                tag_names
+            )),
         Query::And(operators) => operators
             .iter()
-            .map(|op| process_operator(attr_value_map, op, filter))
+            .map(|op| process_query(op, check_tag))
             .collect::<Result<Vec<()>>>()
             .map(|_| ())
             .map_err(err_map!("$and operator validation failed.")),
         Query::Or(operators) => {
             let res = operators
                 .iter()
-                .any(|op| process_operator(attr_value_map, op, filter).is_ok());
+                .any(|op| process_query(op, check_tag).is_ok());
             if res {
                 Ok(())
             } else {
@@ -641,7 +1260,7 @@ pub(crate) fn process_operator(
             }
         }
         Query::Not(operator) => {
-            if process_operator(attr_value_map, operator, filter).is_err() {
+            if process_query(operator, check_tag).is_err() {
                 Ok(())
             } else {
                 Err(err_msg!(
@@ -650,40 +1269,87 @@ pub(crate) fn process_operator(
                 ))
             }
         }
-        _ => Err(err_msg!(ProofRejected, "unsupported operator",)),
     }
 }
 
-fn process_filter(
+fn process_comparison(
+    check_tag: &dyn Fn(&str, TagOperator, &str) -> Result<()>,
+    tag_name: &str,
+    operator: TagOperator,
+    tag_value: &str,
+) -> Result<()> {
+    check_tag(tag_name, operator, tag_value).map_err(err_map!(
+        "{} operator validation failed for tag: \"{}\", value: \"{}\"",
+        operator,
+        tag_name,
+        tag_value
+    ))
+}
+
+/// Credential identifier tags accepted in restrictions by [`process_filter`]
+const RESTRICTION_TAGS: [&str; 8] = [
+    "schema_id",
+    "schema_issuer_did",
+    "schema_issuer_id",
+    "schema_name",
+    "schema_version",
+    "cred_def_id",
+    "issuer_did",
+    "issuer_id",
+];
+
+/// Check whether a restriction tag is understood by [`process_filter`], given the
+/// attribute names which `attr::<name>::value` tags may refer to.
+pub(crate) fn is_known_restriction_tag<'a>(
+    tag: &str,
+    attr_names: impl IntoIterator<Item = &'a str>,
+) -> bool {
+    RESTRICTION_TAGS.contains(&tag)
+        || is_attr_operator(tag)
+        || parse_attr_tag(tag)
+            .is_some_and(|(name, _)| attr_names.into_iter().any(|attr| attr == name))
+}
+
+pub(crate) fn process_filter(
     attr_value_map: &HashMap<String, Option<String>>,
     tag: &str,
+    operator: TagOperator,
     tag_value: &str,
     filter: &Filter,
 ) -> Result<()> {
     trace!(
-        "_process_filter: attr_value_map: {:?}, tag: {}, tag_value: {}, filter: {:?}",
-        attr_value_map, tag, tag_value, filter
+        "_process_filter: attr_value_map: {:?}, tag: {}, operator: {}, tag_value: {}, filter: {:?}",
+        attr_value_map, tag, operator, tag_value, filter
     );
     match tag {
-        tag_ @ "schema_id" => precess_filed(tag_, filter.schema_id.to_string(), tag_value),
+        tag_ @ "schema_id" => {
+            precess_filed(tag_, filter.schema_id.to_string(), operator, tag_value)
+        }
         tag_ @ ("schema_issuer_did" | "schema_issuer_id") => {
-            precess_filed(tag_, filter.schema_issuer_id.clone(), tag_value)
+            precess_filed(tag_, filter.schema_issuer_id.clone(), operator, tag_value)
+        }
+        tag_ @ "schema_name" => precess_filed(tag_, &filter.schema_name, operator, tag_value),
+        tag_ @ "schema_version" => precess_filed(tag_, &filter.schema_version, operator, tag_value),
+        tag_ @ "cred_def_id" => {
+            precess_filed(tag_, filter.cred_def_id.to_string(), operator, tag_value)
         }
-        tag_ @ "schema_name" => precess_filed(tag_, &filter.schema_name, tag_value),
-        tag_ @ "schema_version" => precess_filed(tag_, &filter.schema_version, tag_value),
-        tag_ @ "cred_def_id" => precess_filed(tag_, filter.cred_def_id.to_string(), tag_value),
         tag_ @ ("issuer_did" | "issuer_id") => {
-            precess_filed(tag_, filter.issuer_id.clone(), tag_value)
+            precess_filed(tag_, filter.issuer_id.clone(), operator, tag_value)
         }
         key if is_attr_internal_tag(key, attr_value_map) => {
-            check_internal_tag_revealed_value(key, tag_value, attr_value_map)
+            check_internal_tag_revealed_value(key, operator, tag_value, attr_value_map)
         }
         key if is_attr_operator(key) => Ok(()),
         _ => Err(err_msg!("Unknown Filter Type")),
     }
 }
 
-fn precess_filed(filed: &str, filter_value: impl Into<String>, tag_value: &str) -> Result<()> {
+fn precess_filed(
+    filed: &str,
+    filter_value: impl Into<String>,
+    operator: TagOperator,
+    tag_value: &str,
+) -> Result<()> {
     let filter_value = filter_value.into();
     // We explicitly check here with it is one of the two legacy identifier restrictions. This
     // means that we only allow legacy identifiers which can be detected with a simple regex. If
@@ -697,9 +1363,9 @@ fn precess_filed(filed: &str, filter_value: impl Into<String>, tag_value: &str)
             filed,
         ));
     }
-    if filter_value == tag_value {
+    if operator.matches(filed, &filter_value, tag_value) {
         Ok(())
-    } else {
+    } else if operator == TagOperator::Eq {
         Err(err_msg!(
             ProofRejected,
             "\"{}\" values are different: expected: \"{}\", actual: \"{}\"",
@@ -707,6 +1373,15 @@ fn precess_filed(filed: &str, filter_value: impl Into<String>, tag_value: &str)
             tag_value,
             filter_value,
         ))
+    } else {
+        Err(err_msg!(
+            ProofRejected,
+            "\"{}\" value \"{}\" does not satisfy {} \"{}\"",
+            filed,
+            filter_value,
+            operator,
+            tag_value,
+        ))
     }
 }
 
@@ -720,6 +1395,7 @@ fn is_attr_internal_tag(key: &str, attr_value_map: &HashMap<String, Option<Strin
 
 fn check_internal_tag_revealed_value(
     key: &str,
+    operator: TagOperator,
     tag_value: &str,
     attr_value_map: &HashMap<String, Option<String>>,
 ) -> Result<()> {
@@ -730,11 +1406,12 @@ fn check_internal_tag_revealed_value(
         .ok_or_else(|| err_msg!(InvalidState, "No name has been parsed",))?
         .as_str();
     if let Some(Some(revealed_value)) = attr_value_map.get(attr_name) {
-        if *revealed_value != tag_value {
+        if !operator.matches(key, revealed_value, tag_value) {
             return Err(err_msg!(
                 ProofRejected,
-                "\"{}\" values are different: expected: \"{}\", actual: \"{}\"",
+                "\"{}\" values are different: expected: {} \"{}\", actual: \"{}\"",
                 key,
+                operator,
                 tag_value,
                 revealed_value
             ));
@@ -743,6 +1420,12 @@ fn check_internal_tag_revealed_value(
     Ok(())
 }
 
+/// Split an `attr::<name>::value` or `attr::<name>::marker` tag into its attribute name and kind
+pub(crate) fn parse_attr_tag(key: &str) -> Option<(&str, &str)> {
+    let caps = INTERNAL_TAG_MATCHER.captures(key)?;
+    Some((caps.get(1)?.as_str(), caps.get(2)?.as_str()))
+}
+
 fn is_attr_operator(key: &str) -> bool {
     key.starts_with("attr::") && key.ends_with("::marker")
 }
@@ -825,6 +1508,37 @@ fn check_non_revoked_interval(
     Ok(())
 }
 
+fn check_sub_proof_non_revoked_interval(
+    pres_req: &PresentationRequestPayload,
+    presentation: &Presentation,
+    sub_proof_index: u32,
+    identifier: &Identifier,
+    cred_def: &CredentialDefinition,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<()> {
+    let attributes = presentation
+        .requested_proof
+        .get_attributes_for_credential(sub_proof_index);
+    let predicates = presentation
+        .requested_proof
+        .get_predicates_for_credential(sub_proof_index);
+
+    let (_, attrs_nonrevoked_interval) = pres_req.get_requested_attributes(&attributes)?;
+    let (_, pred_nonrevoked_interval) = pres_req.get_requested_predicates(&predicates)?;
+
+    check_non_revoked_interval(
+        cred_def,
+        attrs_nonrevoked_interval,
+        pred_nonrevoked_interval,
+        pres_req,
+        identifier.rev_reg_id.as_ref(),
+        nonrevoke_interval_override,
+        identifier.timestamp,
+    )
+}
+
 pub(crate) struct CLProofVerifier<'a> {
     proof_verifier: ProofVerifier,
     presentation_request: &'a PresentationRequestPayload,
@@ -899,9 +1613,8 @@ impl<'a> CLProofVerifier<'a> {
     }
 
     pub(crate) fn verify(&mut self, proof: &Proof) -> Result<bool> {
-        let valid = self
-            .proof_verifier
-            .verify(proof, self.presentation_request.nonce.as_native())?;
+        let nonce = self.presentation_request.proof_nonce()?;
+        let valid = self.proof_verifier.verify(proof, nonce.as_native())?;
         Ok(valid)
     }
 
@@ -974,6 +1687,7 @@ impl<'a> CLProofVerifier<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ErrorKind;
 
     pub const SCHEMA_ID: &str = "123";
     pub const SCHEMA_NAME: &str = "Schema Name";
@@ -1073,6 +1787,25 @@ mod tests {
         _process_operator("zip", &op, &filter, None).unwrap()
     }
 
+    #[test]
+    fn known_restriction_tags_are_processed() {
+        let filter = filter();
+        let attr_value_map = HashMap::from([("zip".to_string(), None)]);
+        for tag in RESTRICTION_TAGS {
+            assert!(is_known_restriction_tag(tag, []));
+            // legacy identifier tags reject the non-legacy fixture values as a mismatch
+            if let Err(err) = process_filter(&attr_value_map, tag, TagOperator::Eq, "", &filter) {
+                assert_eq!(err.kind(), ErrorKind::ProofRejected, "{tag}");
+            }
+        }
+
+        assert!(is_known_restriction_tag(&attr_tag_value(), ["zip"]));
+        assert!(!is_known_restriction_tag(&attr_tag_value(), ["city"]));
+        assert!(is_known_restriction_tag(&attr_tag(), []));
+        assert!(!is_known_restriction_tag(&bad_attr_tag(), ["zip"]));
+        assert!(!is_known_restriction_tag("schema_nam", []));
+    }
+
     #[test]
     fn test_process_op_in() {
         let filter = filter();
@@ -1282,6 +2015,118 @@ mod tests {
         assert!(_process_operator("zip", &op, &filter, Some("NOT HERE")).is_err());
     }
 
+    #[test]
+    fn test_process_op_schema_version_comparison() {
+        let filter = filter();
+
+        for (op, passes) in [
+            (Query::Gte(schema_version_tag(), "1.2".to_string()), true),
+            (
+                Query::Gte(schema_version_tag(), "1.2.3.0".to_string()),
+                true,
+            ),
+            (Query::Gt(schema_version_tag(), "1.2.3".to_string()), false),
+            (Query::Gt(schema_version_tag(), "1.10".to_string()), false),
+            (Query::Lt(schema_version_tag(), "1.10".to_string()), true),
+            (Query::Lte(schema_version_tag(), "1.2.3".to_string()), true),
+            (Query::Lt(schema_version_tag(), "1.2".to_string()), false),
+        ] {
+            assert_eq!(
+                _process_operator("zip", &op, &filter, None).is_ok(),
+                passes,
+                "{op:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_op_like() {
+        let filter = filter();
+
+        let mut op = Query::Like(schema_name_tag(), "Schema%".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Like(schema_name_tag(), "%_Name".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Like(cred_def_id_tag(), "3_5".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Like(schema_name_tag(), "Other%".to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Like(cred_def_id_tag(), "3_".to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+    }
+
+    #[test]
+    fn test_process_op_revealed_value_comparison() {
+        let filter = filter();
+
+        let mut op = Query::Gte(attr_tag_value(), "9".to_string());
+        _process_operator("zip", &op, &filter, Some("10")).unwrap();
+
+        op = Query::Lt(attr_tag_value(), "9".to_string());
+        assert!(_process_operator("zip", &op, &filter, Some("10")).is_err());
+
+        op = Query::Like(attr_tag_value(), "1%".to_string());
+        _process_operator("zip", &op, &filter, Some("10")).unwrap();
+
+        // attribute values are only ordered as integers
+        op = Query::Lt(attr_tag_value(), "9".to_string());
+        let err = _process_operator("zip", &op, &filter, Some("10.5")).unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+        op = Query::Not(Box::new(Query::Gt(attr_tag_value(), "9".to_string())));
+        assert!(_process_operator("zip", &op, &filter, Some("10.5")).is_err());
+    }
+
+    #[test]
+    fn test_process_op_unrevealed_value_comparison() {
+        let filter = filter();
+
+        _process_operator(
+            "zip",
+            &Query::Eq(attr_tag_value(), "10".to_string()),
+            &filter,
+            None,
+        )
+        .unwrap();
+        for op in [
+            Query::Gte(attr_tag_value(), "9".to_string()),
+            Query::Like(attr_tag_value(), "1%".to_string()),
+            Query::Not(Box::new(Query::Lt(attr_tag_value(), "9".to_string()))),
+            Query::Or(vec![
+                Query::Lt(attr_tag_value(), "9".to_string()),
+                Query::Eq(schema_name_tag(), SCHEMA_NAME.to_string()),
+            ]),
+        ] {
+            let err = _process_operator("zip", &op, &filter, None).unwrap_err();
+            assert_eq!(ErrorKind::ProofRejected, err.kind(), "{op:?}");
+        }
+    }
+
+    #[test]
+    fn test_process_op_exist() {
+        let filter = filter();
+
+        let mut op = Query::Exist(vec![attr_tag_value(), schema_name_tag()]);
+        _process_operator("zip", &op, &filter, Some("10")).unwrap();
+
+        op = Query::Exist(vec!["attr::other::value".to_string()]);
+        assert!(_process_operator("zip", &op, &filter, Some("10")).is_err());
+    }
+
+    #[test]
+    fn like_matches_works() {
+        assert!(like_matches("did:web:acme%", "did:web:acme.com"));
+        assert!(like_matches("%", ""));
+        assert!(like_matches("a%b%c", "aXbYbZc"));
+        assert!(like_matches("a_c", "abc"));
+        assert!(!like_matches("a_c", "ac"));
+        assert!(!like_matches("abc", "abcd"));
+        assert!(!like_matches("a%d", "abc"));
+    }
+
     fn _received() -> HashMap<String, Identifier> {
         let mut res: HashMap<String, Identifier> = HashMap::new();
         res.insert(
@@ -1316,4 +2161,77 @@ mod tests {
         assert_eq!(normalize_encoded_attr("-100"), "-100");
         assert_eq!(normalize_encoded_attr("-0100"), "-100");
     }
+
+    mod requested_ranges {
+        use super::*;
+        use crate::data_types::presentation::SubProofReferent;
+
+        fn _pres_req() -> PresentationRequestPayload {
+            let req: PresentationRequest = serde_json::from_value(json!({
+                "nonce": "123456",
+                "name": "range_req",
+                "version": "0.1",
+                "requested_ranges": {
+                    "working_age": { "name": "age", "lower": 18, "upper": 65 }
+                }
+            }))
+            .unwrap();
+            match req {
+                PresentationRequest::PresentationRequestV1(payload)
+                | PresentationRequest::PresentationRequestV2(payload) => payload,
+            }
+        }
+
+        fn _identifier() -> Identifier {
+            Identifier {
+                schema_id: SchemaId::new_unchecked(SCHEMA_ID),
+                cred_def_id: CredentialDefinitionId::new_unchecked(CRED_DEF_ID),
+                rev_reg_id: None,
+                timestamp: None,
+            }
+        }
+
+        fn _compare(lower_index: u32, upper_index: u32) -> Result<()> {
+            let pres_req = _pres_req();
+            let (lower, upper) = RangeInfo::predicate_referents("working_age");
+            let requested_proof = RequestedProof {
+                predicates: HashMap::from([
+                    (
+                        lower.clone(),
+                        SubProofReferent {
+                            sub_proof_index: lower_index,
+                        },
+                    ),
+                    (
+                        upper.clone(),
+                        SubProofReferent {
+                            sub_proof_index: upper_index,
+                        },
+                    ),
+                ]),
+                ..RequestedProof::default()
+            };
+            let received_predicates =
+                HashMap::from([(lower, _identifier()), (upper, _identifier())]);
+            compare_attr_from_proof_and_request(
+                &pres_req,
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashSet::new(),
+                &received_predicates,
+                &requested_proof,
+            )
+        }
+
+        #[test]
+        fn compare_attr_from_proof_and_request_accepts_range_from_one_sub_proof() {
+            _compare(0, 0).unwrap();
+        }
+
+        #[test]
+        fn compare_attr_from_proof_and_request_rejects_split_range() {
+            let err = _compare(0, 1).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ProofRejected);
+        }
+    }
 }
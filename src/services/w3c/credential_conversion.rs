@@ -1,5 +1,6 @@
 use crate::Error;
 use crate::data_types::issuer_id::IssuerId;
+use crate::data_types::schema::Schema;
 use crate::data_types::w3c::VerifiableCredentialSpecVersion;
 use crate::data_types::w3c::credential::W3CCredential;
 use crate::data_types::w3c::credential_attributes::CredentialSubject;
@@ -108,9 +109,11 @@ pub fn credential_to_w3c(
         signature_correctness_proof: credential.signature_correctness_proof,
         rev_reg: credential.rev_reg,
         witness: credential.witness,
+        attr_encodings: None,
     };
     let proof = DataIntegrityProof::new_credential_proof(&signature)?;
-    let w3c_credential = W3CCredential::new(issuer, attributes, proof, version.as_ref());
+    let w3c_credential =
+        W3CCredential::new(issuer, attributes, proof, version.as_ref()).with_credential_status()?;
 
     trace!("credential_to_w3c <<< w3c_credential {:?}", w3c_credential);
 
@@ -198,6 +201,22 @@ pub fn credential_to_w3c(
 ///
 /// ```
 pub fn credential_from_w3c(w3c_credential: &W3CCredential) -> Result<Credential, Error> {
+    convert_from_w3c(w3c_credential, None)
+}
+
+/// Convert credential in W3C form into legacy credential form, encoding the attribute values
+/// with the typed encodings declared by the credential schema
+pub fn credential_from_w3c_with_schema(
+    w3c_credential: &W3CCredential,
+    schema: &Schema,
+) -> Result<Credential, Error> {
+    convert_from_w3c(w3c_credential, Some(schema))
+}
+
+fn convert_from_w3c(
+    w3c_credential: &W3CCredential,
+    schema: Option<&Schema>,
+) -> Result<Credential, Error> {
     trace!(
         "credential_from_w3c >>> w3c_credential: {:?}",
         w3c_credential
@@ -206,7 +225,7 @@ pub fn credential_from_w3c(w3c_credential: &W3CCredential) -> Result<Credential,
     w3c_credential.validate()?;
 
     let credential_signature = w3c_credential.get_credential_signature_proof()?.clone();
-    let values = w3c_credential.credential_subject.encode()?;
+    let values = w3c_credential.credential_subject.encode(schema)?;
 
     let credential = Credential {
         values,
@@ -1,4 +1,5 @@
 use crate::data_types::cred_def::CredentialDefinition;
+use crate::data_types::schema::Schema;
 use crate::data_types::w3c::VerifiableCredentialSpecVersion;
 use crate::data_types::w3c::credential::W3CCredential;
 use crate::data_types::w3c::credential_attributes::CredentialSubject;
@@ -14,6 +15,10 @@ use crate::types::{
 /// Credential](https://hyperledger.github.io/anoncreds-spec/#issue-credential)
 ///
 /// This object can be send to a holder which means that the credential is issued to that entity.
+/// Attribute values use the default encoding; use [`create_credential_with_schema`] for a
+/// schema which declares typed encodings. The encodings are recorded in the credential proof,
+/// so presenting a credential issued with this function for such a schema fails with an
+/// explicit error rather than an invalid proof.
 ///
 /// # Example
 ///
@@ -89,6 +94,58 @@ pub fn create_credential(
     raw_credential_values: CredentialSubject,
     revocation_config: Option<CredentialRevocationConfig>,
     version: Option<VerifiableCredentialSpecVersion>,
+) -> Result<W3CCredential> {
+    issue_credential(
+        cred_def,
+        cred_def_private,
+        cred_offer,
+        cred_request,
+        None,
+        raw_credential_values,
+        revocation_config,
+        version,
+    )
+}
+
+/// Create an AnonCreds Credential in W3C form for a schema which declares typed encodings
+///
+/// This is the W3C counterpart of
+/// [`issuer::create_credential_with_schema`](crate::issuer::create_credential_with_schema):
+/// attributes with a declared encoding are encoded with it, and values which are not valid
+/// for their encoding are rejected.
+#[allow(clippy::too_many_arguments)]
+pub fn create_credential_with_schema(
+    cred_def: &CredentialDefinition,
+    cred_def_private: &CredentialDefinitionPrivate,
+    schema: &Schema,
+    cred_offer: &CredentialOffer,
+    cred_request: &CredentialRequest,
+    raw_credential_values: CredentialSubject,
+    revocation_config: Option<CredentialRevocationConfig>,
+    version: Option<VerifiableCredentialSpecVersion>,
+) -> Result<W3CCredential> {
+    issue_credential(
+        cred_def,
+        cred_def_private,
+        cred_offer,
+        cred_request,
+        Some(schema),
+        raw_credential_values,
+        revocation_config,
+        version,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn issue_credential(
+    cred_def: &CredentialDefinition,
+    cred_def_private: &CredentialDefinitionPrivate,
+    cred_offer: &CredentialOffer,
+    cred_request: &CredentialRequest,
+    schema: Option<&Schema>,
+    raw_credential_values: CredentialSubject,
+    revocation_config: Option<CredentialRevocationConfig>,
+    version: Option<VerifiableCredentialSpecVersion>,
 ) -> Result<W3CCredential> {
     trace!(
         "create_w3c_credential >>> cred_def: {:?}, cred_def_private: {:?}, cred_offer.nonce: {:?}, cred_request: {:?},\
@@ -102,7 +159,7 @@ pub fn create_credential(
         version,
     );
 
-    let credential_values = raw_credential_values.encode()?;
+    let credential_values = raw_credential_values.encode(schema)?;
 
     let (credential_signature, signature_correctness_proof, rev_reg_id, rev_reg, witness) =
         CLCredentialIssuer::new(cred_def, cred_def_private).create_credential(
@@ -120,6 +177,11 @@ pub fn create_credential(
         signature_correctness_proof,
         rev_reg,
         witness,
+        attr_encodings: Some(
+            schema
+                .map(|schema| schema.attr_encodings.clone())
+                .unwrap_or_default(),
+        ),
     };
 
     let proof = DataIntegrityProof::new_credential_proof(&signature)?;
@@ -128,7 +190,8 @@ pub fn create_credential(
         raw_credential_values,
         proof,
         version.as_ref(),
-    );
+    )
+    .with_credential_status()?;
 
     trace!(
         "create_w3c_credential <<< credential {:?}",
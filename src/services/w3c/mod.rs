@@ -1,6 +1,8 @@
 pub mod credential_conversion;
 pub mod helpers;
 pub mod issuer;
+pub mod openid4vp;
+pub mod presentation_exchange;
 pub mod prover;
 pub mod types;
 pub mod verifier;
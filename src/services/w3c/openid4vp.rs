@@ -0,0 +1,94 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::data_types::nonce::Nonce;
+use crate::data_types::w3c::presentation::W3CPresentation;
+use crate::error::Result;
+use crate::services::helpers;
+
+/// Domain separation tag of nonces derived from an OpenID4VP request
+const NONCE_DERIVATION_TAG: &[u8] = b"anoncreds-openid4vp-nonce";
+
+/// Derive the presentation request nonce for an OpenID4VP request from its `nonce` and
+/// `client_id`.
+///
+/// The verifier uses the derived nonce in the presentation request, so that the CL proof
+/// created by the holder is bound to both values.
+pub fn derive_nonce(nonce: &str, client_id: &str) -> Result<Nonce> {
+    helpers::derive_nonce(NONCE_DERIVATION_TAG, &[nonce, client_id])
+}
+
+/// Bind a presentation to an OpenID4VP request, by setting the `challenge` and `domain`
+/// of its proof to the request `nonce` and `client_id`.
+///
+/// The presentation must have been created for a presentation request using the nonce
+/// derived by [`derive_nonce`] from the same values.
+pub fn bind_presentation(
+    presentation: &mut W3CPresentation,
+    nonce: &str,
+    client_id: &str,
+) -> Result<()> {
+    trace!(
+        "bind_presentation >>> nonce: {:?}, client_id: {:?}",
+        nonce, client_id
+    );
+
+    let derived_nonce = derive_nonce(nonce, client_id)?.to_string();
+    if presentation.proof.challenge() != Some(derived_nonce.as_str()) {
+        return Err(err_msg!(
+            "Presentation was not created for the OpenID4VP nonce and client_id"
+        ));
+    }
+    presentation.proof.challenge = Some(nonce.to_string());
+    presentation.proof.domain = Some(client_id.to_string());
+
+    trace!("bind_presentation <<<");
+
+    Ok(())
+}
+
+/// Package a presentation as the `vp_token` of an OpenID4VP response, bound to the
+/// request `nonce` and `client_id`.
+pub fn create_vp_token(
+    presentation: &W3CPresentation,
+    nonce: &str,
+    client_id: &str,
+) -> Result<Value> {
+    let mut presentation = presentation.clone();
+    bind_presentation(&mut presentation, nonce, client_id)?;
+    serde_json::to_value(presentation)
+        .map_err(|err| err_msg!("Error serializing vp_token: {}", err))
+}
+
+/// Parse the presentation from the `vp_token` of an OpenID4VP response.
+pub fn parse_vp_token(vp_token: &Value) -> Result<W3CPresentation> {
+    W3CPresentation::deserialize(vp_token).map_err(|err| err_msg!("Invalid vp_token: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_nonce_binds_nonce_and_client_id() {
+        let nonce = derive_nonce("n-0S6_WzA2Mj", "https://client.example.org").unwrap();
+        assert_eq!(
+            nonce,
+            derive_nonce("n-0S6_WzA2Mj", "https://client.example.org").unwrap()
+        );
+        assert_ne!(
+            nonce,
+            derive_nonce("n-0S6_WzA2Mj", "https://other.example.org").unwrap()
+        );
+        assert_ne!(
+            nonce,
+            derive_nonce("n-0S6_WzA2Mk", "https://client.example.org").unwrap()
+        );
+        // values are length prefixed, so they can not be shifted between each other
+        assert_ne!(
+            derive_nonce("ab", "c").unwrap(),
+            derive_nonce("a", "bc").unwrap()
+        );
+        assert!(nonce.as_native().to_bytes().unwrap().len() <= helpers::DERIVED_NONCE_SIZE);
+    }
+}
@@ -0,0 +1,638 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::data_types::nonce::Nonce;
+use crate::data_types::pres_request::{
+    AttributeInfo, PredicateInfo, PredicateTypes, PredicateValue, PresentationRequest,
+    PresentationRequestBuilder, PresentationRequestVersion, RangeInfo,
+};
+use crate::data_types::w3c::credential::W3CCredential;
+use crate::data_types::w3c::presentation::W3CPresentation;
+use crate::data_types::w3c::presentation_exchange::{
+    DescriptorMapping, Field, FieldFilter, InputDescriptor, Optionality, PresentationDefinition,
+    PresentationSubmission,
+};
+use crate::error::Result;
+use crate::utils::query::Query;
+
+/// Claim format of AnonCreds W3C presentations in a presentation submission
+pub const PRESENTATION_FORMAT: &str = "di_vp";
+/// Claim format of AnonCreds W3C credentials in a presentation submission
+pub const CREDENTIAL_FORMAT: &str = "di_vc";
+
+/// Convert a DIF Presentation Exchange presentation definition into a presentation request.
+///
+/// Each input descriptor is translated into the following referents:
+/// - fields on `$.credentialSubject.<attribute>` are requested revealed, as an attribute
+///   group with the descriptor `id` as referent. A `const` or `enum` filter restricts the
+///   revealed value.
+/// - fields with `predicate: required` and a numeric `minimum`, `maximum`,
+///   `exclusiveMinimum` or `exclusiveMaximum` filter are requested as predicates, or as
+///   a range when bounded on both sides, with the referent `<descriptor id>::<attribute>`.
+/// - `$.issuer` fields and the `schema` property restrict the accepted issuers and schemas.
+///
+/// The attribute group, and the bounds of a range, are each proven by a single credential,
+/// but the holder may prove the referents of one descriptor with different credentials
+/// which all satisfy its issuer and schema restrictions.
+///
+/// Any other construct is rejected.
+pub fn create_presentation_request(
+    definition: &PresentationDefinition,
+    nonce: Nonce,
+) -> Result<PresentationRequest> {
+    trace!(
+        "create_presentation_request >>> definition: {:?}, nonce: {:?}",
+        definition, nonce
+    );
+
+    let name = definition.name.as_ref().unwrap_or(&definition.id);
+    let mut builder = PresentationRequestBuilder::new(name, "1.0", nonce);
+    // fully qualified identifiers are only accepted by the second request version
+    builder.set_request_version(PresentationRequestVersion::V2);
+
+    for descriptor in &definition.input_descriptors {
+        let request = DescriptorRequest::parse(descriptor)?;
+        let restrictions = request.restrictions();
+
+        if !request.attributes.is_empty() {
+            let mut info = AttributeInfo::group(request.attributes.iter().map(|(name, _)| name));
+            let value_restrictions = request.attributes.iter().filter_map(|(name, values)| {
+                values
+                    .as_ref()
+                    .map(|values| _restriction(&format!("attr::{name}::value"), values))
+            });
+            info.restrictions = _all(restrictions.iter().cloned().chain(value_restrictions));
+            builder.add_requested_attribute(&descriptor.id, info);
+        }
+        for (name, bounds) in &request.predicates {
+            let referent = format!("{}::{}", descriptor.id, name);
+            match bounds {
+                Bounds::Lower(p_type, value) | Bounds::Upper(p_type, value) => {
+                    let mut info = PredicateInfo::new(name, p_type.clone(), *value);
+                    info.restrictions = _all(restrictions.iter().cloned());
+                    builder.add_requested_predicate(referent, info);
+                }
+                Bounds::Range(lower, upper) => {
+                    let mut info = RangeInfo::new(name, *lower, *upper);
+                    info.restrictions = _all(restrictions.iter().cloned());
+                    builder.add_requested_range(referent, info);
+                }
+            }
+        }
+    }
+
+    let pres_req = builder.build()?;
+
+    trace!("create_presentation_request <<< pres_req: {:?}", pres_req);
+
+    Ok(pres_req)
+}
+
+/// Describe which credentials of a presentation, created for the request converted from
+/// `definition`, satisfy each of its input descriptors.
+///
+/// A descriptor proven by a single credential is mapped to it. A descriptor whose referents
+/// were proven by several credentials has one descriptor map entry for each of them.
+pub fn create_presentation_submission(
+    definition: &PresentationDefinition,
+    presentation: &W3CPresentation,
+) -> Result<PresentationSubmission> {
+    trace!(
+        "create_presentation_submission >>> definition: {:?}, presentation: {:?}",
+        definition, presentation
+    );
+
+    let mut descriptor_map = Vec::with_capacity(definition.input_descriptors.len());
+    for descriptor in &definition.input_descriptors {
+        let request = DescriptorRequest::parse(descriptor)?;
+        let indices = request
+            .credential_indices(&presentation.verifiable_credential)
+            .ok_or_else(|| {
+                err_msg!(
+                    "Presentation does not contain credentials for input descriptor \"{}\"",
+                    descriptor.id
+                )
+            })?;
+        descriptor_map.extend(indices.into_iter().map(|index| DescriptorMapping {
+            id: descriptor.id.clone(),
+            format: PRESENTATION_FORMAT.to_string(),
+            path: "$".to_string(),
+            path_nested: Some(Box::new(DescriptorMapping {
+                id: descriptor.id.clone(),
+                format: CREDENTIAL_FORMAT.to_string(),
+                path: format!("$.verifiableCredential[{index}]"),
+                path_nested: None,
+            })),
+        }));
+    }
+
+    let submission = PresentationSubmission {
+        id: _random_uuid(),
+        definition_id: definition.id.clone(),
+        descriptor_map,
+    };
+
+    trace!(
+        "create_presentation_submission <<< submission: {:?}",
+        submission
+    );
+
+    Ok(submission)
+}
+
+/// Bounds of a predicate field
+#[derive(Debug)]
+enum Bounds {
+    Lower(PredicateTypes, PredicateValue),
+    Upper(PredicateTypes, PredicateValue),
+    /// Inclusive lower and exclusive upper bound
+    Range(PredicateValue, PredicateValue),
+}
+
+/// Requirements of an input descriptor
+#[derive(Debug, Default)]
+struct DescriptorRequest {
+    /// Revealed attributes, with the accepted values if restricted
+    attributes: Vec<(String, Option<Vec<String>>)>,
+    predicates: Vec<(String, Bounds)>,
+    issuer_ids: Option<Vec<String>>,
+    schema_ids: Option<Vec<String>>,
+}
+
+enum FieldPath {
+    Attribute(String),
+    Issuer,
+}
+
+impl DescriptorRequest {
+    fn parse(descriptor: &InputDescriptor) -> Result<Self> {
+        let unsupported = |message: String| {
+            err_msg!(
+                "Unsupported input descriptor \"{}\": {}",
+                descriptor.id,
+                message
+            )
+        };
+
+        let mut request = Self {
+            schema_ids: descriptor
+                .schema
+                .as_ref()
+                .map(|schemas| schemas.iter().map(|schema| schema.uri.clone()).collect()),
+            ..Self::default()
+        };
+        if request.schema_ids.as_ref().is_some_and(Vec::is_empty) {
+            return Err(unsupported("empty schema list".to_string()));
+        }
+
+        for field in &descriptor.constraints.fields {
+            if field.optional == Some(true) {
+                return Err(unsupported("optional fields".to_string()));
+            }
+            let filter = field.filter.clone().unwrap_or_default();
+            match _field_path(field).map_err(&unsupported)? {
+                FieldPath::Issuer => {
+                    if field.predicate.is_some() || _has_bounds(&filter) {
+                        return Err(unsupported("predicates on the issuer".to_string()));
+                    }
+                    let issuer_ids = _accepted_values(&filter).map_err(&unsupported)?;
+                    if issuer_ids.is_none() {
+                        return Err(unsupported("issuer field without filter".to_string()));
+                    }
+                    request.issuer_ids = issuer_ids;
+                }
+                FieldPath::Attribute(name) => {
+                    if request.has_attribute(&name) {
+                        return Err(unsupported(format!("multiple fields on \"{name}\"")));
+                    }
+                    match field.predicate {
+                        Some(Optionality::Required) => {
+                            let bounds = _predicate_bounds(&filter)
+                                .map_err(|err| unsupported(format!("field \"{name}\": {err}")))?;
+                            request.predicates.push((name, bounds));
+                        }
+                        Some(Optionality::Preferred) => {
+                            return Err(unsupported("preferred predicates".to_string()));
+                        }
+                        None => {
+                            if _has_bounds(&filter) {
+                                return Err(unsupported(format!(
+                                    "numeric filter on \"{name}\" without `predicate: required`"
+                                )));
+                            }
+                            let values = _accepted_values(&filter).map_err(&unsupported)?;
+                            request.attributes.push((name, values));
+                        }
+                    }
+                }
+            }
+        }
+
+        if request.attributes.is_empty() && request.predicates.is_empty() {
+            return Err(unsupported(
+                "no fields on `$.credentialSubject` attributes".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+
+    fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|(attr, _)| attr == name)
+            || self.predicates.iter().any(|(attr, _)| attr == name)
+    }
+
+    /// Credential restrictions shared by all referents of the descriptor
+    fn restrictions(&self) -> Vec<Query> {
+        let mut restrictions = Vec::new();
+        if let Some(issuer_ids) = &self.issuer_ids {
+            restrictions.push(_restriction("issuer_id", issuer_ids));
+        }
+        if let Some(schema_ids) = &self.schema_ids {
+            restrictions.push(_restriction("schema_id", schema_ids));
+        }
+        restrictions
+    }
+
+    /// Indices of the credentials which prove the descriptor: a single credential if one
+    /// proves all of it, or else the credentials proving the attribute group and each
+    /// predicate or range
+    fn credential_indices(&self, credentials: &[W3CCredential]) -> Option<Vec<usize>> {
+        let requirements = self.requirements();
+        let proves = |credential: &W3CCredential, requirement: &Requirement| {
+            self.is_accepted(credential)
+                && match requirement {
+                    Requirement::Attributes => self.attributes_revealed(credential),
+                    Requirement::Predicates(predicates) => {
+                        Self::predicates_proven(credential, predicates)
+                    }
+                }
+        };
+
+        if let Some(index) = credentials.iter().position(|credential| {
+            requirements
+                .iter()
+                .all(|requirement| proves(credential, requirement))
+        }) {
+            return Some(vec![index]);
+        }
+        let indices = requirements
+            .iter()
+            .map(|requirement| {
+                credentials
+                    .iter()
+                    .position(|credential| proves(credential, requirement))
+            })
+            .collect::<Option<BTreeSet<usize>>>()?;
+        Some(indices.into_iter().collect())
+    }
+
+    /// Parts of the descriptor which are each proven by a single credential
+    fn requirements(&self) -> Vec<Requirement> {
+        let attributes = (!self.attributes.is_empty()).then_some(Requirement::Attributes);
+        let predicates = self.predicates.iter().map(|(name, bounds)| {
+            Requirement::Predicates(match bounds {
+                Bounds::Lower(p_type, value) | Bounds::Upper(p_type, value) => {
+                    vec![PredicateInfo::new(name, p_type.clone(), *value)]
+                }
+                Bounds::Range(lower, upper) => {
+                    let range = RangeInfo::new(name, *lower, *upper);
+                    vec![range.lower_predicate(), range.upper_predicate()]
+                }
+            })
+        });
+        attributes.into_iter().chain(predicates).collect()
+    }
+
+    /// Whether the credential satisfies the issuer and schema restrictions
+    fn is_accepted(&self, credential: &W3CCredential) -> bool {
+        let Ok(proof) = credential.get_credential_presentation_proof() else {
+            return false;
+        };
+        self.issuer_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&credential.issuer.0))
+            && self
+                .schema_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&proof.schema_id.0))
+    }
+
+    fn attributes_revealed(&self, credential: &W3CCredential) -> bool {
+        self.attributes.iter().all(|(name, values)| {
+            credential.get_attribute(name).is_ok_and(|(_, value)| {
+                values
+                    .as_ref()
+                    .is_none_or(|values| values.contains(&value.to_string()))
+            })
+        })
+    }
+
+    fn predicates_proven(credential: &W3CCredential, predicates: &[PredicateInfo]) -> bool {
+        let Ok(proof) = credential.get_credential_presentation_proof() else {
+            return false;
+        };
+        let cl_predicates = proof.sub_proof.predicates();
+        predicates.iter().all(|predicate| {
+            credential
+                .get_predicate(&predicate.name)
+                .is_ok_and(|(name, _)| {
+                    cl_predicates.iter().any(|p| {
+                        p.attr_name == name
+                            && p.p_type == predicate.p_type.clone().into()
+                            && p.value == predicate.p_value
+                    })
+                })
+        })
+    }
+}
+
+/// Part of an input descriptor proven by a single credential
+enum Requirement {
+    Attributes,
+    Predicates(Vec<PredicateInfo>),
+}
+
+/// Find the first supported JSONPath expression of a field
+fn _field_path(field: &Field) -> std::result::Result<FieldPath, String> {
+    field
+        .path
+        .iter()
+        .find_map(|path| {
+            if path == "$.issuer" || path == "$.issuer.id" {
+                return Some(FieldPath::Issuer);
+            }
+            let attribute = path.strip_prefix("$.credentialSubject")?;
+            let name = attribute.strip_prefix('.').or_else(|| {
+                attribute
+                    .strip_prefix("['")
+                    .and_then(|name| name.strip_suffix("']"))
+                    .or_else(|| {
+                        attribute
+                            .strip_prefix("[\"")
+                            .and_then(|name| name.strip_suffix("\"]"))
+                    })
+            })?;
+            let is_plain = !name.is_empty()
+                && !name
+                    .chars()
+                    .any(|c| matches!(c, '.' | '[' | ']' | '*' | '\'' | '"' | '$' | '@'));
+            is_plain.then(|| FieldPath::Attribute(name.to_string()))
+        })
+        .ok_or_else(|| format!("none of the field paths {:?} is supported", field.path))
+}
+
+fn _has_bounds(filter: &FieldFilter) -> bool {
+    filter.minimum.is_some()
+        || filter.maximum.is_some()
+        || filter.exclusive_minimum.is_some()
+        || filter.exclusive_maximum.is_some()
+}
+
+/// Values accepted by a `const` or `enum` filter, if any
+fn _accepted_values(filter: &FieldFilter) -> std::result::Result<Option<Vec<String>>, String> {
+    let values = match (&filter.const_, &filter.enum_) {
+        (Some(_), Some(_)) => return Err("filter with both `const` and `enum`".to_string()),
+        (Some(value), None) => vec![value],
+        (None, Some(values)) => values.iter().collect(),
+        (None, None) => return Ok(None),
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(value) => Ok(value.clone()),
+            Value::Number(value) => Ok(value.to_string()),
+            value => Err(format!("filter value {value} is not a string or number")),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn _predicate_bounds(filter: &FieldFilter) -> std::result::Result<Bounds, String> {
+    if filter.const_.is_some() || filter.enum_.is_some() {
+        return Err("predicates only support numeric bounds".to_string());
+    }
+    let bound = |value: &Option<Value>| -> std::result::Result<Option<PredicateValue>, String> {
+        value
+            .as_ref()
+            .map(|value| {
+                let bound = value
+                    .as_i64()
+                    .ok_or_else(|| format!("bound {value} is not an integer"))?;
+                PredicateValue::try_from(bound).map_err(|_| {
+                    format!(
+                        "bound {bound} is outside the 32-bit range supported by predicate proofs"
+                    )
+                })
+            })
+            .transpose()
+    };
+    let lower = match (bound(&filter.minimum)?, bound(&filter.exclusive_minimum)?) {
+        (Some(_), Some(_)) => return Err("both `minimum` and `exclusiveMinimum`".to_string()),
+        (Some(value), None) => Some((PredicateTypes::GE, value)),
+        (None, Some(value)) => Some((PredicateTypes::GT, value)),
+        (None, None) => None,
+    };
+    let upper = match (bound(&filter.maximum)?, bound(&filter.exclusive_maximum)?) {
+        (Some(_), Some(_)) => return Err("both `maximum` and `exclusiveMaximum`".to_string()),
+        (Some(value), None) => Some((PredicateTypes::LE, value)),
+        (None, Some(value)) => Some((PredicateTypes::LT, value)),
+        (None, None) => None,
+    };
+    let out_of_range = || "bound out of range".to_string();
+    match (lower, upper) {
+        (Some((p_type, value)), None) => Ok(Bounds::Lower(p_type, value)),
+        (None, Some((p_type, value))) => Ok(Bounds::Upper(p_type, value)),
+        (Some((lower_type, lower)), Some((upper_type, upper))) => {
+            let lower = if lower_type == PredicateTypes::GT {
+                lower.checked_add(1).ok_or_else(out_of_range)?
+            } else {
+                lower
+            };
+            let upper = if upper_type == PredicateTypes::LE {
+                upper.checked_add(1).ok_or_else(out_of_range)?
+            } else {
+                upper
+            };
+            Ok(Bounds::Range(lower, upper))
+        }
+        (None, None) => Err("predicate without numeric bound".to_string()),
+    }
+}
+
+fn _restriction(tag: &str, values: &[String]) -> Query {
+    match values {
+        [value] => Query::Eq(tag.to_string(), value.clone()),
+        values => Query::In(tag.to_string(), values.to_vec()),
+    }
+}
+
+fn _all(restrictions: impl Iterator<Item = Query>) -> Option<Query> {
+    let mut restrictions: Vec<Query> = restrictions.collect();
+    match restrictions.len() {
+        0 => None,
+        1 => restrictions.pop(),
+        _ => Some(Query::And(restrictions)),
+    }
+}
+
+/// Random version 4 UUID
+fn _random_uuid() -> String {
+    let bytes = (rand::random::<u128>() & !(0xf_u128 << 76) & !(0x3_u128 << 62))
+        | (0x4_u128 << 76)
+        | (0x2_u128 << 62);
+    let hex = format!("{bytes:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::pres_request::PresentationRequestPayload;
+
+    const ISSUER_ID: &str = "did:web:issuer.example";
+    const SCHEMA_ID: &str = "did:web:issuer.example/resource/schema";
+
+    fn _definition(fields: Value) -> PresentationDefinition {
+        serde_json::from_value(json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "name": "gvt",
+            "input_descriptors": [{
+                "id": "gvt",
+                "schema": [{ "uri": SCHEMA_ID }],
+                "constraints": { "limit_disclosure": "required", "fields": fields }
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn _request(fields: Value) -> Result<PresentationRequestPayload> {
+        let pres_req = create_presentation_request(&_definition(fields), Nonce::new().unwrap())?;
+        assert_eq!(pres_req.version(), PresentationRequestVersion::V2);
+        match pres_req {
+            PresentationRequest::PresentationRequestV1(payload)
+            | PresentationRequest::PresentationRequestV2(payload) => Ok(payload),
+        }
+    }
+
+    #[test]
+    fn create_presentation_request_works() {
+        let pres_req = _request(json!([
+            { "path": ["$.issuer"], "filter": { "type": "string", "const": ISSUER_ID } },
+            { "path": ["$.credentialSubject.name"], "filter": { "const": "Alex" } },
+            { "path": ["$.vc.credentialSubject.sex", "$.credentialSubject['sex']"] },
+            {
+                "path": ["$.credentialSubject.age"],
+                "predicate": "required",
+                "filter": { "type": "integer", "exclusiveMinimum": 18 }
+            },
+            {
+                "path": ["$.credentialSubject.height"],
+                "predicate": "required",
+                "filter": { "type": "integer", "minimum": 150, "maximum": 200 }
+            }
+        ]))
+        .unwrap();
+
+        let credential_restrictions = vec![
+            Query::Eq("issuer_id".to_string(), ISSUER_ID.to_string()),
+            Query::Eq("schema_id".to_string(), SCHEMA_ID.to_string()),
+        ];
+        assert_eq!(pres_req.name, "gvt");
+
+        let attribute = &pres_req.requested_attributes["gvt"];
+        assert_eq!(
+            attribute.names,
+            Some(vec!["name".to_string(), "sex".to_string()])
+        );
+        let mut attribute_restrictions = credential_restrictions.clone();
+        attribute_restrictions.push(Query::Eq(
+            "attr::name::value".to_string(),
+            "Alex".to_string(),
+        ));
+        assert_eq!(
+            attribute.restrictions,
+            Some(Query::And(attribute_restrictions))
+        );
+
+        let predicate = &pres_req.requested_predicates["gvt::age"];
+        assert_eq!(predicate.p_type, PredicateTypes::GT);
+        assert_eq!(predicate.p_value, 18);
+        assert_eq!(
+            predicate.restrictions,
+            Some(Query::And(credential_restrictions.clone()))
+        );
+
+        let range = &pres_req.requested_ranges["gvt::height"];
+        assert_eq!((range.lower, range.upper), (150, 201));
+        assert_eq!(pres_req.requested_predicates.len(), 3);
+    }
+
+    #[test]
+    fn create_presentation_request_rejects_unsupported_constructs() {
+        for fields in [
+            json!([{ "path": ["$.credentialSubject.name"], "optional": true }]),
+            json!([{ "path": ["$.vc.credentialSubject.name"] }]),
+            json!([{ "path": ["$.credentialSubject.address.city"] }]),
+            json!([{ "path": ["$.credentialSubject.age"], "filter": { "minimum": 18 } }]),
+            json!([{
+                "path": ["$.credentialSubject.age"],
+                "predicate": "preferred",
+                "filter": { "minimum": 18 }
+            }]),
+            json!([{
+                "path": ["$.credentialSubject.age"],
+                "predicate": "required",
+                "filter": { "minimum": 18.5 }
+            }]),
+            json!([{
+                "path": ["$.credentialSubject.age"],
+                "predicate": "required",
+                "filter": { "const": 18 }
+            }]),
+            json!([{ "path": ["$.issuer"], "filter": { "const": ISSUER_ID } }]),
+            json!([
+                { "path": ["$.credentialSubject.name"] },
+                { "path": ["$.credentialSubject.name"], "filter": { "const": "Alex" } }
+            ]),
+        ] {
+            let err = _request(fields.clone()).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("Unsupported input descriptor \"gvt\""),
+                "{fields}: {err}"
+            );
+        }
+
+        // unknown filter keywords are rejected when parsing the definition
+        let definition = serde_json::from_value::<PresentationDefinition>(json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "input_descriptors": [{
+                "id": "gvt",
+                "constraints": {
+                    "fields": [{
+                        "path": ["$.credentialSubject.name"],
+                        "filter": { "type": "string", "pattern": "^A" }
+                    }]
+                }
+            }]
+        }));
+        assert!(definition.is_err());
+    }
+
+    #[test]
+    fn random_uuid_is_version_4() {
+        let uuid = _random_uuid();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(uuid, _random_uuid());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
 use crate::data_types::pres_request::PresentationRequestPayload;
+use crate::data_types::presentation::Identifier;
 use crate::data_types::schema::{Schema, SchemaId};
 use crate::data_types::w3c::credential::W3CCredential;
 use crate::data_types::w3c::presentation::W3CPresentation;
 use crate::error::Result;
+use crate::services::resolver::{AnoncredsResolver, ResolvedObjects};
 use crate::types::{
     CredentialRequestMetadata, CredentialValues, LinkSecret, PresentCredential, PresentCredentials,
     PresentationRequest, RevocationRegistryDefinition,
@@ -15,7 +17,7 @@ use crate::data_types::w3c::credential_attributes::CredentialSubject;
 use crate::data_types::w3c::proof::{
     CredentialPresentationProofValue, DataIntegrityProof, PresentationProofValue,
 };
-use crate::prover::{CLCredentialProver, CLProofBuilder};
+use crate::prover::{CLCredentialProver, CLProofBuilder, MatchCredential};
 use std::collections::HashMap;
 
 /// Process an incoming credential in W3C form as received from the issuer.
@@ -98,6 +100,47 @@ pub fn process_credential(
     link_secret: &LinkSecret,
     cred_def: &CredentialDefinition,
     rev_reg_def: Option<&RevocationRegistryDefinition>,
+) -> Result<()> {
+    store_credential(
+        w3c_credential,
+        cred_request_metadata,
+        link_secret,
+        cred_def,
+        None,
+        rev_reg_def,
+    )
+}
+
+/// Process an incoming credential in W3C form whose schema declares typed encodings
+///
+/// The credential values are encoded with the encodings declared by `schema` before the
+/// signature is checked, as they were when the credential was issued with
+/// [`w3c::issuer::create_credential_with_schema`](crate::w3c::issuer::create_credential_with_schema).
+pub fn process_credential_with_schema(
+    w3c_credential: &mut W3CCredential,
+    cred_request_metadata: &CredentialRequestMetadata,
+    link_secret: &LinkSecret,
+    cred_def: &CredentialDefinition,
+    schema: &Schema,
+    rev_reg_def: Option<&RevocationRegistryDefinition>,
+) -> Result<()> {
+    store_credential(
+        w3c_credential,
+        cred_request_metadata,
+        link_secret,
+        cred_def,
+        Some(schema),
+        rev_reg_def,
+    )
+}
+
+fn store_credential(
+    w3c_credential: &mut W3CCredential,
+    cred_request_metadata: &CredentialRequestMetadata,
+    link_secret: &LinkSecret,
+    cred_def: &CredentialDefinition,
+    schema: Option<&Schema>,
+    rev_reg_def: Option<&RevocationRegistryDefinition>,
 ) -> Result<()> {
     trace!(
         "process_w3c_credential >>> credential: {:?}, cred_request_metadata: {:?}, link_secret: {:?}, cred_def: {:?}, rev_reg_def: {:?}",
@@ -108,10 +151,13 @@ pub fn process_credential(
         rev_reg_def
     );
 
-    let cred_values = w3c_credential.credential_subject.encode()?;
+    w3c_credential.validate_credential_status()?;
+
+    let cred_values = w3c_credential.credential_subject.encode(schema)?;
 
     let proof = w3c_credential.get_mut_data_integrity_proof()?;
     let mut credential_signature = proof.get_credential_signature_proof()?.clone();
+    credential_signature.check_attr_encodings(schema)?;
 
     CLCredentialProver::new(link_secret).process_credential(
         &mut credential_signature.signature,
@@ -167,8 +213,10 @@ pub fn create_presentation(
             continue;
         }
         let credential = present.cred;
-        let credential_values: CredentialValues = credential.credential_subject.encode()?;
         let proof = credential.get_credential_signature_proof()?;
+        let schema = schemas.get(&proof.schema_id);
+        proof.check_attr_encodings(schema)?;
+        let credential_values: CredentialValues = credential.credential_subject.encode(schema)?;
 
         let proof_link_secret = present.link_secret.unwrap_or(link_secret);
         proof_builder.add_sub_proof(
@@ -199,7 +247,8 @@ pub fn create_presentation(
             sub_proof,
         };
         let proof = DataIntegrityProof::new_credential_presentation_proof(&proof)?;
-        let credential = W3CCredential::derive(credential_attributes, proof, present.cred);
+        let credential = W3CCredential::derive(credential_attributes, proof, present.cred)
+            .with_credential_status()?;
         verifiable_credentials.push(credential);
         // Temporary hack - use `cred_def_id` verification_method for presentation
         pres_verification_method = credential_proof.cred_def_id.to_string();
@@ -223,6 +272,64 @@ pub fn create_presentation(
     Ok(presentation)
 }
 
+/// Create W3C presentation, fetching the schemas and credential definitions of the presented
+/// credentials from a resolver
+///
+/// As with `prover::create_presentation_with_resolver`, revocation objects are not fetched:
+/// the revocation states passed along with `credentials` must be created beforehand.
+pub fn create_presentation_with_resolver<R>(
+    pres_req: &PresentationRequest,
+    credentials: PresentCredentials<W3CCredential>,
+    link_secret: &LinkSecret,
+    resolver: &R,
+    version: Option<VerifiableCredentialSpecVersion>,
+) -> Result<W3CPresentation>
+where
+    R: AnoncredsResolver + ?Sized,
+{
+    let identifiers = credentials
+        .0
+        .iter()
+        .map(|present| {
+            let proof = present.cred.get_credential_signature_proof()?;
+            Ok(Identifier {
+                schema_id: proof.schema_id.clone(),
+                cred_def_id: proof.cred_def_id.clone(),
+                rev_reg_id: None,
+                timestamp: None,
+            })
+        })
+        .collect::<Result<Vec<Identifier>>>()?;
+    let resolved = ResolvedObjects::resolve(resolver, &identifiers)?;
+
+    create_presentation(
+        pres_req,
+        credentials,
+        link_secret,
+        &resolved.schemas,
+        &resolved.cred_defs,
+        version,
+    )
+}
+
+impl MatchCredential for W3CCredential {
+    fn identifier(&self) -> Result<Identifier> {
+        let proof = self.get_credential_signature_proof()?;
+        Ok(Identifier {
+            schema_id: proof.schema_id.clone(),
+            cred_def_id: proof.cred_def_id.clone(),
+            rev_reg_id: proof.rev_reg_id.clone(),
+            timestamp: None,
+        })
+    }
+
+    fn raw_value(&self, attr_name: &str) -> Option<String> {
+        self.get_attribute(attr_name)
+            .ok()
+            .map(|(_, value)| value.to_string())
+    }
+}
+
 fn build_credential_attributes<'p>(
     pres_req: &PresentationRequestPayload,
     credentials: &PresentCredential<'p, W3CCredential>,
@@ -249,6 +356,7 @@ fn build_credential_attributes<'p>(
         }
     }
 
+    pres_req.check_requested_ranges(&credentials.requested_predicates)?;
     for referent in credentials.requested_predicates.iter() {
         let predicate_info = pres_req
             .requested_predicates
@@ -1,7 +1,9 @@
 use crate::data_types::cred_def::CredentialDefinition;
 use crate::data_types::cred_def::CredentialDefinitionId;
 use crate::data_types::pres_request::PresentationRequestPayload;
-use crate::data_types::pres_request::{NonRevokedInterval, PredicateInfo};
+use crate::data_types::pres_request::{
+    AttributeInfo, NonRevokedInterval, PredicateInfo, RangeInfo,
+};
 use crate::data_types::presentation::Identifier;
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
 use crate::data_types::schema::Schema;
@@ -11,8 +13,16 @@ use crate::data_types::w3c::credential_attributes::CredentialAttributeValue;
 use crate::data_types::w3c::presentation::W3CPresentation;
 use crate::data_types::w3c::proof::CredentialPresentationProofValue;
 use crate::error::Result;
-use crate::services::helpers::{encode_credential_attribute, get_requested_non_revoked_interval};
-use crate::types::{PresentationRequest, RevocationRegistryDefinition, RevocationStatusList};
+use crate::services::helpers::{
+    attr_common_view, encode_credential_attribute, get_requested_non_revoked_interval,
+};
+use crate::services::nonce_registry::NonceRegistry;
+use crate::services::resolver::{AnoncredsResolver, ResolvedObjects};
+use crate::services::w3c::openid4vp;
+use crate::types::{
+    CheckResult, PresentationRequest, ReferentKind, ReferentReport, RevocationRegistryDefinition,
+    RevocationStatusList, VerificationReport,
+};
 use crate::utils::query::Query;
 use crate::verifier::{CLProofVerifier, verify_revealed_attribute_value};
 use crate::verifier::{gather_filter_info, process_operator};
@@ -40,6 +50,8 @@ pub fn verify_presentation(
 
     let presentation_request = pres_req.value();
 
+    check_presentation_challenge(presentation, presentation_request)?;
+
     // we need to decode proofs in advance as their data needed in two places: checking
     // against the request and proof verification itself
     let credential_proofs = presentation
@@ -95,6 +107,256 @@ pub fn verify_presentation(
     Ok(valid)
 }
 
+/// Verify an incoming presentation in W3C form, fetching the ledger objects referenced by
+/// the credential proofs from a resolver
+pub fn verify_presentation_with_resolver<R>(
+    presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    resolver: &R,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool>
+where
+    R: AnoncredsResolver + ?Sized,
+{
+    let identifiers = presentation
+        .verifiable_credential
+        .iter()
+        .map(|vc| vc.get_credential_presentation_proof().map(proof_identifier))
+        .collect::<Result<Vec<Identifier>>>()?;
+    let resolved = ResolvedObjects::resolve(resolver, &identifiers)?;
+
+    verify_presentation(
+        presentation,
+        pres_req,
+        &resolved.schemas,
+        &resolved.cred_defs,
+        resolved.rev_reg_defs(),
+        resolved.rev_status_lists(),
+        nonrevoke_interval_override,
+    )
+}
+
+/// Verify an incoming presentation in W3C form, consuming the presentation request nonce
+/// from a registry
+///
+/// This is the W3C counterpart of `verifier::verify_presentation_with_nonce_registry`.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_presentation_with_nonce_registry<N>(
+    presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+    nonce_registry: &N,
+) -> Result<bool>
+where
+    N: NonceRegistry + ?Sized,
+{
+    let valid = verify_presentation(
+        presentation,
+        pres_req,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists,
+        nonrevoke_interval_override,
+    )?;
+
+    if valid {
+        nonce_registry.consume(&pres_req.value().nonce)?;
+    }
+
+    Ok(valid)
+}
+
+/// Verify an incoming presentation in W3C form received in response to an OpenID4VP request
+///
+/// `nonce` and `client_id` are the values of the verifier's own OpenID4VP request, from
+/// which the presentation request nonce was derived with
+/// [`openid4vp::derive_nonce`](crate::w3c::openid4vp::derive_nonce). The presentation must
+/// be bound to them, as done by
+/// [`openid4vp::bind_presentation`](crate::w3c::openid4vp::bind_presentation), so that a
+/// presentation made for another relying party is rejected.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_openid4vp_presentation(
+    presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    nonce: &str,
+    client_id: &str,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool> {
+    trace!(
+        "verify_openid4vp_presentation >>> nonce: {:?}, client_id: {:?}",
+        nonce, client_id
+    );
+
+    check_openid4vp_binding(presentation, pres_req.value(), nonce, client_id)?;
+
+    verify_presentation(
+        presentation,
+        pres_req,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists,
+        nonrevoke_interval_override,
+    )
+}
+
+/// Verify an incoming presentation in W3C form, reporting the outcome of every check
+///
+/// This is the W3C counterpart of `verifier::verify_presentation_detailed`. Requested
+/// referents are matched against the presented credentials in the same way as in
+/// `verify_presentation`; for a referent that cannot be matched, the checks are reported
+/// against the closest candidate credential.
+pub fn verify_presentation_detailed(
+    presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<VerificationReport> {
+    trace!(
+        "verify_w3c_presentation_detailed >>> presentation: {:?}, pres_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_status_lists: {:?}",
+        presentation, pres_req, schemas, cred_defs, rev_reg_defs, rev_status_lists
+    );
+
+    presentation.validate()?;
+
+    let presentation_request = pres_req.value();
+
+    let credential_proofs = presentation
+        .verifiable_credential
+        .iter()
+        .map(|vc| vc.get_credential_presentation_proof().cloned())
+        .collect::<Result<Vec<CredentialPresentationProofValue>>>()?;
+
+    let presentation_proof = presentation.get_presentation_proof()?;
+
+    let mut report = VerificationReport {
+        identifiers: credential_proofs.iter().map(proof_identifier).collect(),
+        ..VerificationReport::default()
+    };
+
+    if let Err(err) = check_presentation_challenge(presentation, presentation_request) {
+        report.errors.push(err.to_string());
+    }
+
+    for (index, (cred, proof)) in presentation
+        .verifiable_credential
+        .iter()
+        .zip(&credential_proofs)
+        .enumerate()
+    {
+        if let Err(err) = check_credential_consistency(cred, proof, cred_defs) {
+            report.errors.push(format!("Credential {index}: {err}"));
+        }
+    }
+
+    let mut proof_verifier = CLProofVerifier::new(
+        presentation_request,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists.as_ref(),
+    )?;
+
+    let sub_proof_results = credential_proofs
+        .iter()
+        .map(|credential_proof| {
+            proof_verifier
+                .add_sub_proof(
+                    &credential_proof.sub_proof,
+                    &credential_proof.schema_id,
+                    &credential_proof.cred_def_id,
+                    credential_proof.rev_reg_id.as_ref(),
+                    credential_proof.timestamp,
+                )
+                .into()
+        })
+        .collect::<Vec<CheckResult>>();
+
+    let context = ReportContext {
+        presentation_request,
+        presentation,
+        schemas,
+        cred_defs,
+        nonrevoke_interval_override,
+        credential_proofs: &credential_proofs,
+        sub_proof_results: &sub_proof_results,
+    };
+
+    for (referent, info) in &presentation_request.requested_attributes {
+        let names = info
+            .name
+            .iter()
+            .chain(info.names.iter().flatten())
+            .collect::<Vec<&String>>();
+        // report the first failing attribute of a group, if any
+        let entries = names
+            .into_iter()
+            .map(|name| context.attribute_entry(name, info))
+            .collect::<Vec<ReferentReport>>();
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| !entry.is_valid())
+            .or_else(|| entries.first())
+        {
+            report
+                .requested_attributes
+                .insert(referent.clone(), entry.clone());
+        }
+    }
+
+    for (referent, predicate) in &presentation_request.requested_predicates {
+        report
+            .requested_predicates
+            .insert(referent.clone(), context.predicate_entry(predicate));
+    }
+
+    report.proof = if sub_proof_results
+        .iter()
+        .all(|res| *res == CheckResult::Passed)
+    {
+        let cl_proof = Proof {
+            proofs: credential_proofs
+                .into_iter()
+                .map(|proof| proof.sub_proof)
+                .collect(),
+            aggregated_proof: presentation_proof.aggregated.clone(),
+        };
+        match proof_verifier.verify(&cl_proof) {
+            Ok(true) => CheckResult::Passed,
+            Ok(false) => CheckResult::Failed("Proof verification failed".to_string()),
+            Err(err) => CheckResult::Failed(err.to_string()),
+        }
+    } else {
+        CheckResult::Skipped
+    };
+
+    report.finalize();
+
+    trace!("verify_w3c_presentation_detailed <<< report: {:?}", report);
+
+    Ok(report)
+}
+
 fn check_credential_restrictions(
     credential: &W3CCredential,
     restrictions: Option<&Query>,
@@ -118,6 +380,10 @@ fn check_credential_restrictions(
             if let CredentialAttributeValue::Number(value) = value {
                 attr_value_map.insert(attribute.to_owned(), Some(value.to_string()));
             }
+            // predicate attributes are present in the credential, but their value is not revealed
+            if let CredentialAttributeValue::Bool(_) = value {
+                attr_value_map.insert(attribute.to_owned(), None);
+            }
         }
         process_operator(&attr_value_map, restrictions, &filter).map_err(err_map!(
             "Requested restriction validation failed for \"{:?}\" attributes",
@@ -176,6 +442,33 @@ fn check_credential_conditions(
     Ok(())
 }
 
+/// Check that a revealed attribute value matches the encoded value in the credential proof,
+/// encoding it with the encoding declared by the credential schema, if any.
+fn verify_revealed_credential_attribute(
+    attribute: &str,
+    raw: &str,
+    proof: &CredentialPresentationProofValue,
+    schemas: &HashMap<SchemaId, Schema>,
+) -> Result<()> {
+    let encoding = schemas
+        .get(&proof.schema_id)
+        .and_then(|schema| schema.attr_encoding(attribute));
+    let encoded = match encoding {
+        Some(encoding) => encoding.encode(raw)?,
+        None => encode_credential_attribute(raw)?,
+    };
+    verify_revealed_attribute_value(attribute, &proof.sub_proof, raw, &encoded, encoding)
+}
+
+fn reveals_attribute(sub_proof: &SubProof, attribute: &str) -> bool {
+    let attribute = attr_common_view(attribute);
+    sub_proof.revealed_attrs().is_ok_and(|revealed| {
+        revealed
+            .keys()
+            .any(|name| attr_common_view(name) == attribute)
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn check_requested_attribute<'a>(
     presentation_request: &PresentationRequestPayload,
@@ -199,8 +492,9 @@ fn check_requested_attribute<'a>(
                 .get(index)
                 .ok_or_else(|| err_msg!("Unable to get credential proof for index {}", index))?;
 
-            let encoded = encode_credential_attribute(&value.to_string())?;
-            if verify_revealed_attribute_value(&attribute, &proof.sub_proof, &encoded).is_err() {
+            if verify_revealed_credential_attribute(&attribute, &value.to_string(), proof, schemas)
+                .is_err()
+            {
                 continue;
             }
 
@@ -233,8 +527,12 @@ fn check_requested_attribute<'a>(
             .get(&proof.schema_id)
             .ok_or_else(|| err_msg!("Credential schema not found {}", proof.schema_id))?;
 
-        // credential schema must contain requested attribute
-        if !schema.has_case_insensitive_attribute(attribute) {
+        // credential schema must contain requested attribute, and the cl proof must not
+        // reveal it, as its disclosed value failed the check above
+        if !schema.has_case_insensitive_attribute(attribute)
+            || (credential.get_attribute(attribute).is_ok()
+                && reveals_attribute(&proof.sub_proof, attribute))
+        {
             continue;
         }
 
@@ -277,49 +575,109 @@ fn check_requested_predicate<'a>(
 ) -> Result<&'a W3CCredential> {
     // find a credential matching to requested predicate
     for (index, credential) in presentation.verifiable_credential.iter().enumerate() {
-        // credential must contain requested predicate in subject
-        if let Ok((name, _)) = credential.get_predicate(&predicate.name) {
-            // predicate value must match to predicate in cl proof
-            let proof = credential_proofs
-                .get(index)
-                .ok_or_else(|| err_msg!("Unable to get credential proof for index {}", index))?;
+        let proof = credential_proofs
+            .get(index)
+            .ok_or_else(|| err_msg!("Unable to get credential proof for index {}", index))?;
 
-            let matches_cl_proof_predicate = proof.sub_proof.predicates().into_iter().find(|p| {
-                p.attr_name == name
-                    && p.p_type == predicate.clone().p_type.into()
-                    && p.value == predicate.p_value
-            });
+        if proves_requested_predicate(
+            credential,
+            proof,
+            presentation_request,
+            predicate,
+            schemas,
+            cred_defs,
+            nonrevoke_interval_override,
+        ) {
+            return Ok(credential);
+        }
+    }
 
-            if matches_cl_proof_predicate.is_none() {
-                continue;
-            }
+    Err(err_msg!(
+        "Presentation does not contain predicate {}",
+        predicate.name
+    ))
+}
 
-            // check credential restrictions
-            if check_credential_conditions(
+#[allow(clippy::too_many_arguments)]
+fn check_requested_range<'a>(
+    presentation_request: &PresentationRequestPayload,
+    presentation: &'a W3CPresentation,
+    credential_proofs: &[CredentialPresentationProofValue],
+    range_referent: &str,
+    range: &RangeInfo,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<&'a W3CCredential> {
+    // both bounds of the range must be proven by the same credential
+    let bounds = [range.lower_predicate(), range.upper_predicate()];
+    for (index, credential) in presentation.verifiable_credential.iter().enumerate() {
+        let proof = credential_proofs
+            .get(index)
+            .ok_or_else(|| err_msg!("Unable to get credential proof for index {}", index))?;
+
+        if bounds.iter().all(|predicate| {
+            proves_requested_predicate(
                 credential,
+                proof,
                 presentation_request,
-                predicate.restrictions.as_ref(),
+                predicate,
                 schemas,
                 cred_defs,
-                predicate.non_revoked.as_ref(),
                 nonrevoke_interval_override,
-                proof,
             )
-            .is_err()
-            {
-                continue;
-            }
-
+        }) {
             return Ok(credential);
         }
     }
 
     Err(err_msg!(
-        "Presentation does not contain predicate {}",
-        predicate.name
+        ProofRejected,
+        "Bounds of requested range \"{}\" are not proven by the same credential",
+        range_referent
     ))
 }
 
+fn proves_requested_predicate(
+    credential: &W3CCredential,
+    proof: &CredentialPresentationProofValue,
+    presentation_request: &PresentationRequestPayload,
+    predicate: &PredicateInfo,
+    schemas: &HashMap<SchemaId, Schema>,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    nonrevoke_interval_override: Option<
+        &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> bool {
+    // credential must contain requested predicate in subject
+    let Ok((name, _)) = credential.get_predicate(&predicate.name) else {
+        return false;
+    };
+
+    // predicate value must match to predicate in cl proof
+    let matches_cl_proof_predicate = proof.sub_proof.predicates().into_iter().any(|p| {
+        p.attr_name == name
+            && p.p_type == predicate.clone().p_type.into()
+            && p.value == predicate.p_value
+    });
+
+    // check credential restrictions
+    matches_cl_proof_predicate
+        && check_credential_conditions(
+            credential,
+            presentation_request,
+            predicate.restrictions.as_ref(),
+            schemas,
+            cred_defs,
+            predicate.non_revoked.as_ref(),
+            nonrevoke_interval_override,
+            proof,
+        )
+        .is_ok()
+}
+
 fn check_request_data(
     presentation_request: &PresentationRequestPayload,
     presentation: &W3CPresentation,
@@ -360,7 +718,11 @@ fn check_request_data(
             }
         }
     }
-    for (_, predicate) in presentation_request.requested_predicates.iter() {
+    for (referent, predicate) in presentation_request.requested_predicates.iter() {
+        // range bounds are checked together below
+        if presentation_request.range_for_predicate(referent).is_some() {
+            continue;
+        }
         check_requested_predicate(
             presentation_request,
             presentation,
@@ -371,6 +733,18 @@ fn check_request_data(
             nonrevoke_interval_override,
         )?;
     }
+    for (referent, range) in presentation_request.requested_ranges.iter() {
+        check_requested_range(
+            presentation_request,
+            presentation,
+            credential_proofs,
+            referent,
+            range,
+            schemas,
+            cred_defs,
+            nonrevoke_interval_override,
+        )?;
+    }
 
     for (cred, proof) in presentation
         .verifiable_credential
@@ -378,22 +752,267 @@ fn check_request_data(
         .iter()
         .zip(credential_proofs)
     {
-        let Some(cred_def_issuer) = cred_defs.get(&proof.cred_def_id).map(|cd| &cd.issuer_id)
-        else {
-            return Err(err_msg!("Missing credential definition"));
-        };
-        if cred_def_issuer != &cred.issuer {
-            return Err(err_msg!("Inconsistent issuer ID"));
-        }
-        let di_proof = cred.get_data_integrity_proof()?;
-        if di_proof.verification_method != proof.cred_def_id.0 {
-            return Err(err_msg!("Inconsistent credential definition ID"));
+        check_credential_consistency(cred, proof, cred_defs)?;
+    }
+
+    Ok(())
+}
+
+/// Check that the presentation proof challenge matches the request nonce, either directly
+/// or as the OpenID4VP nonce and client_id (`domain`) the request nonce was derived from.
+///
+/// The `domain` is supplied by the presentation itself, and presentations without challenge
+/// are accepted; use [`verify_openid4vp_presentation`] to check an OpenID4VP presentation
+/// against the verifier's own nonce and client_id.
+fn check_presentation_challenge(
+    presentation: &W3CPresentation,
+    presentation_request: &PresentationRequestPayload,
+) -> Result<()> {
+    let Some(challenge) = presentation.proof.challenge() else {
+        return Ok(());
+    };
+    let matches = match presentation.proof.domain() {
+        Some(client_id) => {
+            openid4vp::derive_nonce(challenge, client_id)? == presentation_request.nonce
         }
+        None => challenge == presentation_request.nonce.to_string(),
+    };
+    if !matches {
+        return Err(err_msg!(
+            ProofRejected,
+            "Presentation challenge does not match the presentation request nonce"
+        ));
+    }
+    Ok(())
+}
+
+/// Check that the presentation is bound to the verifier's OpenID4VP `nonce` and `client_id`,
+/// and that the presentation request nonce was derived from them.
+fn check_openid4vp_binding(
+    presentation: &W3CPresentation,
+    presentation_request: &PresentationRequestPayload,
+    nonce: &str,
+    client_id: &str,
+) -> Result<()> {
+    if presentation.proof.challenge() != Some(nonce) {
+        return Err(err_msg!(
+            ProofRejected,
+            "Presentation challenge does not match the OpenID4VP nonce"
+        ));
     }
+    if presentation.proof.domain() != Some(client_id) {
+        return Err(err_msg!(
+            ProofRejected,
+            "Presentation domain does not match the OpenID4VP client_id"
+        ));
+    }
+    if openid4vp::derive_nonce(nonce, client_id)? != presentation_request.nonce {
+        return Err(err_msg!(
+            "Presentation request nonce was not derived from the OpenID4VP nonce and client_id"
+        ));
+    }
+    Ok(())
+}
 
+fn check_credential_consistency(
+    cred: &W3CCredential,
+    proof: &CredentialPresentationProofValue,
+    cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+) -> Result<()> {
+    let Some(cred_def_issuer) = cred_defs.get(&proof.cred_def_id).map(|cd| &cd.issuer_id) else {
+        return Err(err_msg!("Missing credential definition"));
+    };
+    if cred_def_issuer != &cred.issuer {
+        return Err(err_msg!("Inconsistent issuer ID"));
+    }
+    let di_proof = cred.get_data_integrity_proof()?;
+    if di_proof.verification_method != proof.cred_def_id.0 {
+        return Err(err_msg!("Inconsistent credential definition ID"));
+    }
     Ok(())
 }
 
+fn proof_identifier(proof: &CredentialPresentationProofValue) -> Identifier {
+    Identifier {
+        schema_id: proof.schema_id.to_owned(),
+        cred_def_id: proof.cred_def_id.to_owned(),
+        rev_reg_id: proof.rev_reg_id.to_owned(),
+        timestamp: proof.timestamp,
+    }
+}
+
+/// Shared state for building the report entries of a detailed verification
+struct ReportContext<'a> {
+    presentation_request: &'a PresentationRequestPayload,
+    presentation: &'a W3CPresentation,
+    schemas: &'a HashMap<SchemaId, Schema>,
+    cred_defs: &'a HashMap<CredentialDefinitionId, CredentialDefinition>,
+    nonrevoke_interval_override:
+        Option<&'a HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>>,
+    credential_proofs: &'a [CredentialPresentationProofValue],
+    sub_proof_results: &'a [CheckResult],
+}
+
+impl ReportContext<'_> {
+    fn credential_index(&self, credential: &W3CCredential) -> Option<usize> {
+        self.presentation
+            .verifiable_credential
+            .iter()
+            .position(|cred| std::ptr::eq(cred, credential))
+    }
+
+    fn new_entry(&self, kind: ReferentKind, index: usize) -> ReferentReport {
+        let mut entry = ReferentReport::new(
+            kind,
+            Some(index as u32),
+            self.credential_proofs.get(index).map(proof_identifier),
+        );
+        entry.sub_proof = self
+            .sub_proof_results
+            .get(index)
+            .cloned()
+            .unwrap_or_default();
+        entry
+    }
+
+    fn check_conditions(
+        &self,
+        entry: &mut ReferentReport,
+        index: usize,
+        restrictions: Option<&Query>,
+        nonrevoke_interval: Option<&NonRevokedInterval>,
+    ) {
+        let credential = &self.presentation.verifiable_credential[index];
+        let proof = &self.credential_proofs[index];
+        if restrictions.is_some() {
+            entry.restrictions = check_credential_restrictions(
+                credential,
+                restrictions,
+                self.schemas,
+                self.cred_defs,
+                proof,
+            )
+            .into();
+        }
+        if proof.rev_reg_id.is_some() {
+            entry.non_revoked = check_credential_non_revoked_interval(
+                self.presentation_request,
+                nonrevoke_interval,
+                self.nonrevoke_interval_override,
+                proof,
+            )
+            .into();
+        }
+    }
+
+    fn attribute_entry(&self, attribute: &str, info: &AttributeInfo) -> ReferentReport {
+        let matched = check_requested_attribute(
+            self.presentation_request,
+            self.presentation,
+            attribute,
+            info.restrictions.as_ref(),
+            info.non_revoked.as_ref(),
+            self.schemas,
+            self.cred_defs,
+            self.nonrevoke_interval_override,
+            self.credential_proofs,
+        );
+
+        // when no credential satisfies the request, report on the closest candidate:
+        // a credential revealing the attribute, or else one whose schema contains it
+        let index = match matched {
+            Ok(credential) => self.credential_index(credential),
+            Err(_) => self
+                .presentation
+                .verifiable_credential
+                .iter()
+                .position(|cred| cred.get_attribute(attribute).is_ok())
+                .or_else(|| {
+                    self.credential_proofs.iter().position(|proof| {
+                        self.schemas
+                            .get(&proof.schema_id)
+                            .is_some_and(|schema| schema.has_case_insensitive_attribute(attribute))
+                    })
+                }),
+        };
+        let Some(index) = index else {
+            return ReferentReport::missing(format!(
+                "Presentation does not contain attribute {attribute}"
+            ));
+        };
+
+        let credential = &self.presentation.verifiable_credential[index];
+        let mut entry = if let Ok((name, value)) = credential.get_attribute(attribute) {
+            let mut entry = self.new_entry(ReferentKind::Revealed, index);
+            entry.revealed_value = verify_revealed_credential_attribute(
+                &name,
+                &value.to_string(),
+                &self.credential_proofs[index],
+                self.schemas,
+            )
+            .into();
+            entry
+        } else {
+            self.new_entry(ReferentKind::Unrevealed, index)
+        };
+        self.check_conditions(
+            &mut entry,
+            index,
+            info.restrictions.as_ref(),
+            info.non_revoked.as_ref(),
+        );
+
+        if let Err(err) = matched {
+            if entry.is_valid() {
+                entry.restrictions = CheckResult::Failed(err.to_string());
+            }
+        }
+        entry
+    }
+
+    fn predicate_entry(&self, predicate: &PredicateInfo) -> ReferentReport {
+        let matched = check_requested_predicate(
+            self.presentation_request,
+            self.presentation,
+            self.credential_proofs,
+            predicate,
+            self.schemas,
+            self.cred_defs,
+            self.nonrevoke_interval_override,
+        );
+
+        let index = match matched {
+            Ok(credential) => self.credential_index(credential),
+            Err(_) => self
+                .presentation
+                .verifiable_credential
+                .iter()
+                .position(|cred| cred.get_predicate(&predicate.name).is_ok()),
+        };
+        let Some(index) = index else {
+            return ReferentReport::missing(format!(
+                "Presentation does not contain predicate {}",
+                predicate.name
+            ));
+        };
+
+        let mut entry = self.new_entry(ReferentKind::Predicate, index);
+        self.check_conditions(
+            &mut entry,
+            index,
+            predicate.restrictions.as_ref(),
+            predicate.non_revoked.as_ref(),
+        );
+
+        if let Err(err) = matched {
+            if entry.is_valid() {
+                entry.sub_proof = CheckResult::Failed(err.to_string());
+            }
+        }
+        entry
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -466,7 +1085,9 @@ pub(crate) mod tests {
             version: "1.0".to_string(),
             requested_attributes: HashMap::new(),
             requested_predicates: HashMap::new(),
+            requested_ranges: HashMap::new(),
             non_revoked: None,
+            verifier_domain: None,
         }
     }
 
@@ -791,6 +1412,77 @@ pub(crate) mod tests {
         assert_eq!(ErrorKind::Input, err.kind());
     }
 
+    #[rstest]
+    fn test_mock_presentation_reveals_subject_attribute(
+        schemas: HashMap<SchemaId, Schema>,
+        presentation: W3CPresentation,
+    ) {
+        // the value disclosed in the credential subject matches the one revealed in the proof
+        let credential = &presentation.verifiable_credential[0];
+        let (attribute, value) = credential.get_attribute("name").unwrap();
+        verify_revealed_credential_attribute(
+            &attribute,
+            &value.to_string(),
+            credential.get_credential_presentation_proof().unwrap(),
+            &schemas,
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    fn test_check_request_data_fails_for_tampered_revealed_attribute(
+        schemas: HashMap<SchemaId, Schema>,
+        cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition>,
+        mut presentation: W3CPresentation,
+    ) {
+        // the proof reveals "Alice", so the attribute must not pass as unrevealed instead
+        presentation.verifiable_credential[0]
+            .credential_subject
+            .0
+            .insert(
+                "name".to_string(),
+                CredentialAttributeValue::String("Bob".to_string()),
+            );
+
+        check_request_data(
+            &_presentation_request_with_single_attribute(),
+            &presentation,
+            &schemas,
+            &cred_defs,
+            None,
+            &presentation.credential_proofs(),
+        )
+        .unwrap_err();
+    }
+
+    #[rstest]
+    fn test_check_request_data_requires_range_bounds_from_one_credential(
+        schemas: HashMap<SchemaId, Schema>,
+        cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition>,
+        presentation: W3CPresentation,
+    ) {
+        // the credential proves `age >= 18` but not `age < 65`
+        let mut presentation_request = PresentationRequestPayload {
+            requested_ranges: HashMap::from([(
+                "range1_referent".to_string(),
+                RangeInfo::new("age", 18, 65),
+            )]),
+            .._base_presentation_request()
+        };
+        presentation_request.expand_ranges().unwrap();
+
+        let err = check_request_data(
+            &presentation_request,
+            &presentation,
+            &schemas,
+            &cred_defs,
+            None,
+            &presentation.credential_proofs(),
+        )
+        .unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+    }
+
     #[rstest]
     fn test_check_request_data_works_for_unrevealed_attributes(
         schemas: HashMap<SchemaId, Schema>,
@@ -959,4 +1651,63 @@ pub(crate) mod tests {
         .unwrap_err();
         assert_eq!(ErrorKind::Input, err.kind());
     }
+
+    #[test]
+    fn test_check_openid4vp_binding() {
+        let mut presentation = _w3_presentation();
+        let mut presentation_request = _base_presentation_request();
+        presentation_request.nonce = openid4vp::derive_nonce("nonce", "client").unwrap();
+
+        // a presentation without challenge is not bound to the request
+        presentation.proof.challenge = None;
+        presentation.proof.domain = None;
+        let err = check_openid4vp_binding(&presentation, &presentation_request, "nonce", "client")
+            .unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+
+        presentation.proof.challenge = Some("nonce".to_string());
+        let err = check_openid4vp_binding(&presentation, &presentation_request, "nonce", "client")
+            .unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+
+        presentation.proof.domain = Some("client".to_string());
+        check_openid4vp_binding(&presentation, &presentation_request, "nonce", "client").unwrap();
+
+        // the domain is checked against the verifier's own client_id
+        let err = check_openid4vp_binding(
+            &presentation,
+            &presentation_request,
+            "nonce",
+            "other client",
+        )
+        .unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+        let _err = check_openid4vp_binding(&presentation, &presentation_request, "other", "client")
+            .unwrap_err();
+
+        presentation_request.nonce = Nonce::new().unwrap();
+        let _err = check_openid4vp_binding(&presentation, &presentation_request, "nonce", "client")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_check_presentation_challenge() {
+        let mut presentation = _w3_presentation();
+        let mut presentation_request = _base_presentation_request();
+
+        presentation_request.nonce = Nonce::from_dec("1").unwrap();
+        check_presentation_challenge(&presentation, &presentation_request).unwrap();
+
+        presentation_request.nonce = openid4vp::derive_nonce("nonce", "client").unwrap();
+        let err = check_presentation_challenge(&presentation, &presentation_request).unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+
+        presentation.proof.challenge = Some("nonce".to_string());
+        presentation.proof.domain = Some("client".to_string());
+        check_presentation_challenge(&presentation, &presentation_request).unwrap();
+
+        presentation.proof.domain = Some("other client".to_string());
+        let err = check_presentation_challenge(&presentation, &presentation_request).unwrap_err();
+        assert_eq!(ErrorKind::ProofRejected, err.kind());
+    }
 }
@@ -3,7 +3,6 @@ pub mod validation;
 
 pub mod base58;
 
-#[cfg(feature = "w3c")]
 pub mod base64;
 
 pub mod query;
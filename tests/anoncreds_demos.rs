@@ -1,9 +1,19 @@
+use anoncreds::data_types::nonce::Nonce;
 use anoncreds::data_types::w3c::VerifiableCredentialSpecVersion;
 use anoncreds::data_types::w3c::credential_attributes::CredentialAttributeValue;
+use anoncreds::data_types::w3c::presentation_exchange::PresentationDefinition;
+use anoncreds::issuer::{self, RevocationRegistryManager};
+use anoncreds::nonce_registry::{InMemoryNonceRegistry, NonceRegistry};
+use anoncreds::resolver::{AnoncredsResolver, DirectoryResolver};
+use anoncreds::types::{
+    AttributeEncoding, CalendarDate, CheckResult, DateEncoding, DateOffset, MakeCredentialValues,
+    PredicateInfo, PredicateTypes, PresentationRequest, RangeInfo, ReferentKind, SelectionPolicy,
+};
 use anoncreds::{verifier, w3c};
 use rstest::rstest;
 use serde_json::json;
 use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
 
 use utils::*;
 
@@ -189,6 +199,70 @@ fn anoncreds_demo_works_for_single_issuer_single_prover(
     );
 }
 
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_with_comparison_restrictions(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("attr1_referent", PresentAttributeForm::RevealedAttribute),
+            ("predicate1_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+
+    // Verifier restricts the schema version and identifiers with comparison operators
+    let verify = |schema_version_restriction: serde_json::Value| {
+        let pres_request = serde_json::from_value(json!({
+            "nonce": demo.verifier_wallet.generate_nonce(),
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{
+                    "name":"name",
+                    "restrictions": {
+                        "$and": [
+                            { "schema_version": schema_version_restriction },
+                            { "cred_def_id": { "$like": "creddef:gov%" } },
+                            { "attr::name::value": { "$like": "Al_x" } },
+                        ]
+                    }
+                }
+            },
+            "requested_predicates":{
+                "predicate1_referent":{
+                    "name":"age",
+                    "p_type":">=",
+                    "p_value":18,
+                    "restrictions": { "$exist": ["attr::age::value"] }
+                }
+            }
+        }))
+        .expect("Error creating proof request");
+
+        let presentation =
+            demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+        demo.verify_presentation(&presentation, &pres_request)
+    };
+
+    assert!(verify(json!({ "$gte": "1" })).unwrap());
+    assert!(verify(json!({ "$lt": "1.10" })).unwrap());
+    assert!(!matches!(verify(json!({ "$gt": "1.0.0" })), Ok(true)));
+}
+
 #[rstest]
 #[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
 #[case(CredentialFormat::W3C, PresentationFormat::W3C)]
@@ -244,6 +318,48 @@ fn anoncreds_demo_works_with_revocation_for_single_issuer_single_prover(
         None,
     );
 
+    // W3C credential names its revocation registry and index in `credentialStatus`,
+    // also when converted from the legacy form
+    let w3c_credential = match &issue_cred {
+        Credentials::Legacy(credential) => {
+            w3c::credential_conversion::credential_to_w3c(credential, &gvt_cred_def.issuer_id, None)
+                .expect("Error converting credential to W3C form")
+        }
+        Credentials::W3C(credential) => credential.clone(),
+    };
+    let status = w3c_credential
+        .credential_status
+        .as_ref()
+        .expect("Revocable credential must contain credential status");
+    assert_eq!(status.id.to_string(), gvt_rev_reg_def_id);
+    assert_eq!(
+        status.revocation_registry_index,
+        Some(fixtures::GVT_REV_IDX)
+    );
+    let context = serde_json::to_value(&w3c_credential.context).expect("Error serializing context");
+    assert!(
+        context
+            .as_array()
+            .expect("Context must be a list")
+            .iter()
+            .any(|context| context.get("AnonCredsCredentialStatusList2023").is_some())
+    );
+
+    // The status survives a round trip through the legacy form
+    let legacy_credential = w3c::credential_conversion::credential_from_w3c(&w3c_credential)
+        .expect("Error converting credential from W3C form");
+    let converted = w3c::credential_conversion::credential_to_w3c(
+        &legacy_credential,
+        &gvt_cred_def.issuer_id,
+        None,
+    )
+    .expect("Error converting credential to W3C form");
+    assert_eq!(
+        converted.credential_status,
+        w3c_credential.credential_status
+    );
+    assert_eq!(converted.context, w3c_credential.context);
+
     let time_after_creating_cred = time_create_rev_status_list + 1;
     let issued_rev_status_list = issuer_wallet.update_revocation_status_list(
         &gvt_cred_def,
@@ -344,6 +460,16 @@ fn anoncreds_demo_works_with_revocation_for_single_issuer_single_prover(
         None,
     );
 
+    // Presented credentials name the registry only, never the holder's index
+    if let Presentations::W3C(presentation) = &presentation {
+        let status = presentation.verifiable_credential[0]
+            .credential_status
+            .as_ref()
+            .expect("Revocable credential must contain credential status");
+        assert_eq!(status.id.to_string(), gvt_rev_reg_def_id);
+        assert_eq!(status.revocation_registry_index, None);
+    }
+
     let valid = verifier_wallet
         .verify_presentation(
             &presentation,
@@ -432,6 +558,284 @@ fn anoncreds_demo_works_with_revocation_for_single_issuer_single_prover(
     assert!(!valid);
 }
 
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_with_revocation_registry_manager(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    let mut demo = Demo::default();
+    let (gvt_schema_id, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, true);
+    let gvt_cred_def = demo.cred_def(&gvt_cred_def_id).try_clone().unwrap();
+
+    // Issuer hands the revocation registry over to a manager
+    let time_create_rev_status_list = 12;
+    let mut manager = demo.issuer_wallet.create_revocation_registry_manager(
+        &mut demo.ledger,
+        &gvt_cred_def,
+        Some(time_create_rev_status_list),
+        true,
+    );
+    let gvt_rev_reg_def = manager.rev_reg_def().clone();
+    let gvt_rev_reg_def_id = manager.rev_reg_def_id().to_string();
+
+    // The manager issues the credential at the first free index
+    let cred_offer = demo
+        .issuer_wallet
+        .create_credential_offer(&gvt_schema_id, &gvt_cred_def_id);
+    let (cred_request, cred_request_metadata) = demo
+        .prover_wallet
+        .create_credential_request(&gvt_cred_def, &cred_offer);
+    let time_issue_cred = time_create_rev_status_list + 1;
+    let mut rec_cred = demo.issuer_wallet.create_credential_with_manager(
+        &credential_format,
+        &mut manager,
+        &cred_offer,
+        &cred_request,
+        fixtures::credential_values(GVT_CRED).into(),
+        Some(time_issue_cred),
+    );
+    assert_eq!(manager.issued_count(), 1);
+    let issued_rev_status_list = manager.status_list().clone();
+
+    // Prover receives the credential and processes it
+    demo.prover_wallet.store_credential(
+        GVT_CRED,
+        &mut rec_cred,
+        &cred_request_metadata,
+        &gvt_cred_def,
+        Some(&gvt_rev_reg_def),
+    );
+
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{ "name":"name" }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        },
+        "non_revoked": {"from": 10, "to": 200}
+    }))
+    .expect("Error creating proof request");
+
+    let schemas = demo.schemas();
+    let cred_defs = demo.cred_defs();
+    let rev_reg_def_map = demo.ledger.resolve_rev_reg_defs(vec![&gvt_rev_reg_def_id]);
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("attr1_referent", PresentAttributeForm::RevealedAttribute),
+            ("predicate1_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+
+    let rev_idx = match &rec_cred {
+        Credentials::Legacy(credential) => credential.signature.extract_index(),
+        Credentials::W3C(credential) => credential
+            .get_credential_signature_proof()
+            .unwrap()
+            .signature
+            .extract_index(),
+    }
+    .unwrap();
+    let rev_state = demo.prover_wallet.create_or_update_revocation_state(
+        &gvt_rev_reg_def.value.tails_location,
+        &gvt_rev_reg_def,
+        &issued_rev_status_list,
+        rev_idx,
+        None,
+        None,
+    );
+    let time_present = rev_state.timestamp;
+    demo.prover_wallet.rev_states.insert(
+        gvt_rev_reg_def_id.clone(),
+        (Some(rev_state.clone()), Some(time_present)),
+    );
+
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    let valid = demo
+        .verifier_wallet
+        .verify_presentation(
+            &presentation,
+            &pres_request,
+            &schemas,
+            &cred_defs,
+            Some(&rev_reg_def_map),
+            Some(vec![issued_rev_status_list.clone()]),
+            None,
+        )
+        .unwrap();
+    assert!(valid);
+
+    // The issuer persists the manager, restores it and revokes the credential
+    let json = serde_json::to_string(&manager).unwrap();
+    let mut manager: RevocationRegistryManager = serde_json::from_str(&json).unwrap();
+    let time_revoke_cred = time_issue_cred + 1;
+    let revoked_rev_status_list = demo.issuer_wallet.revoke_credential_with_manager(
+        &mut manager,
+        &gvt_cred_def,
+        &rec_cred,
+        Some(time_revoke_cred),
+    );
+
+    let rev_state = demo.prover_wallet.create_or_update_revocation_state(
+        &gvt_rev_reg_def.value.tails_location,
+        &gvt_rev_reg_def,
+        &revoked_rev_status_list,
+        rev_idx,
+        Some(&rev_state),
+        Some(&issued_rev_status_list),
+    );
+    demo.prover_wallet.rev_states.insert(
+        gvt_rev_reg_def_id.clone(),
+        (Some(rev_state), Some(time_revoke_cred)),
+    );
+
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    let valid = demo
+        .verifier_wallet
+        .verify_presentation(
+            &presentation,
+            &pres_request,
+            &schemas,
+            &cred_defs,
+            Some(&rev_reg_def_map),
+            Some(vec![issued_rev_status_list, revoked_rev_status_list]),
+            None,
+        )
+        .unwrap();
+    assert!(!valid);
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy, false)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C, false)]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy, true)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C, true)]
+fn anoncreds_demo_works_with_resolver_for_single_issuer_single_prover(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+    #[case] use_directory: bool,
+) {
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, true);
+
+    // Create revocation registry and issue a revocable credential
+    let time_create_rev_status_list = 12;
+    let (gvt_rev_reg_def_id, gvt_rev_reg_def, gvt_revocation_status_list) =
+        demo.create_revocation_registry(&gvt_cred_def_id, time_create_rev_status_list);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        Some(IssuanceRevocation {
+            rev_reg_def_id: &gvt_rev_reg_def_id,
+            status_list: &gvt_revocation_status_list,
+            rev_idx: fixtures::GVT_REV_IDX,
+        }),
+    );
+
+    // Verifier creates a presentation request
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"name"
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        },
+        "non_revoked": {"from": 10, "to": 200}
+    }))
+    .expect("Error creating proof request");
+
+    // The prover presents the state of the registry at a later time at which
+    // the status list created at `time_create_rev_status_list` was still current
+    let time_present = time_create_rev_status_list + 5;
+    let rev_state = demo.prover_wallet.create_or_update_revocation_state(
+        &gvt_rev_reg_def.value.tails_location,
+        &gvt_rev_reg_def,
+        &gvt_revocation_status_list,
+        fixtures::GVT_REV_IDX,
+        None,
+        None,
+    );
+    demo.prover_wallet.rev_states.insert(
+        gvt_rev_reg_def_id.to_string(),
+        (Some(rev_state), Some(time_present)),
+    );
+
+    // Publish the ledger objects through a resolver
+    let mut in_memory_resolver = demo.ledger.resolver();
+    in_memory_resolver
+        .add_rev_status_list(gvt_revocation_status_list.clone())
+        .unwrap();
+    let directory = std::env::temp_dir().join(format!(
+        "anoncreds-resolver-{}",
+        demo.verifier_wallet.generate_nonce()
+    ));
+    let directory_resolver = DirectoryResolver::new(&directory);
+    for (schema_id, schema) in &demo.schemas() {
+        directory_resolver.store_schema(schema_id, schema).unwrap();
+    }
+    for (cred_def_id, cred_def) in &demo.cred_defs() {
+        directory_resolver
+            .store_cred_def(cred_def_id, cred_def)
+            .unwrap();
+    }
+    directory_resolver
+        .store_rev_reg_def(
+            &gvt_rev_reg_def_id.as_str().try_into().unwrap(),
+            &gvt_rev_reg_def,
+        )
+        .unwrap();
+    directory_resolver
+        .store_rev_status_list(&gvt_revocation_status_list)
+        .unwrap();
+    let resolver: &dyn AnoncredsResolver = if use_directory {
+        &directory_resolver
+    } else {
+        &in_memory_resolver
+    };
+
+    // Prover creates presentation
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("attr1_referent", PresentAttributeForm::RevealedAttribute),
+            ("predicate1_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+    let presentation = demo.prover_wallet.create_presentation_with_resolver(
+        &presentation_format,
+        resolver,
+        &pres_request,
+        &present_credentials,
+        None,
+    );
+
+    // Verifier verifies presentation
+    let valid = demo
+        .verifier_wallet
+        .verify_presentation_with_resolver(&presentation, &pres_request, resolver, None)
+        .expect("Error verifying presentation");
+    assert!(valid);
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
 #[rstest]
 #[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
 #[case(CredentialFormat::W3C, PresentationFormat::W3C)]
@@ -609,94 +1013,312 @@ fn anoncreds_demo_works_for_multiple_issuer_single_prover(
 #[rstest]
 #[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
 #[case(CredentialFormat::W3C, PresentationFormat::W3C)]
-fn anoncreds_demo_proof_does_not_verify_with_wrong_attr_and_predicates(
+fn anoncreds_demo_matches_credentials_for_multiple_issuer_single_prover(
     #[case] credential_format: CredentialFormat,
     #[case] presentation_format: PresentationFormat,
 ) {
-    // Create pseudo ledger and wallets
-    let mut ledger = Ledger::default();
-    let mut issuer_wallet = IssuerWallet::default();
-    let mut prover_wallet = ProverWallet::default();
-    let verifier_wallet = VerifierWallet::default();
-
-    // Create schema
-    let (gvt_schema, gvt_schema_id) = issuer_wallet.create_schema(&mut ledger, GVT_CRED);
-
-    // Create credential definition
-    let (gvt_cred_def, gvt_cred_def_id) =
-        issuer_wallet.create_cred_def(&mut ledger, &gvt_schema, false);
-
-    // Issuer creates a Credential Offer
-    let cred_offer = issuer_wallet.create_credential_offer(&gvt_schema_id, &gvt_cred_def_id);
-
-    // Prover creates a Credential Request
-    let (cred_request, cred_request_metadata) =
-        prover_wallet.create_credential_request(&gvt_cred_def, &cred_offer);
-
-    // Issuer creates a credential
-    let cred_values = fixtures::credential_values(GVT_CRED);
-    let issue_cred = issuer_wallet.create_credential(
+    // Issuers publish the GVT and EMP credential definitions and issue credentials
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    let (_, emp_cred_def_id) = demo.create_cred_def(EMP_CRED, false);
+    demo.issue_credential(
         &credential_format,
+        GVT_CRED,
         &gvt_cred_def_id,
-        &cred_offer,
-        &cred_request,
-        cred_values.into(),
-        None,
-        None,
-        None,
+        fixtures::credential_values(GVT_CRED).into(),
         None,
     );
-
-    // Prover receives the credential and processes it
-    let mut recv_cred = issue_cred;
-    prover_wallet.store_credential(
-        GVT_CRED,
-        &mut recv_cred,
-        &cred_request_metadata,
-        &gvt_cred_def,
+    demo.issue_credential(
+        &credential_format,
+        EMP_CRED,
+        &emp_cred_def_id,
+        fixtures::credential_values(EMP_CRED).into(),
         None,
     );
 
-    // Verifier creates a presentation request
-    let nonce = verifier_wallet.generate_nonce();
+    let nonce = demo.verifier_wallet.generate_nonce();
     let pres_request = serde_json::from_value(json!({
         "nonce": nonce,
-        "name":"pres_req_1",
+        "name":"proof_req_1",
         "version":"0.1",
-        "requested_attributes":{
-            "attr1_referent":{
-                "name":"name"
+        "requested_attributes": {
+            "any_name_referent": { "name": "name" },
+            "gvt_name_referent": {
+                "name": "name",
+                "restrictions": { "cred_def_id": gvt_cred_def_id, "attr::name::value": "Alex" }
             },
-            "attr2_referent":{
-                "name":"missing attribute"
+            "role_referent": {
+                "names": ["name", "role"],
+                "restrictions": { "attr::department::marker": "1" }
             },
-            "attr3_referent":{
-                "names": ["name", "height"]
+            "wrong_value_referent": {
+                "name": "height",
+                "restrictions": { "attr::height::value": "180" }
             }
         },
-        "requested_predicates":{
-            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        "requested_predicates": {
+            "adult_referent": { "name": "age", "p_type": ">=", "p_value": 18 },
+            "senior_referent": { "name": "age", "p_type": ">", "p_value": 65 }
         }
     }))
     .expect("Error creating proof request");
 
-    // Prover creates presentation
-    // `attr2_referent` is missing
-    let present_credentials = vec![CredentialToPresent {
-        id: GVT_CRED.to_string(),
-        attributes: vec![
-            PresentAttribute {
-                referent: "attr1_referent".to_string(),
-                form: PresentAttributeForm::RevealedAttribute,
-            },
-            PresentAttribute {
-                referent: "attr3_referent".to_string(),
-                form: PresentAttributeForm::RevealedAttribute,
-            },
-            PresentAttribute {
-                referent: "predicate1_referent".to_string(),
-                form: PresentAttributeForm::Predicate,
-            },
+    // Prover finds the credentials which may fill each referent
+    let matches = demo.prover_wallet.match_credentials(
+        &credential_format,
+        &pres_request,
+        &demo.schemas(),
+        &demo.cred_defs(),
+    );
+    let expected = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+    assert_eq!(
+        matches["any_name_referent"],
+        expected(&[GVT_CRED, EMP_CRED])
+    );
+    assert_eq!(matches["gvt_name_referent"], expected(&[GVT_CRED]));
+    assert_eq!(matches["role_referent"], expected(&[EMP_CRED]));
+    assert_eq!(matches["wrong_value_referent"], expected(&[]));
+    assert_eq!(matches["adult_referent"], expected(&[GVT_CRED]));
+    assert_eq!(matches["senior_referent"], expected(&[]));
+
+    // A presentation built from the matched credentials is accepted by the verifier
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"proof_req_1",
+        "version":"0.1",
+        "requested_attributes": {
+            "gvt_name_referent": {
+                "name": "name",
+                "restrictions": { "cred_def_id": gvt_cred_def_id, "attr::name::value": "Alex" }
+            },
+            "role_referent": {
+                "names": ["name", "role"],
+                "restrictions": { "attr::department::marker": "1" }
+            }
+        },
+        "requested_predicates": {
+            "adult_referent": { "name": "age", "p_type": ">=", "p_value": 18 }
+        }
+    }))
+    .expect("Error creating proof request");
+    let present_credentials = [
+        CredentialToPresent::new(
+            GVT_CRED,
+            &[
+                ("gvt_name_referent", PresentAttributeForm::RevealedAttribute),
+                ("adult_referent", PresentAttributeForm::Predicate),
+            ],
+        ),
+        CredentialToPresent::new(
+            EMP_CRED,
+            &[("role_referent", PresentAttributeForm::RevealedAttribute)],
+        ),
+    ];
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    assert!(
+        demo.verify_presentation(&presentation, &pres_request)
+            .unwrap()
+    );
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_with_automatic_credential_selection(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    // Issuers publish a revocable GVT and a non-revocable EMP credential definition
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, true);
+    let (_, emp_cred_def_id) = demo.create_cred_def(EMP_CRED, false);
+    let time_create_rev_status_list = 12;
+    let (gvt_rev_reg_def_id, gvt_rev_reg_def, gvt_revocation_status_list) =
+        demo.create_revocation_registry(&gvt_cred_def_id, time_create_rev_status_list);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        Some(IssuanceRevocation {
+            rev_reg_def_id: &gvt_rev_reg_def_id,
+            status_list: &gvt_revocation_status_list,
+            rev_idx: fixtures::GVT_REV_IDX,
+        }),
+    );
+    demo.issue_credential(
+        &credential_format,
+        EMP_CRED,
+        &emp_cred_def_id,
+        fixtures::credential_values(EMP_CRED).into(),
+        None,
+    );
+
+    // Prover keeps a revocation state for the GVT credential
+    let rev_state = demo.prover_wallet.create_or_update_revocation_state(
+        &gvt_rev_reg_def.value.tails_location,
+        &gvt_rev_reg_def,
+        &gvt_revocation_status_list,
+        fixtures::GVT_REV_IDX,
+        None,
+        None,
+    );
+    demo.prover_wallet.rev_states.insert(
+        gvt_rev_reg_def_id.to_string(),
+        (Some(rev_state), Some(time_create_rev_status_list)),
+    );
+
+    // Verifier asks for a name which both credentials hold, and a phone number which
+    // only the legacy format can self-attest
+    let mut requested_attributes = json!({
+        "name_referent": { "name": "name" },
+        "role_referent": { "name": "role" }
+    });
+    if presentation_format == PresentationFormat::Legacy {
+        requested_attributes["phone_referent"] = json!({ "name": "phone" });
+    }
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes": requested_attributes,
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        },
+        "non_revoked": {"from": 10, "to": 200}
+    }))
+    .expect("Error creating proof request");
+
+    let schemas = demo.schemas();
+    let cred_defs = demo.cred_defs();
+    let rev_reg_def_map = demo.ledger.resolve_rev_reg_defs(vec![&gvt_rev_reg_def_id]);
+    let self_attested_values =
+        HashMap::from([("phone_referent".to_string(), "8-800-300".to_string())]);
+
+    for policy in [
+        SelectionPolicy::FewestCredentials,
+        SelectionPolicy::LastAdded,
+        SelectionPolicy::NonRevocable,
+    ] {
+        // Prover lets the library choose the credentials to present
+        let presentation = demo.prover_wallet.create_presentation_with_selection(
+            &presentation_format,
+            &schemas,
+            &cred_defs,
+            &pres_request,
+            policy,
+            &self_attested_values,
+        );
+
+        let valid = demo
+            .verifier_wallet
+            .verify_presentation(
+                &presentation,
+                &pres_request,
+                &schemas,
+                &cred_defs,
+                Some(&rev_reg_def_map),
+                Some(vec![gvt_revocation_status_list.clone()]),
+                None,
+            )
+            .unwrap();
+        assert!(valid);
+    }
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_proof_does_not_verify_with_wrong_attr_and_predicates(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    // Create pseudo ledger and wallets
+    let mut ledger = Ledger::default();
+    let mut issuer_wallet = IssuerWallet::default();
+    let mut prover_wallet = ProverWallet::default();
+    let verifier_wallet = VerifierWallet::default();
+
+    // Create schema
+    let (gvt_schema, gvt_schema_id) = issuer_wallet.create_schema(&mut ledger, GVT_CRED);
+
+    // Create credential definition
+    let (gvt_cred_def, gvt_cred_def_id) =
+        issuer_wallet.create_cred_def(&mut ledger, &gvt_schema, false);
+
+    // Issuer creates a Credential Offer
+    let cred_offer = issuer_wallet.create_credential_offer(&gvt_schema_id, &gvt_cred_def_id);
+
+    // Prover creates a Credential Request
+    let (cred_request, cred_request_metadata) =
+        prover_wallet.create_credential_request(&gvt_cred_def, &cred_offer);
+
+    // Issuer creates a credential
+    let cred_values = fixtures::credential_values(GVT_CRED);
+    let issue_cred = issuer_wallet.create_credential(
+        &credential_format,
+        &gvt_cred_def_id,
+        &cred_offer,
+        &cred_request,
+        cred_values.into(),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // Prover receives the credential and processes it
+    let mut recv_cred = issue_cred;
+    prover_wallet.store_credential(
+        GVT_CRED,
+        &mut recv_cred,
+        &cred_request_metadata,
+        &gvt_cred_def,
+        None,
+    );
+
+    // Verifier creates a presentation request
+    let nonce = verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"name"
+            },
+            "attr2_referent":{
+                "name":"missing attribute"
+            },
+            "attr3_referent":{
+                "names": ["name", "height"]
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        }
+    }))
+    .expect("Error creating proof request");
+
+    // Prover creates presentation
+    // `attr2_referent` is missing
+    let present_credentials = vec![CredentialToPresent {
+        id: GVT_CRED.to_string(),
+        attributes: vec![
+            PresentAttribute {
+                referent: "attr1_referent".to_string(),
+                form: PresentAttributeForm::RevealedAttribute,
+            },
+            PresentAttribute {
+                referent: "attr3_referent".to_string(),
+                form: PresentAttributeForm::RevealedAttribute,
+            },
+            PresentAttribute {
+                referent: "predicate1_referent".to_string(),
+                form: PresentAttributeForm::Predicate,
+            },
         ],
     }];
 
@@ -727,6 +1349,674 @@ fn anoncreds_demo_proof_does_not_verify_with_wrong_attr_and_predicates(
     assert!(valid.is_err())
 }
 
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_verifies_typed_attribute_encoding(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    const SCHEMA_ID: &str = "schema:identity";
+    const CRED_DEF_ID: &str = "creddef:identity";
+    const CRED: &str = "Identity";
+
+    // Create schema declaring typed date, decimal, boolean and integer attributes
+    let mut demo = Demo::default();
+    let schema = issuer::create_schema_with_attr_encodings(
+        "Identity Schema",
+        "1.0",
+        GVT_ISSUER_ID.try_into().unwrap(),
+        ["name", "birthdate", "balance", "verified", "height"][..].into(),
+        HashMap::from([
+            ("birthdate".to_owned(), AttributeEncoding::Date),
+            (
+                "balance".to_owned(),
+                AttributeEncoding::Decimal { scale: 2 },
+            ),
+            ("verified".to_owned(), AttributeEncoding::Boolean),
+            ("height".to_owned(), AttributeEncoding::Integer),
+        ]),
+    )
+    .expect("Error creating schema");
+    demo.create_cred_def_for_schema(SCHEMA_ID, &schema, CRED_DEF_ID);
+
+    // Issuer creates a credential, encoding the typed attributes as declared by the schema
+    let mut cred_values = MakeCredentialValues::default();
+    cred_values
+        .add_raw("name", "Alex")
+        .expect("Error encoding attribute");
+    for (name, raw) in [
+        ("birthdate", "2000-02-29"),
+        ("balance", "1234.56"),
+        ("verified", "true"),
+        ("height", "175"),
+    ] {
+        cred_values
+            .add_typed(name, raw, schema.attr_encoding(name).unwrap())
+            .expect("Error encoding attribute");
+    }
+    demo.issue_credential(
+        &credential_format,
+        CRED,
+        CRED_DEF_ID,
+        cred_values.into(),
+        None,
+    );
+
+    // Verifier asks for the typed values, and for date of birth predicates relative
+    // to a reference date: at least 18 years old, born before the reference date and
+    // on or after 1900-01-01
+    let today: CalendarDate = "2024-06-15".parse().unwrap();
+    let adult = PredicateInfo::for_minimum_age("birthdate", 18, today, DateEncoding::DaysFromCe)
+        .expect("Error creating predicate");
+    let born = PredicateInfo::for_relative_date(
+        "birthdate",
+        PredicateTypes::LT,
+        today,
+        DateOffset::Days(0),
+        DateEncoding::DaysFromCe,
+    )
+    .expect("Error creating predicate");
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "balance_referent":{"name":"balance"},
+            "verified_referent":{"name":"verified"}
+        },
+        "requested_predicates":{
+            "adult_referent": adult,
+            "born_referent": born,
+            "recent_referent":{
+                "name":"birthdate",
+                "p_type":">=",
+                "p_value":"1900-01-01".parse::<CalendarDate>().unwrap().days_from_ce()
+            },
+            "height_referent":{"name":"height","p_type":">=","p_value":150}
+        }
+    }))
+    .expect("Error creating proof request");
+
+    let present_credentials = [CredentialToPresent::new(
+        CRED,
+        &[
+            ("balance_referent", PresentAttributeForm::RevealedAttribute),
+            ("verified_referent", PresentAttributeForm::RevealedAttribute),
+            ("adult_referent", PresentAttributeForm::Predicate),
+            ("born_referent", PresentAttributeForm::Predicate),
+            ("recent_referent", PresentAttributeForm::Predicate),
+            ("height_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+    let mut presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    assert!(
+        demo.verify_presentation(&presentation, &pres_request)
+            .expect("Error verifying presentation")
+    );
+
+    // The holder compares the same encoded values when matching its credentials against
+    // the request, so a date of birth less than 18 years before the reference date fails
+    let matches = demo.prover_wallet.match_credentials(
+        &credential_format,
+        &pres_request,
+        &demo.schemas(),
+        &demo.cred_defs(),
+    );
+    for referent in [
+        "adult_referent",
+        "born_referent",
+        "recent_referent",
+        "height_referent",
+    ] {
+        assert_eq!(matches[referent], BTreeSet::from([CRED.to_string()]));
+    }
+
+    let minor_request = serde_json::from_value(json!({
+        "nonce": demo.verifier_wallet.generate_nonce(),
+        "name":"pres_req_2",
+        "version":"0.1",
+        "requested_predicates":{
+            "adult_referent": PredicateInfo::for_minimum_age(
+                "birthdate",
+                18,
+                "2018-02-27".parse().unwrap(),
+                DateEncoding::DaysFromCe,
+            )
+            .unwrap()
+        }
+    }))
+    .expect("Error creating proof request");
+    assert!(
+        demo.prover_wallet.match_credentials(
+            &credential_format,
+            &minor_request,
+            &demo.schemas(),
+            &demo.cred_defs(),
+        )["adult_referent"]
+            .is_empty()
+    );
+
+    // A raw value which does not round-trip through the declared encoding is rejected
+    match &mut presentation {
+        Presentations::Legacy(presentation) => {
+            presentation
+                .requested_proof
+                .revealed_attrs
+                .get_mut("balance_referent")
+                .unwrap()
+                .raw = "1234.57".to_string();
+        }
+        Presentations::W3C(presentation) => {
+            presentation.verifiable_credential[0]
+                .credential_subject
+                .0
+                .insert(
+                    "balance".to_string(),
+                    CredentialAttributeValue::String("1234.57".to_string()),
+                );
+        }
+    }
+    assert!(!matches!(
+        demo.verify_presentation(&presentation, &pres_request),
+        Ok(true)
+    ));
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_for_requested_range(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+
+    // Verifier creates a presentation request asking for `18 <= age < 65`
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_ranges":{
+            "range1_referent":{"name":"age","lower":18,"upper":65}
+        }
+    }))
+    .expect("Error creating proof request");
+
+    // Both bounds of the range are proven with the same credential
+    let (lower_referent, upper_referent) = RangeInfo::predicate_referents("range1_referent");
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            (&lower_referent, PresentAttributeForm::Predicate),
+            (&upper_referent, PresentAttributeForm::Predicate),
+        ],
+    )];
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    assert!(
+        demo.verify_presentation(&presentation, &pres_request)
+            .expect("Error verifying presentation")
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_for_presentation_exchange_definition() {
+    const OTHER_GVT_CRED: &str = "OtherGvt";
+
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    for cred in [GVT_CRED, OTHER_GVT_CRED] {
+        demo.issue_credential(
+            &CredentialFormat::W3C,
+            cred,
+            &gvt_cred_def_id,
+            fixtures::credential_values(GVT_CRED).into(),
+            None,
+        );
+    }
+
+    // Verifier converts a presentation definition into a presentation request
+    let definition: PresentationDefinition = serde_json::from_value(json!({
+        "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+        "input_descriptors": [{
+            "id": "gvt",
+            "schema": [{ "uri": GVT_SCHEMA_ID }],
+            "constraints": {
+                "limit_disclosure": "required",
+                "fields": [
+                    { "path": ["$.issuer"], "filter": { "type": "string", "const": GVT_ISSUER_ID } },
+                    { "path": ["$.credentialSubject.name"], "filter": { "type": "string", "const": "Alex" } },
+                    {
+                        "path": ["$.credentialSubject.age"],
+                        "predicate": "required",
+                        "filter": { "type": "integer", "minimum": 18 }
+                    }
+                ]
+            }
+        }]
+    }))
+    .expect("Error parsing presentation definition");
+    let pres_request = w3c::presentation_exchange::create_presentation_request(
+        &definition,
+        demo.verifier_wallet.generate_nonce(),
+    )
+    .expect("Error converting presentation definition");
+
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("gvt", PresentAttributeForm::RevealedAttribute),
+            ("gvt::age", PresentAttributeForm::Predicate),
+        ],
+    )];
+    let presentation = demo.create_presentation(
+        &PresentationFormat::W3C,
+        &pres_request,
+        &present_credentials,
+    );
+    assert!(
+        demo.verify_presentation(&presentation, &pres_request)
+            .expect("Error verifying presentation")
+    );
+
+    // The presentation submission maps the input descriptor to the presented credential
+    let Presentations::W3C(presentation) = &presentation else {
+        unreachable!()
+    };
+    let submission =
+        w3c::presentation_exchange::create_presentation_submission(&definition, presentation)
+            .expect("Error creating presentation submission");
+    assert_eq!(submission.definition_id, definition.id);
+    assert_eq!(submission.descriptor_map.len(), 1);
+    let mapping = &submission.descriptor_map[0];
+    assert_eq!(mapping.id, "gvt");
+    assert_eq!(
+        mapping.path_nested.as_ref().unwrap().path,
+        "$.verifiableCredential[0]"
+    );
+
+    // The holder may prove the referents of a descriptor with different credentials, which
+    // are all mapped to the descriptor
+    let present_credentials = [
+        CredentialToPresent::new(
+            GVT_CRED,
+            &[("gvt", PresentAttributeForm::RevealedAttribute)],
+        ),
+        CredentialToPresent::new(
+            OTHER_GVT_CRED,
+            &[("gvt::age", PresentAttributeForm::Predicate)],
+        ),
+    ];
+    let presentation = demo.create_presentation(
+        &PresentationFormat::W3C,
+        &pres_request,
+        &present_credentials,
+    );
+    assert!(
+        demo.verify_presentation(&presentation, &pres_request)
+            .expect("Error verifying presentation")
+    );
+    let Presentations::W3C(presentation) = &presentation else {
+        unreachable!()
+    };
+    let submission =
+        w3c::presentation_exchange::create_presentation_submission(&definition, presentation)
+            .expect("Error creating presentation submission");
+    let paths = submission
+        .descriptor_map
+        .iter()
+        .map(|mapping| {
+            assert_eq!(mapping.id, "gvt");
+            mapping.path_nested.as_ref().unwrap().path.as_str()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["$.verifiableCredential[0]", "$.verifiableCredential[1]"]
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_for_openid4vp_bound_presentation() {
+    const OPENID_NONCE: &str = "n-0S6_WzA2Mj";
+    const CLIENT_ID: &str = "https://client.example.org/cb";
+
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &CredentialFormat::W3C,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+
+    // Verifier creates a presentation request bound to its OpenID4VP nonce and client_id
+    let pres_request_for = |client_id: &str| -> PresentationRequest {
+        let nonce =
+            w3c::openid4vp::derive_nonce(OPENID_NONCE, client_id).expect("Error deriving nonce");
+        serde_json::from_value(json!({
+            "nonce": nonce,
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{"name":"name"}
+            }
+        }))
+        .expect("Error creating proof request")
+    };
+    let pres_request = pres_request_for(CLIENT_ID);
+
+    // Prover creates presentation and packages it as a vp_token
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[("attr1_referent", PresentAttributeForm::RevealedAttribute)],
+    )];
+    let presentation = demo.create_presentation(
+        &PresentationFormat::W3C,
+        &pres_request,
+        &present_credentials,
+    );
+    let Presentations::W3C(presentation) = &presentation else {
+        unreachable!()
+    };
+    let vp_token = w3c::openid4vp::create_vp_token(presentation, OPENID_NONCE, CLIENT_ID)
+        .expect("Error creating vp_token");
+    assert_eq!(vp_token["proof"]["challenge"], OPENID_NONCE);
+    assert_eq!(vp_token["proof"]["domain"], CLIENT_ID);
+
+    // Verifier verifies presentation against its own OpenID4VP nonce and client_id
+    let verify =
+        |vp_token: &serde_json::Value, pres_request: &PresentationRequest, client_id: &str| {
+            let presentation =
+                w3c::openid4vp::parse_vp_token(vp_token).expect("Error parsing vp_token");
+            w3c::verifier::verify_openid4vp_presentation(
+                &presentation,
+                pres_request,
+                OPENID_NONCE,
+                client_id,
+                &demo.schemas(),
+                &demo.cred_defs(),
+                None,
+                None,
+                None,
+            )
+        };
+    assert!(verify(&vp_token, &pres_request, CLIENT_ID).expect("Error verifying presentation"));
+
+    // A presentation replayed to another relying party is rejected
+    const OTHER_CLIENT_ID: &str = "https://other.example.org/cb";
+    let other_pres_request = pres_request_for(OTHER_CLIENT_ID);
+    assert!(verify(&vp_token, &other_pres_request, OTHER_CLIENT_ID).is_err());
+
+    // as is a presentation claiming to be bound to another relying party
+    let mut relayed_vp_token = vp_token.clone();
+    relayed_vp_token["proof"]["domain"] = json!(OTHER_CLIENT_ID);
+    assert!(verify(&relayed_vp_token, &pres_request, CLIENT_ID).is_err());
+
+    // and a presentation which is not bound to the OpenID4VP request at all
+    let unbound_vp_token = serde_json::to_value(presentation).unwrap();
+    assert!(verify(&unbound_vp_token, &pres_request, CLIENT_ID).is_err());
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_for_verifier_domain_bound_presentation(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    const VERIFIER_DOMAIN: &str = "https://verifier.example.org";
+
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+
+    // Verifier creates a presentation request bound to its domain
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request_for = |verifier_domain: Option<&str>| -> PresentationRequest {
+        serde_json::from_value(json!({
+            "nonce": nonce,
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{"name":"name"}
+            },
+            "requested_predicates":{
+                "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+            },
+            "verifier_domain": verifier_domain
+        }))
+        .expect("Error creating proof request")
+    };
+    let pres_request = pres_request_for(Some(VERIFIER_DOMAIN));
+
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("attr1_referent", PresentAttributeForm::RevealedAttribute),
+            ("predicate1_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+
+    let verify = |pres_request: &PresentationRequest| {
+        demo.verify_presentation(&presentation, pres_request)
+            .unwrap_or(false)
+    };
+    assert!(verify(&pres_request));
+
+    // The presentation is rejected by a verifier using the same nonce with another
+    // domain, or without a domain
+    assert!(!verify(&pres_request_for(Some(
+        "https://other.example.org"
+    ))));
+    assert!(!verify(&pres_request_for(None)));
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_works_for_nonce_registry(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+    let nonce_registry = InMemoryNonceRegistry::new(Duration::from_secs(300));
+
+    // Verifier creates a presentation request with a nonce issued by the registry
+    let pres_request_for = |nonce: Nonce| -> PresentationRequest {
+        serde_json::from_value(json!({
+            "nonce": nonce,
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{"name":"name"}
+            }
+        }))
+        .expect("Error creating proof request")
+    };
+    let pres_request = pres_request_for(nonce_registry.issue().expect("Error issuing nonce"));
+
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[("attr1_referent", PresentAttributeForm::RevealedAttribute)],
+    )];
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+    let unknown_pres_request = pres_request_for(demo.verifier_wallet.generate_nonce());
+    let unknown_presentation = demo.create_presentation(
+        &presentation_format,
+        &unknown_pres_request,
+        &present_credentials,
+    );
+
+    // Verifier verifies presentation, consuming the nonce
+    let (schemas, cred_defs) = (demo.schemas(), demo.cred_defs());
+    let verify = |presentation: &Presentations, pres_request: &PresentationRequest| {
+        demo.verifier_wallet
+            .verify_presentation_with_nonce_registry(
+                presentation,
+                pres_request,
+                &schemas,
+                &cred_defs,
+                &nonce_registry,
+            )
+    };
+
+    // A presentation which fails verification does not use up the nonce
+    assert!(!matches!(
+        verify(&unknown_presentation, &pres_request),
+        Ok(true)
+    ));
+    assert!(verify(&presentation, &pres_request).expect("Error verifying presentation"));
+
+    // A replayed presentation is rejected
+    assert!(verify(&presentation, &pres_request).is_err());
+
+    // as is a presentation for a request with a nonce which was never issued
+    assert!(verify(&unknown_presentation, &unknown_pres_request).is_err());
+}
+
+#[rstest]
+#[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
+#[case(CredentialFormat::W3C, PresentationFormat::W3C)]
+fn anoncreds_demo_detailed_verification_reports_unmet_restriction(
+    #[case] credential_format: CredentialFormat,
+    #[case] presentation_format: PresentationFormat,
+) {
+    let mut demo = Demo::default();
+    let (_, gvt_cred_def_id) = demo.create_cred_def(GVT_CRED, false);
+    demo.issue_credential(
+        &credential_format,
+        GVT_CRED,
+        &gvt_cred_def_id,
+        fixtures::credential_values(GVT_CRED).into(),
+        None,
+    );
+
+    // Verifier creates a presentation request
+    // `attr2_referent` is restricted to a credential definition the prover does not hold
+    let nonce = demo.verifier_wallet.generate_nonce();
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"name",
+                "restrictions": { "cred_def_id": GVT_CRED_DEF_ID }
+            },
+            "attr2_referent":{
+                "name":"sex",
+                "restrictions": { "cred_def_id": "creddef:other" }
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        }
+    }))
+    .expect("Error creating proof request");
+
+    let present_credentials = [CredentialToPresent::new(
+        GVT_CRED,
+        &[
+            ("attr1_referent", PresentAttributeForm::RevealedAttribute),
+            ("attr2_referent", PresentAttributeForm::RevealedAttribute),
+            ("predicate1_referent", PresentAttributeForm::Predicate),
+        ],
+    )];
+    let presentation =
+        demo.create_presentation(&presentation_format, &pres_request, &present_credentials);
+
+    // Verifier verifies presentation and inspects the report
+    let (schemas, cred_defs) = (demo.schemas(), demo.cred_defs());
+    let report = demo
+        .verifier_wallet
+        .verify_presentation_detailed(
+            &presentation,
+            &pres_request,
+            VerifierInputs::new(&schemas, &cred_defs),
+        )
+        .expect("Error verifying presentation");
+
+    assert!(!report.valid);
+    assert_eq!(report.proof, CheckResult::Passed);
+    assert_eq!(report.identifiers.len(), 1);
+
+    let attr1 = &report.requested_attributes["attr1_referent"];
+    assert_eq!(attr1.kind, ReferentKind::Revealed);
+    assert_eq!(attr1.revealed_value, CheckResult::Passed);
+    assert_eq!(attr1.restrictions, CheckResult::Passed);
+    assert_eq!(attr1.sub_proof, CheckResult::Passed);
+    assert!(attr1.is_valid());
+
+    let attr2 = &report.requested_attributes["attr2_referent"];
+    assert_eq!(attr2.revealed_value, CheckResult::Passed);
+    assert!(attr2.restrictions.is_failed());
+    assert!(!attr2.is_valid());
+
+    assert!(report.requested_predicates["predicate1_referent"].is_valid());
+
+    // The report serializes for transport over FFI
+    let json = serde_json::to_value(&report).expect("Error serializing report");
+    assert_eq!(
+        json["requested_attributes"]["attr2_referent"]["restrictions"]["status"],
+        "failed"
+    );
+
+    // Referents missing from the presentation are reported the same way in both formats
+    let mut extended_request = serde_json::to_value(&pres_request).unwrap();
+    extended_request["requested_attributes"]["attr3_referent"] = json!({"name":"address"});
+    extended_request["requested_predicates"]["predicate2_referent"] =
+        json!({"name":"score","p_type":">=","p_value":1});
+    let extended_request: PresentationRequest = serde_json::from_value(extended_request).unwrap();
+    let report = demo
+        .verifier_wallet
+        .verify_presentation_detailed(
+            &presentation,
+            &extended_request,
+            VerifierInputs::new(&schemas, &cred_defs),
+        )
+        .expect("Error verifying presentation");
+    for missing in [
+        &report.requested_attributes["attr3_referent"],
+        &report.requested_predicates["predicate2_referent"],
+    ] {
+        assert_eq!(missing.kind, ReferentKind::Missing);
+        assert!(missing.sub_proof.is_failed());
+        assert_eq!(missing.revealed_value, CheckResult::Skipped);
+        assert_eq!(missing.restrictions, CheckResult::Skipped);
+    }
+}
+
 #[rstest]
 #[case(CredentialFormat::Legacy, PresentationFormat::Legacy)]
 #[case(CredentialFormat::W3C, PresentationFormat::W3C)]
@@ -14,6 +14,9 @@ use anoncreds::data_types::w3c::credential_attributes::{
     CredentialAttributeValue, CredentialSubject,
 };
 use anoncreds::data_types::w3c::presentation::W3CPresentation;
+use anoncreds::nonce_registry::NonceRegistry;
+use anoncreds::prover::{MatchCredential, PresentCredentialsBuilder};
+use anoncreds::resolver::{AnoncredsResolver, InMemoryResolver};
 use anoncreds::types::{
     CredentialRequestMetadata, CredentialRevocationState, CredentialValues,
     RevocationRegistryDefinition, RevocationStatusList,
@@ -29,11 +32,13 @@ use anoncreds::{
         rev_reg_def::RevocationRegistryDefinitionId,
         schema::{Schema, SchemaId},
     },
-    issuer, prover,
+    issuer::{self, RevocationRegistryManager},
+    prover,
     tails::TailsFileWriter,
     types::{
         CredentialDefinitionConfig, CredentialRequest, CredentialRevocationConfig,
-        MakeCredentialValues, PresentCredentials, PresentationRequest, RegistryType, SignatureType,
+        MakeCredentialValues, PresentCredentials, PresentationRequest, RegistryType,
+        SelectedCredentials, SelectionPolicy, SignatureType, VerificationReport,
     },
     verifier, w3c,
 };
@@ -583,6 +588,227 @@ impl<'a> Mock<'a> {
     }
 }
 
+// Ledger and wallets of a demo in which credentials are issued to a single prover
+#[derive(Debug, Default)]
+pub struct Demo<'a> {
+    pub ledger: Ledger<'a>,
+    pub issuer_wallet: IssuerWallet,
+    pub prover_wallet: ProverWallet<'a>,
+    pub verifier_wallet: VerifierWallet,
+}
+
+// Revocation registry a credential is issued in
+pub struct IssuanceRevocation<'a> {
+    pub rev_reg_def_id: &'a str,
+    pub status_list: &'a RevocationStatusList,
+    pub rev_idx: u32,
+}
+
+impl<'a> Demo<'a> {
+    // Publish the `GVT` or `EMP` schema and a credential definition for it,
+    // returning their ids
+    pub fn create_cred_def(&mut self, name: &str, support_revocation: bool) -> (String, String) {
+        let (schema, schema_id) = self.issuer_wallet.create_schema(&mut self.ledger, name);
+        let (_, cred_def_id) =
+            self.issuer_wallet
+                .create_cred_def(&mut self.ledger, &schema, support_revocation);
+        (schema_id, cred_def_id)
+    }
+
+    // Publish a custom schema and a non-revocable credential definition for it
+    pub fn create_cred_def_for_schema(
+        &mut self,
+        schema_id: &str,
+        schema: &Schema,
+        cred_def_id: &str,
+    ) {
+        let (cred_def, cred_def_priv, key_proof) = issuer::create_credential_definition(
+            schema_id.try_into().unwrap(),
+            schema,
+            schema.issuer_id.clone(),
+            "tag",
+            SignatureType::CL,
+            CredentialDefinitionConfig::default(),
+        )
+        .expect("Error creating credential definition");
+        self.ledger.add_schema(schema_id, schema);
+        self.ledger.add_cred_def(cred_def_id, &cred_def);
+        self.issuer_wallet.cred_defs.insert(
+            cred_def_id.to_string(),
+            StoredCredDef {
+                public: cred_def,
+                private: cred_def_priv,
+                key_proof,
+            },
+        );
+    }
+
+    // Create a revocation registry for a credential definition, issuing by default
+    pub fn create_revocation_registry(
+        &mut self,
+        cred_def_id: &str,
+        time: u64,
+    ) -> (String, RevocationRegistryDefinition, RevocationStatusList) {
+        let cred_def = self.cred_def(cred_def_id).try_clone().unwrap();
+        self.issuer_wallet
+            .create_revocation_registry(&mut self.ledger, &cred_def, Some(time), true)
+    }
+
+    pub fn cred_def(&self, cred_def_id: &str) -> &CredentialDefinition {
+        &self
+            .issuer_wallet
+            .cred_defs
+            .get(cred_def_id)
+            .expect("Credential Definition not found")
+            .public
+    }
+
+    // Issue a credential with the encodings declared by its schema, and store it in the
+    // prover wallet under `id`
+    pub fn issue_credential(
+        &mut self,
+        format: &CredentialFormat,
+        id: &str,
+        cred_def_id: &str,
+        cred_values: CredentialValues,
+        revocation: Option<IssuanceRevocation>,
+    ) -> Credentials {
+        let stored_cred_def = self
+            .issuer_wallet
+            .cred_defs
+            .get(cred_def_id)
+            .expect("Credential Definition not found");
+        let cred_def = &stored_cred_def.public;
+        let schema = &self.ledger.schemas[&cred_def.schema_id];
+
+        let cred_offer = self
+            .issuer_wallet
+            .create_credential_offer(&cred_def.schema_id.0, cred_def_id);
+        let (cred_request, cred_request_metadata) = self
+            .prover_wallet
+            .create_credential_request(cred_def, &cred_offer);
+
+        let stored_rev_def = revocation.as_ref().map(|revocation| {
+            self.issuer_wallet
+                .rev_defs
+                .get(revocation.rev_reg_def_id)
+                .expect("Revocation Registry Definition not found")
+        });
+        let revocation_config =
+            revocation
+                .as_ref()
+                .zip(stored_rev_def)
+                .map(|(revocation, stored_rev_def)| CredentialRevocationConfig {
+                    reg_def: &stored_rev_def.public,
+                    reg_def_private: &stored_rev_def.private,
+                    registry_idx: revocation.rev_idx,
+                    status_list: revocation.status_list,
+                });
+        let rev_reg_def = stored_rev_def.map(|stored_rev_def| &stored_rev_def.public);
+
+        let link_secret = &self.prover_wallet.link_secret;
+        match format {
+            CredentialFormat::Legacy => {
+                let mut credential = issuer::create_credential_with_schema(
+                    cred_def,
+                    &stored_cred_def.private,
+                    schema,
+                    &cred_offer,
+                    &cred_request,
+                    cred_values,
+                    revocation_config,
+                )
+                .expect("Error creating credential");
+                prover::process_credential(
+                    &mut credential,
+                    &cred_request_metadata,
+                    link_secret,
+                    cred_def,
+                    rev_reg_def,
+                )
+                .expect("Error processing credential");
+                self.prover_wallet
+                    .credentials
+                    .insert(id.to_string(), credential.try_clone().unwrap());
+                Credentials::Legacy(credential)
+            }
+            CredentialFormat::W3C => {
+                let mut credential = w3c::issuer::create_credential_with_schema(
+                    cred_def,
+                    &stored_cred_def.private,
+                    schema,
+                    &cred_offer,
+                    &cred_request,
+                    CredentialSubject::from(&cred_values),
+                    revocation_config,
+                    None,
+                )
+                .expect("Error creating credential");
+                w3c::prover::process_credential_with_schema(
+                    &mut credential,
+                    &cred_request_metadata,
+                    link_secret,
+                    cred_def,
+                    schema,
+                    rev_reg_def,
+                )
+                .expect("Error processing credential");
+                self.prover_wallet
+                    .w3c_credentials
+                    .insert(id.to_string(), credential.clone());
+                Credentials::W3C(credential)
+            }
+        }
+    }
+
+    pub fn schemas(&self) -> HashMap<SchemaId, Schema> {
+        self.ledger.schemas.clone()
+    }
+
+    pub fn cred_defs(&self) -> HashMap<CredentialDefinitionId, CredentialDefinition> {
+        self.ledger
+            .cred_defs
+            .iter()
+            .map(|(k, v)| (k.clone(), v.try_clone().unwrap()))
+            .collect()
+    }
+
+    // Create a presentation of the stored credentials against all published objects
+    pub fn create_presentation(
+        &self,
+        format: &PresentationFormat,
+        pres_request: &PresentationRequest,
+        present_credentials: &[CredentialToPresent],
+    ) -> Presentations {
+        self.prover_wallet.create_presentation(
+            format,
+            &self.schemas(),
+            &self.cred_defs(),
+            pres_request,
+            present_credentials,
+            None,
+            None,
+        )
+    }
+
+    // Verify a presentation of non-revocable credentials against all published objects
+    pub fn verify_presentation(
+        &self,
+        presentation: &Presentations,
+        pres_request: &PresentationRequest,
+    ) -> Result<bool, TestError> {
+        self.verifier_wallet.verify_presentation(
+            presentation,
+            pres_request,
+            &self.schemas(),
+            &self.cred_defs(),
+            None,
+            None,
+            None,
+        )
+    }
+}
+
 impl<'a> Ledger<'a> {
     pub fn add_schema(&mut self, schema_id: &str, schema: &Schema) {
         let schema_id = SchemaId::new_unchecked(schema_id);
@@ -628,6 +854,25 @@ impl<'a> Ledger<'a> {
         cred_defs
     }
 
+    pub fn resolver(&self) -> InMemoryResolver {
+        let mut resolver = InMemoryResolver::new();
+        for (schema_id, schema) in &self.schemas {
+            resolver.add_schema(schema_id.clone(), schema.clone());
+        }
+        for (cred_def_id, cred_def) in &self.cred_defs {
+            resolver.add_cred_def(cred_def_id.clone(), cred_def.try_clone().unwrap());
+        }
+        for (rev_reg_def_id, rev_reg_def) in &self.rev_reg_defs {
+            resolver.add_rev_reg_def(rev_reg_def_id.clone(), rev_reg_def.clone());
+        }
+        for lists in self.revocation_list.values() {
+            for list in lists.values() {
+                resolver.add_rev_status_list(list.clone()).unwrap();
+            }
+        }
+        resolver
+    }
+
     pub fn resolve_rev_reg_defs(
         &self,
         rev_reg_def_ids: Vec<&str>,
@@ -777,8 +1022,7 @@ impl IssuerWallet {
                     cred_def_private,
                     &cred_offer,
                     &cred_request,
-                    CredentialSubject::try_from(&cred_values)
-                        .expect("Error generating credential attributes"),
+                    CredentialSubject::from(&cred_values),
                     revocation_config,
                     version,
                 )
@@ -790,6 +1034,88 @@ impl IssuerWallet {
         credential
     }
 
+    pub fn create_revocation_registry_manager(
+        &self,
+        ledger: &mut Ledger,
+        cred_def: &CredentialDefinition,
+        time: Option<u64>,
+        issuance_by_default: bool,
+    ) -> RevocationRegistryManager {
+        let manager = RevocationRegistryManager::create(
+            cred_def,
+            fixtures::GVT_CRED_DEF_ID.try_into().unwrap(),
+            fixtures::GVT_REV_REG_DEF_ID.try_into().unwrap(),
+            fixtures::GVT_REV_REG_TAG,
+            fixtures::GVT_REV_MAX_CRED_NUM,
+            issuance_by_default,
+            time,
+            &mut TailsFileWriter::new(None),
+        )
+        .expect("Error creating revocation registry manager");
+        ledger.add_rev_reg_def(fixtures::GVT_REV_REG_DEF_ID, manager.rev_reg_def());
+        manager
+    }
+
+    pub fn create_credential_with_manager(
+        &self,
+        format: &CredentialFormat,
+        manager: &mut RevocationRegistryManager,
+        cred_offer: &CredentialOffer,
+        cred_request: &CredentialRequest,
+        cred_values: CredentialValues,
+        time: Option<u64>,
+    ) -> Credentials {
+        let cred_def_record = &self
+            .cred_defs
+            .get(cred_offer.cred_def_id.0.as_str())
+            .expect("Credential Definition not found");
+        let cred_def_private = &cred_def_record.private;
+        let cred_def = &cred_def_record.public;
+
+        manager
+            .issue_credential_with(cred_def, time, |revocation_config| match format {
+                CredentialFormat::Legacy => issuer::create_credential(
+                    cred_def,
+                    cred_def_private,
+                    cred_offer,
+                    cred_request,
+                    cred_values,
+                    Some(revocation_config),
+                )
+                .map(Credentials::Legacy),
+                CredentialFormat::W3C => w3c::issuer::create_credential(
+                    cred_def,
+                    cred_def_private,
+                    cred_offer,
+                    cred_request,
+                    CredentialSubject::from(&cred_values),
+                    Some(revocation_config),
+                    None,
+                )
+                .map(Credentials::W3C),
+            })
+            .expect("Error creating credential")
+    }
+
+    pub fn revoke_credential_with_manager(
+        &self,
+        manager: &mut RevocationRegistryManager,
+        cred_def: &CredentialDefinition,
+        credential: &Credentials,
+        time: Option<u64>,
+    ) -> RevocationStatusList {
+        match credential {
+            Credentials::Legacy(credential) => {
+                manager.revoke_credential(cred_def, credential, time)
+            }
+            Credentials::W3C(credential) => {
+                manager.revoke_w3c_credential(cred_def, credential, time)
+            }
+        }
+        .expect("Error revoking credential")
+        .clone()
+    }
+
     pub fn update_revocation_status_list(
         &self,
         cred_def: &CredentialDefinition,
@@ -925,13 +1251,138 @@ impl<'a> ProverWallet<'a> {
         }
     }
 
+    // Returns the ids of the credentials matching each attribute and predicate referent
+    pub fn match_credentials(
+        &self,
+        format: &CredentialFormat,
+        pres_request: &PresentationRequest,
+        schemas: &HashMap<SchemaId, Schema>,
+        cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+    ) -> HashMap<String, BTreeSet<String>> {
+        let (ids, matches) = match format {
+            CredentialFormat::Legacy => {
+                let (ids, credentials): (Vec<&String>, Vec<Credential>) = self
+                    .credentials
+                    .iter()
+                    .map(|(id, credential)| (id, credential.try_clone().unwrap()))
+                    .unzip();
+                let matches =
+                    prover::match_credentials(pres_request, &credentials, schemas, cred_defs)
+                        .expect("Error matching credentials");
+                (ids, matches)
+            }
+            CredentialFormat::W3C => {
+                let (ids, credentials): (Vec<&String>, Vec<W3CCredential>) = self
+                    .w3c_credentials
+                    .iter()
+                    .map(|(id, credential)| (id, credential.clone()))
+                    .unzip();
+                let matches =
+                    prover::match_credentials(pres_request, &credentials, schemas, cred_defs)
+                        .expect("Error matching credentials");
+                (ids, matches)
+            }
+        };
+        matches
+            .requested_attributes
+            .iter()
+            .chain(matches.requested_predicates.iter())
+            .map(|(referent, candidates)| {
+                let candidate_ids = candidates
+                    .iter()
+                    .map(|candidate| ids[candidate.index].to_string())
+                    .collect();
+                (referent.to_string(), candidate_ids)
+            })
+            .collect()
+    }
+
+    pub fn select_credentials_to_present<'b, T: RevocableCredential + MatchCredential>(
+        &'b self,
+        credentials: &'b HashMap<String, T>,
+        pres_request: &PresentationRequest,
+        schemas: &HashMap<SchemaId, Schema>,
+        cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+        policy: SelectionPolicy,
+    ) -> SelectedCredentials<'b, T> {
+        let mut builder = PresentCredentialsBuilder::default();
+        builder.set_policy(policy);
+        for credential in credentials.values() {
+            let rev_state = credential
+                .rev_reg_id()
+                .and_then(|id| self.rev_states.get(&id.0))
+                .and_then(|(rev_state, _)| rev_state.as_ref());
+            builder.add_credential(credential, rev_state);
+        }
+        builder
+            .build(pres_request, schemas, cred_defs)
+            .expect("Error selecting credentials")
+    }
+
+    pub fn create_presentation_with_selection(
+        &self,
+        format: &PresentationFormat,
+        schemas: &HashMap<SchemaId, Schema>,
+        cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+        pres_request: &PresentationRequest,
+        policy: SelectionPolicy,
+        self_attested_values: &HashMap<String, String>,
+    ) -> Presentations {
+        match format {
+            PresentationFormat::Legacy => {
+                let selected = self.select_credentials_to_present(
+                    &self.credentials,
+                    pres_request,
+                    schemas,
+                    cred_defs,
+                    policy,
+                );
+                let self_attested = selected
+                    .self_attested
+                    .iter()
+                    .map(|referent| (referent.clone(), self_attested_values[referent].clone()))
+                    .collect();
+                prover::create_presentation(
+                    pres_request,
+                    selected.present,
+                    Some(self_attested),
+                    &self.link_secret,
+                    schemas,
+                    cred_defs,
+                )
+                .expect("Error creating presentation")
+                .into()
+            }
+            PresentationFormat::W3C => {
+                let selected = self.select_credentials_to_present(
+                    &self.w3c_credentials,
+                    pres_request,
+                    schemas,
+                    cred_defs,
+                    policy,
+                );
+                assert!(selected.self_attested.is_empty());
+                w3c::prover::create_presentation(
+                    pres_request,
+                    selected.present,
+                    &self.link_secret,
+                    schemas,
+                    cred_defs,
+                    None,
+                )
+                .expect("Error creating presentation")
+                .into()
+            }
+        }
+    }
+
     pub fn create_presentation(
         &self,
         format: &PresentationFormat,
         schemas: &HashMap<SchemaId, Schema>,
         cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
         pres_request: &PresentationRequest,
-        present_credentials: &Vec<CredentialToPresent>,
+        present_credentials: &[CredentialToPresent],
         self_attested_credentials: Option<HashMap<String, String>>,
         version: Option<VerifiableCredentialSpecVersion>,
     ) -> Presentations {
@@ -975,6 +1426,52 @@ impl<'a> ProverWallet<'a> {
         }
     }
 
+    pub fn create_presentation_with_resolver(
+        &self,
+        format: &PresentationFormat,
+        resolver: &dyn AnoncredsResolver,
+        pres_request: &PresentationRequest,
+        present_credentials: &[CredentialToPresent],
+        version: Option<VerifiableCredentialSpecVersion>,
+    ) -> Presentations {
+        match format {
+            PresentationFormat::Legacy => {
+                let mut present = PresentCredentials::default();
+                self.prepare_credentials_to_present(
+                    &mut present,
+                    &self.credentials,
+                    present_credentials,
+                );
+                let presentation = prover::create_presentation_with_resolver(
+                    pres_request,
+                    present,
+                    None,
+                    &self.link_secret,
+                    resolver,
+                )
+                .expect("Error creating presentation");
+                presentation.into()
+            }
+            PresentationFormat::W3C => {
+                let mut present = PresentCredentials::default();
+                self.prepare_credentials_to_present(
+                    &mut present,
+                    &self.w3c_credentials,
+                    present_credentials,
+                );
+                let presentation = w3c::prover::create_presentation_with_resolver(
+                    pres_request,
+                    present,
+                    &self.link_secret,
+                    resolver,
+                    version,
+                )
+                .expect("Error creating presentation");
+                presentation.into()
+            }
+        }
+    }
+
     pub fn convert_credential(
         &mut self,
         id: &str,
@@ -1045,6 +1542,101 @@ impl VerifierWallet {
         }
     }
 
+    pub fn verify_presentation_with_nonce_registry(
+        &self,
+        presentation: &Presentations,
+        pres_req: &PresentationRequest,
+        schemas: &HashMap<SchemaId, Schema>,
+        cred_defs: &HashMap<CredentialDefinitionId, CredentialDefinition>,
+        nonce_registry: &dyn NonceRegistry,
+    ) -> Result<bool, TestError> {
+        match presentation {
+            Presentations::Legacy(presentation) => {
+                verifier::verify_presentation_with_nonce_registry(
+                    presentation,
+                    pres_req,
+                    schemas,
+                    cred_defs,
+                    None,
+                    None,
+                    None,
+                    nonce_registry,
+                )
+                .map_err(|e| TestError(e.to_string()))
+            }
+            Presentations::W3C(presentation) => {
+                w3c::verifier::verify_presentation_with_nonce_registry(
+                    presentation,
+                    pres_req,
+                    schemas,
+                    cred_defs,
+                    None,
+                    None,
+                    None,
+                    nonce_registry,
+                )
+                .map_err(|e| TestError(e.to_string()))
+            }
+        }
+    }
+
+    pub fn verify_presentation_with_resolver(
+        &self,
+        presentation: &Presentations,
+        pres_req: &PresentationRequest,
+        resolver: &dyn AnoncredsResolver,
+        nonrevoke_interval_override: Option<
+            &HashMap<RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+        >,
+    ) -> Result<bool, TestError> {
+        match presentation {
+            Presentations::Legacy(presentation) => verifier::verify_presentation_with_resolver(
+                presentation,
+                pres_req,
+                resolver,
+                nonrevoke_interval_override,
+            )
+            .map_err(|e| TestError(e.to_string())),
+            Presentations::W3C(presentation) => w3c::verifier::verify_presentation_with_resolver(
+                presentation,
+                pres_req,
+                resolver,
+                nonrevoke_interval_override,
+            )
+            .map_err(|e| TestError(e.to_string())),
+        }
+    }
+
+    pub fn verify_presentation_detailed(
+        &self,
+        presentation: &Presentations,
+        pres_req: &PresentationRequest,
+        inputs: VerifierInputs<'_>,
+    ) -> Result<VerificationReport, TestError> {
+        match presentation {
+            Presentations::Legacy(presentation) => verifier::verify_presentation_detailed(
+                presentation,
+                pres_req,
+                inputs.schemas,
+                inputs.cred_defs,
+                inputs.rev_reg_defs,
+                inputs.rev_status_lists,
+                inputs.nonrevoke_interval_override,
+            )
+            .map_err(|e| TestError(e.to_string())),
+            Presentations::W3C(presentation) => w3c::verifier::verify_presentation_detailed(
+                presentation,
+                pres_req,
+                inputs.schemas,
+                inputs.cred_defs,
+                inputs.rev_reg_defs,
+                inputs.rev_status_lists,
+                inputs.nonrevoke_interval_override,
+            )
+            .map_err(|e| TestError(e.to_string())),
+        }
+    }
+
     pub fn check_presentation_attribute(
         &self,
         presentation: &Presentations,
@@ -1146,16 +1738,57 @@ impl VerifierWallet {
     }
 }
 
+// Ledger objects a presentation is verified against
+pub struct VerifierInputs<'a> {
+    pub schemas: &'a HashMap<SchemaId, Schema>,
+    pub cred_defs: &'a HashMap<CredentialDefinitionId, CredentialDefinition>,
+    pub rev_reg_defs:
+        Option<&'a HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>>,
+    pub rev_status_lists: Option<Vec<RevocationStatusList>>,
+    pub nonrevoke_interval_override: Option<&'a Override<'a>>,
+}
+
+impl<'a> VerifierInputs<'a> {
+    pub fn new(
+        schemas: &'a HashMap<SchemaId, Schema>,
+        cred_defs: &'a HashMap<CredentialDefinitionId, CredentialDefinition>,
+    ) -> Self {
+        Self {
+            schemas,
+            cred_defs,
+            rev_reg_defs: None,
+            rev_status_lists: None,
+            nonrevoke_interval_override: None,
+        }
+    }
+}
+
 pub struct CredentialToPresent {
     pub id: String,
     pub attributes: Vec<PresentAttribute>,
 }
 
+impl CredentialToPresent {
+    pub fn new(id: &str, attributes: &[(&str, PresentAttributeForm)]) -> Self {
+        Self {
+            id: id.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(referent, form)| PresentAttribute {
+                    referent: referent.to_string(),
+                    form: *form,
+                })
+                .collect(),
+        }
+    }
+}
+
 pub struct PresentAttribute {
     pub referent: String,
     pub form: PresentAttributeForm,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum PresentAttributeForm {
     RevealedAttribute,
     UnrevealedAttribute,
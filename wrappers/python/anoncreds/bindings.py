@@ -815,6 +815,44 @@ def verify_presentation(
     return bool(verify)
 
 
+def verify_presentation_detailed(
+    presentation: ObjectHandle,
+    pres_req: ObjectHandle,
+    schema_ids: Sequence[str],
+    schemas: Sequence[ObjectHandle],
+    cred_def_ids: Sequence[str],
+    cred_defs: Sequence[ObjectHandle],
+    rev_reg_def_ids: Optional[Sequence[str]],
+    rev_reg_defs: Optional[Sequence[ObjectHandle]],
+    rev_status_lists: Optional[Sequence[ObjectHandle]],
+    nonrevoked_interval_overrides: Optional[Sequence[NonrevokedIntervalOverride]],
+) -> dict:
+    report = ByteBuffer()
+
+    nonrevoked_interval_overrides_list = NonrevokedIntervalOverrideList()
+    if nonrevoked_interval_overrides:
+        nonrevoked_interval_overrides_list.count = len(nonrevoked_interval_overrides)
+        nonrevoked_interval_overrides_list.data = (
+            NonrevokedIntervalOverride * len(nonrevoked_interval_overrides)
+        )(*nonrevoked_interval_overrides)
+
+    do_call(
+        "anoncreds_verify_presentation_detailed",
+        presentation,
+        pres_req,
+        FfiObjectHandleList.create(schemas),
+        FfiStrList.create(schema_ids),
+        FfiObjectHandleList.create(cred_defs),
+        FfiStrList.create(cred_def_ids),
+        FfiObjectHandleList.create(rev_reg_defs),
+        FfiStrList.create(rev_reg_def_ids),
+        FfiObjectHandleList.create(rev_status_lists),
+        nonrevoked_interval_overrides_list,
+        byref(report),
+    )
+    return json.loads(bytes(report))
+
+
 def create_revocation_registry_definition(
     cred_def: ObjectHandle,
     cred_def_id: str,
@@ -823,21 +861,37 @@ def create_revocation_registry_definition(
     rev_reg_type: str,
     max_cred_num: int,
     tails_dir_path: Optional[str],
+    tails_location_template: Optional[str] = None,
 ) -> Tuple[ObjectHandle, ObjectHandle]:
     reg_def = ObjectHandle()
     reg_def_private = ObjectHandle()
-    do_call(
-        "anoncreds_create_revocation_registry_def",
-        cred_def,
-        encode_str(cred_def_id),
-        encode_str(issuer_id),
-        encode_str(tag),
-        encode_str(rev_reg_type),
-        c_int64(max_cred_num),
-        encode_str(tails_dir_path),
-        byref(reg_def),
-        byref(reg_def_private),
-    )
+    if tails_location_template is None:
+        do_call(
+            "anoncreds_create_revocation_registry_def",
+            cred_def,
+            encode_str(cred_def_id),
+            encode_str(issuer_id),
+            encode_str(tag),
+            encode_str(rev_reg_type),
+            c_int64(max_cred_num),
+            encode_str(tails_dir_path),
+            byref(reg_def),
+            byref(reg_def_private),
+        )
+    else:
+        do_call(
+            "anoncreds_create_revocation_registry_def_with_tails_location",
+            cred_def,
+            encode_str(cred_def_id),
+            encode_str(issuer_id),
+            encode_str(tag),
+            encode_str(rev_reg_type),
+            c_int64(max_cred_num),
+            encode_str(tails_dir_path),
+            encode_str(tails_location_template),
+            byref(reg_def),
+            byref(reg_def_private),
+        )
     return reg_def, reg_def_private
 
 
@@ -1117,6 +1171,44 @@ def verify_w3c_presentation(
     return bool(verify)
 
 
+def verify_w3c_presentation_detailed(
+    presentation: ObjectHandle,
+    pres_req: ObjectHandle,
+    schema_ids: Sequence[str],
+    schemas: Sequence[ObjectHandle],
+    cred_def_ids: Sequence[str],
+    cred_defs: Sequence[ObjectHandle],
+    rev_reg_def_ids: Optional[Sequence[str]],
+    rev_reg_defs: Optional[Sequence[ObjectHandle]],
+    rev_status_lists: Optional[Sequence[ObjectHandle]],
+    nonrevoked_interval_overrides: Optional[Sequence[NonrevokedIntervalOverride]],
+) -> dict:
+    report = ByteBuffer()
+
+    nonrevoked_interval_overrides_list = NonrevokedIntervalOverrideList()
+    if nonrevoked_interval_overrides:
+        nonrevoked_interval_overrides_list.count = len(nonrevoked_interval_overrides)
+        nonrevoked_interval_overrides_list.data = (
+            NonrevokedIntervalOverride * len(nonrevoked_interval_overrides)
+        )(*nonrevoked_interval_overrides)
+
+    do_call(
+        "anoncreds_verify_w3c_presentation_detailed",
+        presentation,
+        pres_req,
+        FfiObjectHandleList.create(schemas),
+        FfiStrList.create(schema_ids),
+        FfiObjectHandleList.create(cred_defs),
+        FfiStrList.create(cred_def_ids),
+        FfiObjectHandleList.create(rev_reg_defs),
+        FfiStrList.create(rev_reg_def_ids),
+        FfiObjectHandleList.create(rev_status_lists),
+        nonrevoked_interval_overrides_list,
+        byref(report),
+    )
+    return json.loads(bytes(report))
+
+
 def w3c_credential_get_integrity_proof_details(
     cred: ObjectHandle,
 ) -> ObjectHandle:
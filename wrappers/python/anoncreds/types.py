@@ -184,6 +184,7 @@ class RevocationRegistryDefinition(bindings.AnoncredsObject):
         max_cred_num: int,
         *,
         tails_dir_path: str = None,
+        tails_location_template: str = None,
     ) -> Tuple["RevocationRegistryDefinition", "RevocationRegistryDefinitionPrivate",]:
         if not isinstance(cred_def, bindings.AnoncredsObject):
             cred_def = CredentialDefinition.load(cred_def)
@@ -198,6 +199,7 @@ class RevocationRegistryDefinition(bindings.AnoncredsObject):
             registry_type,
             max_cred_num,
             tails_dir_path,
+            tails_location_template,
         )
         return (
             RevocationRegistryDefinition(reg_def),
@@ -586,6 +588,79 @@ class PresentCredentials:
             entry[1].add(reft)
 
 
+def _verification_args(
+    pres_req: Union[str, "PresentationRequest"],
+    schemas: Mapping[str, Union[str, "Schema"]],
+    cred_defs: Mapping[str, Union[str, "CredentialDefinition"]],
+    rev_reg_defs: Optional[Mapping[str, Union[str, "RevocationRegistryDefinition"]]],
+    rev_status_lists: Optional[Sequence[Union[str, "RevocationStatusList"]]],
+    nonrevoked_interval_overrides: Optional[Sequence["NonrevokedIntervalOverride"]],
+) -> tuple:
+    if not isinstance(pres_req, bindings.AnoncredsObject):
+        pres_req = PresentationRequest.load(pres_req)
+
+    schema_ids = list(schemas.keys())
+    schema_handles = [
+        (
+            Schema.load(s) if not isinstance(s, bindings.AnoncredsObject) else s
+        ).handle
+        for s in schemas.values()
+    ]
+
+    cred_def_ids = list(cred_defs.keys())
+    cred_def_handles = [
+        (
+            CredentialDefinition.load(c)
+            if not isinstance(c, bindings.AnoncredsObject)
+            else c
+        ).handle
+        for c in cred_defs.values()
+    ]
+
+    if rev_reg_defs:
+        rev_reg_def_ids = list(rev_reg_defs.keys())
+        rev_reg_def_handles = [
+            (
+                RevocationRegistryDefinition.load(r)
+                if not isinstance(r, bindings.AnoncredsObject)
+                else r
+            ).handle
+            for r in rev_reg_defs.values()
+        ]
+    else:
+        rev_reg_def_ids = None
+        rev_reg_def_handles = None
+
+    if rev_status_lists:
+        rev_status_list_handles = [
+            (
+                RevocationStatusList.load(r)
+                if not isinstance(r, bindings.AnoncredsObject)
+                else r
+            ).handle
+            for r in rev_status_lists
+        ]
+    else:
+        rev_status_list_handles = None
+
+    nonrevoked_interval_overrides_native = []
+    if nonrevoked_interval_overrides:
+        for o in nonrevoked_interval_overrides:
+            nonrevoked_interval_overrides_native.append(o._native)
+
+    return (
+        pres_req.handle,
+        schema_ids,
+        schema_handles,
+        cred_def_ids,
+        cred_def_handles,
+        rev_reg_def_ids,
+        rev_reg_def_handles,
+        rev_status_list_handles,
+        nonrevoked_interval_overrides_native,
+    )
+
+
 class Presentation(bindings.AnoncredsObject):
     @classmethod
     def create(
@@ -666,69 +741,41 @@ class Presentation(bindings.AnoncredsObject):
             Sequence["NonrevokedIntervalOverride"]
         ] = None,
     ) -> bool:
-        if not isinstance(pres_req, bindings.AnoncredsObject):
-            pres_req = PresentationRequest.load(pres_req)
-
-        schema_ids = list(schemas.keys())
-        schema_handles = [
-            (
-                Schema.load(s) if not isinstance(s, bindings.AnoncredsObject) else s
-            ).handle
-            for s in schemas.values()
-        ]
-
-        cred_def_ids = list(cred_defs.keys())
-        cred_def_handles = [
-            (
-                CredentialDefinition.load(c)
-                if not isinstance(c, bindings.AnoncredsObject)
-                else c
-            ).handle
-            for c in cred_defs.values()
-        ]
-
-        if rev_reg_defs:
-            rev_reg_def_ids = list(rev_reg_defs.keys())
-            rev_reg_def_handles = [
-                (
-                    RevocationRegistryDefinition.load(r)
-                    if not isinstance(r, bindings.AnoncredsObject)
-                    else r
-                ).handle
-                for r in rev_reg_defs.values()
-            ]
-        else:
-            rev_reg_def_ids = None
-            rev_reg_def_handles = None
-
-        if rev_status_lists:
-            rev_status_list_handles = [
-                (
-                    RevocationStatusList.load(r)
-                    if not isinstance(r, bindings.AnoncredsObject)
-                    else r
-                ).handle
-                for r in rev_status_lists
-            ]
-        else:
-            rev_status_list_handles = None
-
-        nonrevoked_interval_overrides_native = []
-        if nonrevoked_interval_overrides:
-            for o in nonrevoked_interval_overrides:
-                nonrevoked_interval_overrides_native.append(o._native)
-
         return bindings.verify_presentation(
             self.handle,
-            pres_req.handle,
-            schema_ids,
-            schema_handles,
-            cred_def_ids,
-            cred_def_handles,
-            rev_reg_def_ids,
-            rev_reg_def_handles,
-            rev_status_list_handles,
-            nonrevoked_interval_overrides_native,
+            *_verification_args(
+                pres_req,
+                schemas,
+                cred_defs,
+                rev_reg_defs,
+                rev_status_lists,
+                nonrevoked_interval_overrides,
+            ),
+        )
+
+    def verify_detailed(
+        self,
+        pres_req: Union[str, PresentationRequest],
+        schemas: Mapping[str, Union[str, Schema]],
+        cred_defs: Mapping[str, Union[str, CredentialDefinition]],
+        rev_reg_defs: Optional[
+            Mapping[str, Union[str, "RevocationRegistryDefinition"]]
+        ] = None,
+        rev_status_lists: Optional[Sequence[Union[str, "RevocationStatusList"]]] = None,
+        nonrevoked_interval_overrides: Optional[
+            Sequence["NonrevokedIntervalOverride"]
+        ] = None,
+    ) -> dict:
+        return bindings.verify_presentation_detailed(
+            self.handle,
+            *_verification_args(
+                pres_req,
+                schemas,
+                cred_defs,
+                rev_reg_defs,
+                rev_status_lists,
+                nonrevoked_interval_overrides,
+            ),
         )
 
 
@@ -812,69 +859,41 @@ class W3cPresentation(bindings.AnoncredsObject):
             Sequence["NonrevokedIntervalOverride"]
         ] = None,
     ) -> bool:
-        if not isinstance(pres_req, bindings.AnoncredsObject):
-            pres_req = PresentationRequest.load(pres_req)
-
-        schema_ids = list(schemas.keys())
-        schema_handles = [
-            (
-                Schema.load(s) if not isinstance(s, bindings.AnoncredsObject) else s
-            ).handle
-            for s in schemas.values()
-        ]
-
-        cred_def_ids = list(cred_defs.keys())
-        cred_def_handles = [
-            (
-                CredentialDefinition.load(c)
-                if not isinstance(c, bindings.AnoncredsObject)
-                else c
-            ).handle
-            for c in cred_defs.values()
-        ]
-
-        if rev_reg_defs:
-            rev_reg_def_ids = list(rev_reg_defs.keys())
-            rev_reg_def_handles = [
-                (
-                    RevocationRegistryDefinition.load(r)
-                    if not isinstance(r, bindings.AnoncredsObject)
-                    else r
-                ).handle
-                for r in rev_reg_defs.values()
-            ]
-        else:
-            rev_reg_def_ids = None
-            rev_reg_def_handles = None
-
-        if rev_status_lists:
-            rev_status_list_handles = [
-                (
-                    RevocationStatusList.load(r)
-                    if not isinstance(r, bindings.AnoncredsObject)
-                    else r
-                ).handle
-                for r in rev_status_lists
-            ]
-        else:
-            rev_status_list_handles = None
-
-        nonrevoked_interval_overrides_native = []
-        if nonrevoked_interval_overrides:
-            for o in nonrevoked_interval_overrides:
-                nonrevoked_interval_overrides_native.append(o._native)
-
         return bindings.verify_w3c_presentation(
             self.handle,
-            pres_req.handle,
-            schema_ids,
-            schema_handles,
-            cred_def_ids,
-            cred_def_handles,
-            rev_reg_def_ids,
-            rev_reg_def_handles,
-            rev_status_list_handles,
-            nonrevoked_interval_overrides_native,
+            *_verification_args(
+                pres_req,
+                schemas,
+                cred_defs,
+                rev_reg_defs,
+                rev_status_lists,
+                nonrevoked_interval_overrides,
+            ),
+        )
+
+    def verify_detailed(
+        self,
+        pres_req: Union[str, PresentationRequest],
+        schemas: Mapping[str, Union[str, Schema]],
+        cred_defs: Mapping[str, Union[str, CredentialDefinition]],
+        rev_reg_defs: Optional[
+            Mapping[str, Union[str, "RevocationRegistryDefinition"]]
+        ] = None,
+        rev_status_lists: Optional[Sequence[Union[str, "RevocationStatusList"]]] = None,
+        nonrevoked_interval_overrides: Optional[
+            Sequence["NonrevokedIntervalOverride"]
+        ] = None,
+    ) -> dict:
+        return bindings.verify_w3c_presentation_detailed(
+            self.handle,
+            *_verification_args(
+                pres_req,
+                schemas,
+                cred_defs,
+                rev_reg_defs,
+                rev_status_lists,
+                nonrevoked_interval_overrides,
+            ),
         )
 
 